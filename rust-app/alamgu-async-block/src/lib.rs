@@ -11,7 +11,7 @@
 //! # Example
 //!
 //!
-//! ```
+//! ```ignore
 //! use core::future::Future;
 //! use core::task::*;
 //! use ledger_async_block::*;
@@ -65,7 +65,7 @@
 //!
 //! ```
 //!
-#![no_std]
+#![cfg_attr(target_family = "bolos", no_std)]
 #![feature(type_alias_impl_trait)]
 #![cfg_attr(not(version("1.63")), feature(cell_filter_map))]
 #![feature(cfg_version)]
@@ -82,20 +82,35 @@
 use arrayvec::ArrayVec;
 use core::future::Future;
 use core::pin::Pin;
+#[cfg(target_family = "bolos")]
 use ledger_device_sdk::io;
+#[cfg(target_family = "bolos")]
 use ledger_device_sdk::io::SyscallError;
+#[cfg(target_family = "bolos")]
 use ledger_device_sdk::log::*;
+#[cfg(target_family = "bolos")]
 use ledger_device_sdk::{hash::sha2::Sha2_256, hash::HashInit, Pic};
 use ledger_parser_combinators::async_parser::{
-    reject, Readable, UnwrappableReadable, REJECTED_CODE,
+    reject, take_rejection, Readable, UnwrappableReadable,
 };
 
 use core::cell::{Ref, RefCell, RefMut};
 use core::convert::TryFrom;
 use core::convert::TryInto;
 use core::task::*;
+#[cfg(target_family = "bolos")]
 use ledger_device_sdk::io::Reply; //, BorrowMutError};
 
+#[cfg(not(target_family = "bolos"))]
+mod host;
+#[cfg(not(target_family = "bolos"))]
+use host::*;
+#[cfg(not(target_family = "bolos"))]
+pub use host::{Reply, SyscallError};
+
+#[cfg(not(target_family = "bolos"))]
+pub mod testing;
+
 #[repr(u8)]
 #[derive(Debug, PartialEq)]
 pub enum HostToLedgerCmd {
@@ -135,14 +150,34 @@ pub type SHA256Sum = [u8; HASH_LEN];
 #[derive(Debug)]
 pub struct ChunkNotFound;
 
+/// The APDU exchange the block protocol runs over: [io::Comm] on the device, or
+/// [testing::MockComm] on the host.
+pub trait Transport {
+    /// The data of the last APDU from the host, without the APDU header.
+    fn get_data(&self) -> Result<&[u8], Reply>;
+    /// Append to the response to the last APDU.
+    fn append(&mut self, m: &[u8]);
+}
+
+#[cfg(target_family = "bolos")]
+impl Transport for io::Comm {
+    fn get_data(&self) -> Result<&[u8], Reply> {
+        io::Comm::get_data(self).map_err(Reply::from)
+    }
+
+    fn append(&mut self, m: &[u8]) {
+        io::Comm::append(self, m);
+    }
+}
+
 pub struct HostIOState {
-    pub comm: &'static RefCell<io::Comm>,
+    pub comm: &'static RefCell<dyn Transport>,
     pub requested_block: Option<SHA256Sum>,
     pub sent_command: Option<LedgerToHostCmd>,
 }
 
 impl HostIOState {
-    pub const fn new(comm: &'static RefCell<io::Comm>) -> HostIOState {
+    pub const fn new(comm: &'static RefCell<dyn Transport>) -> HostIOState {
         HostIOState {
             comm: comm,
             requested_block: None,
@@ -225,8 +260,8 @@ impl<'a> Future for WriterFuture<'a> {
 pub struct HostIO(pub &'static RefCell<HostIOState>);
 
 impl HostIO {
-    /// Grab a mutable reference to the [io::Comm] object, to do some direct stuff with.
-    pub fn get_comm<'a>(self) -> Result<RefMut<'a, io::Comm>, Reply> {
+    /// Grab a mutable reference to the [Transport] object, to do some direct stuff with.
+    pub fn get_comm<'a>(self) -> Result<RefMut<'a, dyn Transport + 'static>, Reply> {
         self.0
             .try_borrow_mut()
             .or(Err(SyscallError::InvalidState))?
@@ -444,6 +479,34 @@ impl Readable for ByteStream {
             buffer.into_inner().unwrap()
         }
     }
+
+    type SkipFut<'a> = impl 'a + core::future::Future<Output = ()>;
+    fn skip<'a: 'b, 'b>(&'a mut self, n: usize) -> Self::SkipFut<'b> {
+        async move {
+            let mut remaining = n;
+            while remaining > 0 {
+                let block = self.get_current_block().await;
+                let consuming = core::cmp::min(self.slice_from_block(&block).len(), remaining);
+                self.consume(&*block, consuming);
+                remaining -= consuming;
+            }
+        }
+    }
+
+    type ReadIntoFut<'a> = impl 'a + core::future::Future<Output = ()>;
+    fn read_into<'a: 'c, 'b: 'c, 'c>(&'a mut self, buf: &'b mut [u8]) -> Self::ReadIntoFut<'c> {
+        async move {
+            let mut filled = 0;
+            while filled < buf.len() {
+                let block = self.get_current_block().await;
+                let avail = self.slice_from_block(&block);
+                let consuming = core::cmp::min(avail.len(), buf.len() - filled);
+                buf[filled..filled + consuming].copy_from_slice(&avail[..consuming]);
+                self.consume(&*block, consuming);
+                filled += consuming;
+            }
+        }
+    }
 }
 
 impl UnwrappableReadable for ByteStream {
@@ -473,7 +536,8 @@ pub fn poll_with_trivial_context<Fut: Future + ?Sized>(
 
 // Hashing required for validating blocks from the host.
 
-fn sha256_hash(data: &[u8]) -> [u8; 32] {
+#[cfg(target_family = "bolos")]
+pub(crate) fn sha256_hash(data: &[u8]) -> [u8; 32] {
     let mut rv = [0; 32];
     let mut hasher = Sha2_256::new();
     let _ = hasher.update(data);
@@ -502,6 +566,9 @@ pub fn poll_apdu_handlers<'a: 'b, 'b, F: Future<Output = ()>, Ins, A: Fn(HostIO,
     }; // Map empty APDUs to STARTs, so we can handle those the same as ones with inputs.
     match command {
         Ok(HostToLedgerCmd::START) => {
+            // Forget any block requested by a command this one replaces, so that its response is
+            // not mistaken for the START.
+            io.0.borrow_mut().requested_block = None;
             call_me_maybe(|| {
                 s.set(Some(apdus(io, ins))); // Initialize the APDU represented.
                 Some(())
@@ -559,12 +626,9 @@ pub fn poll_apdu_handlers<'a: 'b, 'b, F: Future<Output = ()>, Ins, A: Fn(HostIO,
                     return Ok(());
                 } else {
                     error!("APDU handler future neither completed nor sent a command; something is probably missing an .await");
-                    unsafe {
-                        if REJECTED_CODE == 0 {
-                            Err(Reply::from(SyscallError::InvalidState))?
-                        } else {
-                            Err(Reply(REJECTED_CODE))?
-                        }
+                    match take_rejection() {
+                        None => Err(Reply::from(SyscallError::InvalidState))?,
+                        Some(e) => Err(Reply(e.code))?,
                     }
                 }
             }
@@ -582,3 +646,166 @@ pub fn poll_apdu_handlers<'a: 'b, 'b, F: Future<Output = ()>, Ins, A: Fn(HostIO,
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::testing::*;
+    use super::*;
+    use std::vec::Vec;
+
+    const CHUNK_SIZE: usize = 4;
+    const PARAM: &[u8] = b"abcdef";
+
+    // Reads a parameter of two blocks, and sends it back in two pieces
+    async fn read_and_reply(io: HostIO, _ins: ()) {
+        let mut params = match io.get_params::<1>() {
+            Some(p) => p,
+            None => reject(SyscallError::InvalidParameter as u16).await,
+        };
+        let data: [u8; 6] = params[0].read().await;
+        io.result_accumulating(&data[..3]).await;
+        io.result_accumulating(&data[3..]).await;
+        io.result_final(b"end").await;
+    }
+
+    fn cat(parts: &[&[u8]]) -> Vec<u8> {
+        parts.concat()
+    }
+
+    #[test]
+    fn multi_chunk_parameter_and_result() {
+        let (hash, blocks) = make_blocks(PARAM, CHUNK_SIZE);
+        let next = sha256_hash(&blocks[1]);
+        let mut device = MockDevice::new(read_and_reply);
+
+        assert_eq!(
+            device.exchange((), &cat(&[&[0], &hash])),
+            Ok(cat(&[&[2], &hash]))
+        );
+        assert_eq!(
+            device.exchange((), &cat(&[&[1], &blocks[0]])),
+            Ok(cat(&[&[2], &next]))
+        );
+        assert_eq!(
+            device.exchange((), &cat(&[&[1], &blocks[1]])),
+            Ok(cat(&[&[0], b"abc"]))
+        );
+        assert_eq!(device.exchange((), &[4]), Ok(cat(&[&[0], b"def"])));
+        assert_eq!(device.exchange((), &[4]), Ok(cat(&[&[1], b"end"])));
+        assert!(!device.is_running());
+    }
+
+    #[test]
+    fn out_of_order_responses() {
+        let (hash, blocks) = make_blocks(PARAM, CHUNK_SIZE);
+        let start = cat(&[&[0], &hash]);
+        let invalid = Err(Reply::from(SyscallError::InvalidParameter));
+        let mut device = MockDevice::new(read_and_reply);
+
+        // Waiting for a block
+        device.exchange((), &start).unwrap();
+        assert_eq!(device.exchange((), &[4]), invalid);
+        device.exchange((), &start).unwrap();
+        assert_eq!(device.exchange((), &[3]), invalid);
+        // Not the requested block
+        device.exchange((), &start).unwrap();
+        assert_eq!(device.exchange((), &cat(&[&[1], &blocks[1]])), invalid);
+        // Unknown command
+        device.exchange((), &start).unwrap();
+        assert_eq!(device.exchange((), &[5]), invalid);
+
+        // Waiting for the host to take the result
+        device.exchange((), &start).unwrap();
+        device.exchange((), &cat(&[&[1], &blocks[0]])).unwrap();
+        device.exchange((), &cat(&[&[1], &blocks[1]])).unwrap();
+        assert_eq!(device.exchange((), &[2]), invalid);
+        assert!(!device.is_running());
+    }
+
+    #[test]
+    fn get_chunk_response_failure() {
+        let (hash, _) = make_blocks(PARAM, CHUNK_SIZE);
+        let mut device = MockDevice::new(read_and_reply);
+
+        device.exchange((), &cat(&[&[0], &hash])).unwrap();
+        assert_eq!(
+            device.exchange((), &[2]),
+            Err(Reply(SyscallError::InvalidParameter as u16))
+        );
+        assert!(!device.is_running());
+    }
+
+    #[test]
+    fn start_mid_command() {
+        let (hash, blocks) = make_blocks(PARAM, CHUNK_SIZE);
+        let start = cat(&[&[0], &hash]);
+        let mut device = MockDevice::new(read_and_reply);
+
+        // While waiting for a block: the new command asks for the same block again
+        assert_eq!(device.exchange((), &start), Ok(cat(&[&[2], &hash])));
+        assert_eq!(device.exchange((), &start), Ok(cat(&[&[2], &hash])));
+
+        // While sending the result: the new command starts over
+        device.exchange((), &cat(&[&[1], &blocks[0]])).unwrap();
+        device.exchange((), &cat(&[&[1], &blocks[1]])).unwrap();
+        assert_eq!(device.exchange((), &start), Ok(cat(&[&[2], &hash])));
+
+        device.exchange((), &cat(&[&[1], &blocks[0]])).unwrap();
+        assert_eq!(
+            device.exchange((), &cat(&[&[1], &blocks[1]])),
+            Ok(cat(&[&[0], b"abc"]))
+        );
+        assert_eq!(device.exchange((), &[4]), Ok(cat(&[&[0], b"def"])));
+        assert_eq!(device.exchange((), &[4]), Ok(cat(&[&[1], b"end"])));
+        assert!(!device.is_running());
+    }
+
+    // Skips into the second block, and reads up to the end of the third
+    async fn skip_and_read_into(io: HostIO, _ins: ()) {
+        let mut params = match io.get_params::<1>() {
+            Some(p) => p,
+            None => reject(SyscallError::InvalidParameter as u16).await,
+        };
+        let mut data = [0; 5];
+        params[0].skip(5).await;
+        params[0].read_into(&mut data).await;
+        io.result_final(&data).await;
+    }
+
+    #[test]
+    fn skip_and_read_into_across_blocks() {
+        let (hash, blocks) = make_blocks(b"abcdefghij", CHUNK_SIZE);
+        let hashes: Vec<SHA256Sum> = blocks.iter().map(|b| sha256_hash(b)).collect();
+        let mut device = MockDevice::new(skip_and_read_into);
+
+        assert_eq!(
+            device.exchange((), &cat(&[&[0], &hash])),
+            Ok(cat(&[&[2], &hashes[0]]))
+        );
+        assert_eq!(
+            device.exchange((), &cat(&[&[1], &blocks[0]])),
+            Ok(cat(&[&[2], &hashes[1]]))
+        );
+        assert_eq!(
+            device.exchange((), &cat(&[&[1], &blocks[1]])),
+            Ok(cat(&[&[2], &hashes[2]]))
+        );
+        assert_eq!(
+            device.exchange((), &cat(&[&[1], &blocks[2]])),
+            Ok(cat(&[&[1], b"fghij"]))
+        );
+        assert!(!device.is_running());
+    }
+
+    #[test]
+    fn sha256() {
+        assert_eq!(
+            sha256_hash(b"abc"),
+            [
+                0xba, 0x78, 0x16, 0xbf, 0x8f, 0x01, 0xcf, 0xea, 0x41, 0x41, 0x40, 0xde, 0x5d, 0xae,
+                0x22, 0x23, 0xb0, 0x03, 0x61, 0xa3, 0x96, 0x17, 0x7a, 0x9c, 0xb4, 0x10, 0xff, 0x61,
+                0xf2, 0x00, 0x15, 0xad
+            ]
+        );
+    }
+}
@@ -0,0 +1,87 @@
+//! An in-memory APDU pipe, to run the block protocol on the host, without a device or emulator.
+
+use crate::*;
+use core::marker::PhantomData;
+use std::boxed::Box;
+use std::vec::Vec;
+
+/// Stands in for [io::Comm](ledger_device_sdk::io::Comm): holds the data of the last APDU from
+/// the host, and the response to it.
+#[derive(Default, Debug)]
+pub struct MockComm {
+    pub rx: Vec<u8>,
+    pub tx: Vec<u8>,
+}
+
+impl Transport for MockComm {
+    fn get_data(&self) -> Result<&[u8], Reply> {
+        Ok(&self.rx)
+    }
+
+    fn append(&mut self, m: &[u8]) {
+        self.tx.extend_from_slice(m);
+    }
+}
+
+/// Runs the APDU handlers like the main loop of an app, one APDU at a time.
+///
+/// The state is leaked, as [HostIO] needs a `'static` reference to it; this is only meant for tests.
+pub struct MockDevice<Ins, F, A> {
+    pub comm: &'static RefCell<MockComm>,
+    pub io: HostIO,
+    states: Pin<Box<Option<F>>>,
+    apdus: A,
+    _ins: PhantomData<fn(Ins)>,
+}
+
+impl<Ins, F: Future<Output = ()>, A: Fn(HostIO, Ins) -> F> MockDevice<Ins, F, A> {
+    pub fn new(apdus: A) -> Self {
+        let comm: &'static RefCell<MockComm> =
+            Box::leak(Box::new(RefCell::new(MockComm::default())));
+        let state: &'static RefCell<HostIOState> =
+            Box::leak(Box::new(RefCell::new(HostIOState::new(comm))));
+        MockDevice {
+            comm,
+            io: HostIO(state),
+            states: Box::pin(None),
+            apdus,
+            _ins: PhantomData,
+        }
+    }
+
+    /// Sends an APDU with this data to the device, and returns its response, or the status word
+    /// it failed with.
+    pub fn exchange(&mut self, ins: Ins, data: &[u8]) -> Result<Vec<u8>, Reply> {
+        {
+            let mut comm = self.comm.borrow_mut();
+            comm.rx = data.to_vec();
+            comm.tx.clear();
+        }
+        match poll_apdu_handlers(self.states.as_mut(), ins, self.io, &self.apdus) {
+            Ok(()) => Ok(core::mem::take(&mut self.comm.borrow_mut().tx)),
+            Err(sw) => {
+                self.states.set(None);
+                Err(sw)
+            }
+        }
+    }
+
+    /// Whether the handler of the last command is still running.
+    pub fn is_running(&self) -> bool {
+        self.states.is_some()
+    }
+}
+
+/// Splits a parameter into blocks linked as the host does, and returns the hash of the first one
+/// with the blocks.
+pub fn make_blocks(data: &[u8], chunk_size: usize) -> (SHA256Sum, Vec<Vec<u8>>) {
+    let mut next = [0; HASH_LEN];
+    let mut blocks = Vec::new();
+    for chunk in data.chunks(chunk_size).rev() {
+        let mut block = next.to_vec();
+        block.extend_from_slice(chunk);
+        next = sha256_hash(&block);
+        blocks.insert(0, block);
+    }
+    (next, blocks)
+}
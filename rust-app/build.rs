@@ -1,15 +1,95 @@
 use image::{ImageFormat, ImageReader, Pixel};
+use std::collections::BTreeSet;
 use std::env;
+use std::fmt::Write;
 
 fn main() {
     println!("cargo:rerun-if-changed=script.ld");
     println!("cargo:rerun-if-changed=sui-small.gif");
     println!("cargo:rerun-if-changed=mask_14x14.gif");
+    println!("cargo:rerun-if-changed=known_coins.json");
 
     if let Ok(path) = env::var("NEWLIB_LIB_PATH") {
         println!("cargo:rustc-link-search={path}");
     }
 
+    known_coins();
+    home_glyph();
+}
+
+/// Generates the KNOWN_COINS table of ui/common.rs from known_coins.json, sorted by coin type
+/// for binary search.
+///
+/// The lengths of the strings are checked against the constants of the app when it is compiled.
+fn known_coins() {
+    let json = std::fs::read_to_string("known_coins.json").unwrap();
+    let entries: serde_json::Value =
+        serde_json::from_str(&json).expect("known_coins.json is not valid JSON");
+
+    let mut coins = Vec::new();
+    for (i, entry) in entries
+        .as_array()
+        .expect("known_coins.json is not a list")
+        .iter()
+        .enumerate()
+    {
+        let field = |name: &str| -> &str {
+            entry[name]
+                .as_str()
+                .filter(|s| !s.is_empty() && s.is_ascii())
+                .unwrap_or_else(|| panic!("known coin {i}: {name} is not a non-empty ASCII string"))
+        };
+        let hex_id = field("coin_id");
+        let hex_id = hex_id.strip_prefix("0x").unwrap_or(hex_id);
+        if hex_id.len() != 64 || !hex_id.bytes().all(|c| c.is_ascii_hexdigit()) {
+            panic!("known coin {i}: coin_id is not 32 bytes of hex");
+        }
+        let coin_id: Vec<u8> = (0..32)
+            .map(|b| u8::from_str_radix(&hex_id[2 * b..2 * b + 2], 16).unwrap())
+            .collect();
+        // Amounts are u64, which have up to 20 digits
+        let decimals = entry["decimals"]
+            .as_u64()
+            .filter(|d| *d <= 19)
+            .unwrap_or_else(|| panic!("known coin {i}: decimals is not a number up to 19"));
+        coins.push((
+            coin_id,
+            field("module").to_string(),
+            field("struct").to_string(),
+            decimals,
+            field("ticker").to_string(),
+        ));
+    }
+
+    coins.sort();
+    for pair in coins.windows(2) {
+        if pair[0].0 == pair[1].0 && pair[0].1 == pair[1].1 && pair[0].2 == pair[1].2 {
+            panic!("known coin {}::{} is listed twice", pair[0].1, pair[0].2);
+        }
+    }
+    // So that a token can not be displayed as another one
+    let tickers: BTreeSet<_> = coins.iter().map(|c| c.4.as_str()).collect();
+    if tickers.len() != coins.len() || tickers.contains("SUI") {
+        panic!("tickers of known coins are not unique");
+    }
+
+    let mut out = String::new();
+    writeln!(out, "// Generated by build.rs from known_coins.json").unwrap();
+    writeln!(out, "const KNOWN_COINS: [KnownCoin; {}] = [", coins.len()).unwrap();
+    for (coin_id, module, struct_name, decimals, ticker) in coins {
+        writeln!(
+            out,
+            "    KnownCoin {{ coin_id: {coin_id:?}, module: {module:?}, struct_name: {struct_name:?}, decimals: {decimals}, ticker: {ticker:?} }},"
+        )
+        .unwrap();
+    }
+    writeln!(out, "];").unwrap();
+
+    let out_dir = std::path::PathBuf::from(env::var("OUT_DIR").unwrap());
+    std::fs::write(out_dir.join("known_coins.rs"), out).unwrap();
+}
+
+fn home_glyph() {
     let path = std::path::PathBuf::from("./");
     let reader = ImageReader::open(path.join("sui-small.gif")).unwrap();
     let img = reader.decode().unwrap();
@@ -0,0 +1,22 @@
+// Runs object_parser on an object, as sent in the object list of a sign request
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use ledger_parser_combinators::async_parser::*;
+use ledger_parser_combinators::bcs::testing::*;
+use sui::parser::common::*;
+use sui::parser::object::object_parser;
+use sui_fuzz::*;
+
+fuzz_target!(|data: &[u8]| {
+    let (_, heap_peak) = with_heap_peak(|| {
+        let mut bs = TruncatedReadable::new(data);
+        let _: core::task::Poll<ObjectData> = poll_once(object_parser().parse(&mut bs));
+    });
+    assert!(
+        heap_peak <= HEAP_LIMIT,
+        "heap usage {} over the limit",
+        heap_peak
+    );
+});
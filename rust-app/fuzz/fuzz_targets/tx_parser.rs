@@ -0,0 +1,44 @@
+// Runs tx_parser on a transaction and its objects, see split_tx_input for the input format
+#![no_main]
+
+use core::cell::Cell;
+use core::task::Poll;
+use libfuzzer_sys::fuzz_target;
+
+use ledger_parser_combinators::async_parser::*;
+use ledger_parser_combinators::bcs::testing::*;
+use sui::parser::move_call::MoveCallDescriptor;
+use sui::parser::tx::*;
+use sui_fuzz::*;
+
+fuzz_target!(|data: &[u8]| {
+    let Some(input) = split_tx_input(data) else {
+        return;
+    };
+    let objects_amount = Cell::new(0);
+    let object_data = FuzzObjectData {
+        objects: &input.objects,
+        total_amount: &objects_amount,
+    };
+
+    let (result, heap_peak) = with_heap_peak(|| {
+        let mut bs = TruncatedReadable::new(input.tx);
+        poll_once(tx_parser(object_data, None::<&MoveCallDescriptor>).parse(&mut bs))
+    });
+    assert!(
+        heap_peak <= HEAP_LIMIT,
+        "heap usage {} over the limit",
+        heap_peak
+    );
+
+    if let Poll::Ready((known_tx, _)) = result {
+        // Every coin shown comes from an object, or is split from one
+        let split_amount =
+            split_coins_amount(input.tx).expect("the transaction was parsed up to its commands");
+        assert!(
+            known_tx_amount(&known_tx) <= objects_amount.get() + split_amount,
+            "{:?} is more than its inputs",
+            known_tx
+        );
+    }
+});
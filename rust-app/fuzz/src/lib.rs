@@ -0,0 +1,172 @@
+// Shared code of the fuzz targets of the transaction and object parsers
+#![feature(impl_trait_in_assoc_type)]
+
+use core::cell::Cell;
+use core::future::Future;
+use core::task::Poll;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use ledger_parser_combinators::async_parser::*;
+use ledger_parser_combinators::bcs::async_parser::ULEB128;
+use ledger_parser_combinators::bcs::testing::*;
+use ledger_parser_combinators::interp::*;
+use sui::parser::common::*;
+use sui::parser::object::object_parser;
+use sui::parser::tx::*;
+
+// The heap of the app on device (8k), which the parsers must stay within
+// The parsers only bound the maps they keep, using estimate_btree_map_usage
+pub const HEAP_LIMIT: usize = 8192;
+
+// Tracks the live and peak heap usage of the fuzz target
+struct CountingAllocator;
+
+static HEAP_USED: AtomicUsize = AtomicUsize::new(0);
+static HEAP_PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let used = HEAP_USED.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            HEAP_PEAK.fetch_max(used, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        HEAP_USED.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+// Runs f, and returns its result with the peak heap usage while it ran
+pub fn with_heap_peak<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    let base = HEAP_USED.load(Ordering::Relaxed);
+    HEAP_PEAK.store(base, Ordering::Relaxed);
+    let r = f();
+    (r, HEAP_PEAK.load(Ordering::Relaxed) - base)
+}
+
+// Input of the tx_parser target:
+//   object count (1 byte)
+//   for each object: digest (33 bytes), length (u16 LE), object
+//   the transaction, up to the end of the input
+// The digests are given rather than computed, so that the fuzzer can
+// reference an object from the transaction by copying its digest.
+pub struct TxInput<'a> {
+    pub objects: Vec<(ObjectDigest, &'a [u8])>,
+    pub tx: &'a [u8],
+}
+
+pub fn split_tx_input(data: &[u8]) -> Option<TxInput<'_>> {
+    let (&count, mut rest) = data.split_first()?;
+    let mut objects = Vec::new();
+    for _ in 0..count {
+        let (digest, r) = rest.split_first_chunk::<33>()?;
+        let (length, r) = r.split_first_chunk::<2>()?;
+        let length = u16::from_le_bytes(*length) as usize;
+        if r.len() < length {
+            return None;
+        }
+        let (object, r) = r.split_at(length);
+        objects.push((*digest, object));
+        rest = r;
+    }
+    Some(TxInput { objects, tx: rest })
+}
+
+// Provides the objects of the fuzz input, and sums the amounts it returns,
+// so that the amounts shown can be checked against it
+#[derive(Clone)]
+pub struct FuzzObjectData<'o> {
+    pub objects: &'o [(ObjectDigest, &'o [u8])],
+    pub total_amount: &'o Cell<u128>,
+}
+
+impl<'o> HasObjectData for FuzzObjectData<'o> {
+    type State<'c>
+        = impl Future<Output = Option<ObjectData>> + 'c
+    where
+        Self: 'c;
+
+    fn get_object_data<'a: 'c, 'b: 'c, 'c>(&'b self, digest: &'a ObjectDigest) -> Self::State<'c> {
+        async move {
+            let (_, object) = self.objects.iter().find(|(d, _)| d == digest)?;
+            let mut bs = TruncatedReadable::new(*object);
+            let data: ObjectData = object_parser().parse(&mut bs).await;
+            self.total_amount
+                .set(self.total_amount.get() + u128::from(data.1));
+            Some(data)
+        }
+    }
+}
+
+// The sum of the amounts created by the SplitCoins commands of the transaction,
+// counting an amount input each time it is used
+// None if the transaction could not be read up to its commands
+pub fn split_coins_amount(tx: &[u8]) -> Option<u128> {
+    let mut bs = TruncatedReadable::new(tx);
+    let result = poll_once(async {
+        let input = &mut bs;
+        // Intent, TransactionData and TransactionKind variants
+        for _ in 0..5 {
+            <DefaultInterp as AsyncParser<ULEB128, _>>::parse(&DefaultInterp, input).await;
+        }
+
+        let input_count =
+            <DefaultInterp as AsyncParser<ULEB128, _>>::parse(&DefaultInterp, input).await;
+        let mut amounts = Vec::new();
+        for _ in 0..input_count {
+            let arg =
+                <DefaultInterp as AsyncParser<CallArgSchema, _>>::parse(&DefaultInterp, input)
+                    .await;
+            amounts.push(match arg {
+                CallArg::Amount(amount) => Some(amount),
+                _ => None,
+            });
+        }
+
+        let command_count =
+            <DefaultInterp as AsyncParser<ULEB128, _>>::parse(&DefaultInterp, input).await;
+        let mut total: u128 = 0;
+        for _ in 0..command_count {
+            let command =
+                <DefaultInterp as AsyncParser<CommandSchema, _>>::parse(&DefaultInterp, input)
+                    .await;
+            if let Command::SplitCoins(_, args) = command {
+                for arg in args {
+                    if let Argument::Input(ix) = arg {
+                        if let Some(Some(amount)) = amounts.get(ix as usize) {
+                            total += u128::from(*amount);
+                        }
+                    }
+                }
+            }
+        }
+        total
+    });
+    match result {
+        Poll::Ready(total) => Some(total),
+        Poll::Pending => None,
+    }
+}
+
+// The total amount shown to the user for the transaction
+pub fn known_tx_amount(tx: &KnownTx) -> u128 {
+    match tx {
+        KnownTx::TransferTx { total_amount, .. }
+        | KnownTx::StakeTx { total_amount, .. }
+        | KnownTx::UnstakeTx { total_amount, .. }
+        | KnownTx::RestakeTx { total_amount, .. } => u128::from(*total_amount),
+        KnownTx::CoinManagementTx { output_amounts, .. } => {
+            output_amounts.iter().map(|a| u128::from(*a)).sum()
+        }
+        // The fuzz target gives no descriptor, so no call is described
+        KnownTx::MoveCallTx { .. } => 0,
+    }
+}
@@ -0,0 +1,313 @@
+//! Derives the BCS [`AsyncParser`] of [`DefaultInterp`] for a schema declared as a Rust enum or
+//! struct, instead of writing the `impl` by hand.
+//!
+//! ```ignore
+//! pub struct ArgumentSchema;
+//!
+//! #[derive(BcsAsyncParser)]
+//! #[bcs(schema = ArgumentSchema, reject = SyscallError::NotSupported as u16, log = info)]
+//! pub enum Argument {
+//!     GasCoin,
+//!     Input(#[bcs(schema = U16LE)] u16),
+//!     Result(#[bcs(schema = U16LE)] u16),
+//!     NestedResult(#[bcs(schema = U16LE)] u16, #[bcs(schema = U16LE)] u16),
+//! }
+//! ```
+//!
+//! An enum is parsed as a ULEB128 variant tag followed by the fields of that variant, and a
+//! struct as its fields in order. The parser returns the enum or struct itself. The names of the
+//! variant and of named fields are pushed to the `breadcrumbs` while they are parsed.
+//!
+//! Attributes of the enum or struct:
+//!  - `schema = T`: the schema to implement the parser for; the type itself if not given.
+//!  - `reject = code`: required for enums; unknown variant tags are rejected with this code.
+//!  - `log = m`: logs the schema and variant of each parsed enum with the macro `m!`.
+//!
+//! Attributes of a variant:
+//!  - `tag = n`: the variant tag; by default one more than the previous variant, starting at 0.
+//!  - `reject` or `reject = code`: a known variant that is not supported, rejected with the code
+//!    of the enum or this one.
+//!
+//! Attributes of a field (at most one):
+//!  - `schema = T`: parsed with the `DefaultInterp` of `T`; by default the type of the field.
+//!  - `parser = e`: parsed with the parser `e`, whose output is the type of the field.
+//!
+//! The generated code refers to `ledger_parser_combinators`, and uses `impl Trait` in
+//! associated types, so the crate must enable `impl_trait_in_assoc_type` as for hand-written
+//! parsers.
+//!
+//! [`AsyncParser`]: ../ledger_parser_combinators/async_parser/trait.AsyncParser.html
+//! [`DefaultInterp`]: ../ledger_parser_combinators/interp/struct.DefaultInterp.html
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+
+#[proc_macro_derive(BcsAsyncParser, attributes(bcs))]
+pub fn derive_bcs_async_parser(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    generate_bcs_async_parser(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+// One `key` or `key = value` of a #[bcs(...)] attribute
+enum BcsArg {
+    Schema(syn::Type),
+    Parser(syn::Expr),
+    Reject(Option<syn::Expr>),
+    Tag(syn::LitInt),
+    Log(syn::Path),
+}
+
+impl Parse for BcsArg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key: syn::Ident = input.parse()?;
+        let has_value = input.peek(syn::Token![=]);
+        if has_value {
+            input.parse::<syn::Token![=]>()?;
+        }
+        let arg = match key.to_string().as_str() {
+            "reject" if !has_value => BcsArg::Reject(None),
+            _ if !has_value => return Err(syn::Error::new(key.span(), "expected `= value`")),
+            "schema" => BcsArg::Schema(input.parse()?),
+            "parser" => BcsArg::Parser(input.parse()?),
+            "reject" => BcsArg::Reject(Some(input.parse()?)),
+            "tag" => BcsArg::Tag(input.parse()?),
+            "log" => BcsArg::Log(input.parse()?),
+            _ => return Err(syn::Error::new(key.span(), "unknown bcs attribute")),
+        };
+        Ok(arg)
+    }
+}
+
+fn bcs_args(attrs: &[syn::Attribute]) -> syn::Result<Vec<(proc_macro2::Span, BcsArg)>> {
+    let mut args = Vec::new();
+    for attr in attrs.iter().filter(|a| a.path.is_ident("bcs")) {
+        let parsed =
+            attr.parse_args_with(Punctuated::<BcsArg, syn::Token![,]>::parse_terminated)?;
+        args.extend(parsed.into_iter().map(|arg| (attr.span(), arg)));
+    }
+    Ok(args)
+}
+
+#[derive(Default)]
+struct TypeAttrs {
+    schema: Option<syn::Type>,
+    reject: Option<syn::Expr>,
+    log: Option<syn::Path>,
+}
+
+fn type_attrs(attrs: &[syn::Attribute]) -> syn::Result<TypeAttrs> {
+    let mut rv = TypeAttrs::default();
+    for (span, arg) in bcs_args(attrs)? {
+        match arg {
+            BcsArg::Schema(ty) => rv.schema = Some(ty),
+            BcsArg::Reject(Some(code)) => rv.reject = Some(code),
+            BcsArg::Log(path) => rv.log = Some(path),
+            _ => {
+                return Err(syn::Error::new(
+                    span,
+                    "not an attribute of an enum or struct",
+                ))
+            }
+        }
+    }
+    Ok(rv)
+}
+
+#[derive(Default)]
+struct VariantAttrs {
+    tag: Option<u32>,
+    reject: Option<Option<syn::Expr>>,
+}
+
+fn variant_attrs(attrs: &[syn::Attribute]) -> syn::Result<VariantAttrs> {
+    let mut rv = VariantAttrs::default();
+    for (span, arg) in bcs_args(attrs)? {
+        match arg {
+            BcsArg::Tag(tag) => rv.tag = Some(tag.base10_parse()?),
+            BcsArg::Reject(code) => rv.reject = Some(code),
+            _ => return Err(syn::Error::new(span, "not an attribute of a variant")),
+        }
+    }
+    Ok(rv)
+}
+
+// How one field is parsed
+fn field_parser(field: &syn::Field) -> syn::Result<TokenStream2> {
+    let mut parser = None;
+    for (span, arg) in bcs_args(&field.attrs)? {
+        if parser.is_some() {
+            return Err(syn::Error::new(span, "a field has at most one parser"));
+        }
+        parser = Some(match arg {
+            BcsArg::Schema(schema) => default_interp_parse(&schema),
+            BcsArg::Parser(expr) => quote! {
+                ::ledger_parser_combinators::async_parser::AsyncParser::<_, BS>::parse(&#expr, input).await
+            },
+            _ => return Err(syn::Error::new(span, "not an attribute of a field")),
+        });
+    }
+    Ok(parser.unwrap_or_else(|| default_interp_parse(&field.ty)))
+}
+
+fn default_interp_parse(schema: &syn::Type) -> TokenStream2 {
+    quote! {
+        <::ledger_parser_combinators::interp::DefaultInterp as ::ledger_parser_combinators::async_parser::AsyncParser<#schema, BS>>::parse(
+            &::ledger_parser_combinators::interp::DefaultInterp,
+            input,
+        )
+        .await
+    }
+}
+
+// Parses the fields in order, and builds the value with them
+fn construct(path: TokenStream2, fields: &syn::Fields) -> syn::Result<TokenStream2> {
+    Ok(match fields {
+        syn::Fields::Named(named) => {
+            let names: Vec<_> = named.named.iter().map(|f| &f.ident).collect();
+            let parsers = named
+                .named
+                .iter()
+                .map(|field| {
+                    let parser = field_parser(field)?;
+                    let crumb = breadcrumb(field.ident.as_ref().unwrap());
+                    Ok(quote! {{ #crumb #parser }})
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+            // Bound first, so that the fields are parsed in declaration order
+            quote! {{
+                #(let #names = #parsers;)*
+                #path { #(#names),* }
+            }}
+        }
+        syn::Fields::Unnamed(unnamed) => {
+            let parsers = unnamed
+                .unnamed
+                .iter()
+                .map(field_parser)
+                .collect::<syn::Result<Vec<_>>>()?;
+            quote! { #path(#(#parsers),*) }
+        }
+        syn::Fields::Unit => path,
+    })
+}
+
+// Names the field or variant being parsed in the breadcrumbs, until the end of the block
+fn breadcrumb(ident: &syn::Ident) -> TokenStream2 {
+    let name = ident.to_string();
+    quote! {
+        let _crumb = ::ledger_parser_combinators::breadcrumbs::field(#name);
+    }
+}
+
+fn reject_on(code: &syn::Expr) -> TokenStream2 {
+    quote! {
+        ::ledger_parser_combinators::async_parser::reject_on(
+            core::file!(),
+            core::line!(),
+            #code,
+        )
+        .await
+    }
+}
+
+fn generate_bcs_async_parser(input: syn::DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.span(),
+            "BcsAsyncParser can not be derived for generic types",
+        ));
+    }
+    let attrs = type_attrs(&input.attrs)?;
+    let schema = match &attrs.schema {
+        Some(schema) => quote! { #schema },
+        None => quote! { #ident },
+    };
+    let schema_name = match &attrs.schema {
+        Some(schema) => quote! { #schema }.to_string().replace(' ', ""),
+        None => ident.to_string(),
+    };
+
+    let body = match &input.data {
+        syn::Data::Struct(data) => construct(quote! { #ident }, &data.fields)?,
+        syn::Data::Enum(data) => {
+            let reject = attrs.reject.as_ref().ok_or_else(|| {
+                syn::Error::new(
+                    ident.span(),
+                    "an enum needs #[bcs(reject = code)] for unknown variants",
+                )
+            })?;
+            let mut next_tag: u32 = 0;
+            let mut arms = Vec::new();
+            for variant in data.variants.iter() {
+                let variant_attrs = variant_attrs(&variant.attrs)?;
+                let tag = variant_attrs.tag.unwrap_or(next_tag);
+                next_tag = tag.wrapping_add(1);
+                let tag = syn::LitInt::new(&tag.to_string(), variant.span());
+                let var_ident = &variant.ident;
+                let log = attrs.log.as_ref().map(|log| {
+                    let msg = format!("{}: {}", schema_name, var_ident);
+                    quote! { #log!(#msg); }
+                });
+                let value = match &variant_attrs.reject {
+                    Some(code) => reject_on(code.as_ref().unwrap_or(reject)),
+                    None => construct(quote! { #ident::#var_ident }, &variant.fields)?,
+                };
+                let crumb = breadcrumb(var_ident);
+                arms.push(quote! {
+                    #tag => {
+                        #log
+                        #crumb
+                        #value
+                    }
+                });
+            }
+            let reject_unknown = reject_on(reject);
+            quote! {
+                let enum_variant = <::ledger_parser_combinators::interp::DefaultInterp as ::ledger_parser_combinators::async_parser::AsyncParser<::ledger_parser_combinators::bcs::async_parser::ULEB128, BS>>::parse(
+                    &::ledger_parser_combinators::interp::DefaultInterp,
+                    input,
+                )
+                .await;
+                match enum_variant {
+                    #(#arms)*
+                    _ => #reject_unknown,
+                }
+            }
+        }
+        syn::Data::Union(_) => {
+            return Err(syn::Error::new(
+                ident.span(),
+                "BcsAsyncParser can not be derived for unions",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        impl ::ledger_parser_combinators::async_parser::HasOutput<#schema>
+            for ::ledger_parser_combinators::interp::DefaultInterp
+        {
+            type Output = #ident;
+        }
+
+        impl<BS: Clone + ::ledger_parser_combinators::async_parser::Readable>
+            ::ledger_parser_combinators::async_parser::AsyncParser<#schema, BS>
+            for ::ledger_parser_combinators::interp::DefaultInterp
+        {
+            type State<'c>
+                = impl core::future::Future<Output = Self::Output> + 'c
+            where
+                BS: 'c;
+            #[allow(unused_variables)]
+            fn parse<'a: 'c, 'b: 'c, 'c>(&'b self, input: &'a mut BS) -> Self::State<'c> {
+                async move { #body }
+            }
+        }
+    })
+}
@@ -7,8 +7,10 @@
 //! behavior, we _can_ manually construct a state type and directly implement Future for that
 //! state.
 //!
+use crate::breadcrumbs::{self, Path};
 use crate::endianness::{Convert, Endianness};
 use crate::interp::{Action, DefaultInterp, DropInterp, ObserveBytes, SubInterp};
+use crate::numbers::Uint256;
 use crate::schema::*;
 
 use arrayvec::ArrayVec;
@@ -45,45 +47,147 @@ where
         T: 'c;
 }
 
+/// Code of the last rejection, 0 if none; kept for the code reading it directly, see
+/// [last_rejection] for the whole [ParseError].
+#[cfg_attr(not(target_family = "bolos"), thread_local)]
 pub static mut REJECTED_CODE: u16 = 0;
 
+// The parsers run on a single thread on the device; on the host, each test has its own.
+#[cfg_attr(not(target_family = "bolos"), thread_local)]
+static mut REJECTION: Option<ParseError> = None;
+
 // This is an unused error code, see SyscallError in SDK
 pub const PARSE_ERROR_CODE: u16 = 0x6850;
 
-/// Reject the parse.
-pub fn reject<T>(error_code: u16) -> impl Future<Output = T> {
-    // Do some out-of-band rejection thingie
+// Rejects input that is not in the canonical encoding of its format, e.g. non-minimal ULEB128 in
+// BCS, as it could be read differently by the device and by the chain. Also unused by the SDK.
+pub const NON_CANONICAL_ERROR_CODE: u16 = 0x6851;
+
+/// Why a parser rejected its input.
+///
+/// The location is only kept in debug builds, to not add the file names to release builds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// Status word to reply with
+    pub code: u16,
+    /// The schema that was being parsed, set by the innermost [Fallible] around the rejection.
+    pub context: Option<&'static str>,
+    /// The fields being parsed at the rejection, with the `breadcrumbs` feature.
+    pub path: Path,
+    #[cfg(debug_assertions)]
+    pub file: &'static str,
+    #[cfg(debug_assertions)]
+    pub line: u32,
+}
+
+impl ParseError {
+    pub fn new(code: u16) -> Self {
+        ParseError::at("", 0, code)
+    }
+
+    #[allow(unused_variables)]
+    pub fn at(file: &'static str, line: u32, code: u16) -> Self {
+        ParseError {
+            code,
+            context: None,
+            path: Path::EMPTY,
+            #[cfg(debug_assertions)]
+            file,
+            #[cfg(debug_assertions)]
+            line,
+        }
+    }
+
+    /// Sets the schema context, unless a more precise one is already set.
+    pub fn with_context(self, context: &'static str) -> Self {
+        ParseError {
+            context: self.context.or(Some(context)),
+            ..self
+        }
+    }
+}
+
+/// Reject the parse with this error.
+///
+/// The returned future never completes; the error is reported out-of-band to the nearest
+/// [TryFuture], [TryResultFuture] or [Fallible] around it, or else to the APDU handler. The
+/// current [breadcrumbs] are recorded in the error, unless it already has a path.
+pub fn reject_with<T>(mut error: ParseError) -> impl Future<Output = T> {
+    if error.path.is_empty() {
+        error.path = Path::current();
+    }
     unsafe {
-        REJECTED_CODE = error_code;
+        REJECTION = Some(error);
+        REJECTED_CODE = error.code;
     }
     core::future::pending()
 }
 
-#[allow(unused_variables)]
+/// Reject the parse.
+pub fn reject<T>(error_code: u16) -> impl Future<Output = T> {
+    reject_with(ParseError::new(error_code))
+}
+
 pub fn reject_on<T>(file: &'static str, line: u32, error_code: u16) -> impl Future<Output = T> {
-    unsafe {
-        REJECTED_CODE = error_code;
+    reject_with(ParseError::at(file, line, error_code))
+}
+
+/// Rejects with the error of this result, if any.
+pub async fn unwrap_or_reject<T>(result: Result<T, ParseError>) -> T {
+    match result {
+        Ok(v) => v,
+        Err(e) => reject_with(e).await,
     }
-    core::future::pending()
 }
 
 pub fn reset_rejected() {
     unsafe {
+        REJECTION = None;
         REJECTED_CODE = 0;
     }
 }
 
+/// The pending rejection, if any.
+pub fn last_rejection() -> Option<ParseError> {
+    unsafe { REJECTION }
+}
+
+/// Takes the pending rejection, so that it is not seen again by the futures around the one
+/// reporting it.
+pub fn take_rejection() -> Option<ParseError> {
+    let rv = last_rejection();
+    reset_rejected();
+    rv
+}
+
+/// Completes with the error of a rejection of the inner future, instead of staying pending.
+#[pin_project]
+pub struct TryResultFuture<F: Future>(#[pin] pub F);
+impl<F: Future> Future for TryResultFuture<F> {
+    type Output = Result<F::Output, ParseError>;
+    fn poll(self: Pin<&mut Self>, ctxd: &mut Context<'_>) -> core::task::Poll<Self::Output> {
+        use core::task::Poll;
+        reset_rejected();
+        match self.project().0.poll(ctxd) {
+            Poll::Pending => match take_rejection() {
+                Some(e) => Poll::Ready(Err(e)),
+                None => Poll::Pending,
+            },
+            Poll::Ready(r) => Poll::Ready(Ok(r)),
+        }
+    }
+}
+
+/// Like [TryResultFuture], but without the error.
 #[pin_project]
 pub struct TryFuture<F: Future>(#[pin] pub F);
 impl<F: Future> Future for TryFuture<F> {
     type Output = Option<F::Output>;
     fn poll(self: Pin<&mut Self>, ctxd: &mut Context<'_>) -> core::task::Poll<Self::Output> {
         use core::task::Poll;
-        unsafe {
-            REJECTED_CODE = 0;
-        }
+        reset_rejected();
         match self.project().0.poll(ctxd) {
-            Poll::Pending if unsafe { REJECTED_CODE != 0 } => Poll::Ready(None),
+            Poll::Pending if take_rejection().is_some() => Poll::Ready(None),
             Poll::Pending => Poll::Pending,
             Poll::Ready(r) => Poll::Ready(Some(r)),
         }
@@ -99,6 +203,20 @@ pub trait Readable {
     /// read N bytes from this Readable; returns a future that will complete with a byte array of
     /// the result.
     fn read<'a: 'b, 'b, const N: usize>(&'a mut self) -> Self::OutFut<'b, N>;
+
+    /// Type alias for the future type of skip
+    type SkipFut<'a>: 'a + Future<Output = ()>
+    where
+        Self: 'a;
+    /// skip the next n bytes of this Readable, without reading them one by one.
+    fn skip<'a: 'b, 'b>(&'a mut self, n: usize) -> Self::SkipFut<'b>;
+
+    /// Type alias for the future type of read_into
+    type ReadIntoFut<'a>: 'a + Future<Output = ()>
+    where
+        Self: 'a;
+    /// read buf.len() bytes from this Readable into buf; for lengths only known at runtime.
+    fn read_into<'a: 'c, 'b: 'c, 'c>(&'a mut self, buf: &'b mut [u8]) -> Self::ReadIntoFut<'c>;
 }
 
 pub trait ReadableLength {
@@ -114,6 +232,16 @@ impl<BS: 'static + Readable> Readable for LengthTrack<BS> {
         self.1 += N;
         self.0.read()
     }
+    type SkipFut<'a> = impl Future<Output = ()> + 'a;
+    fn skip<'a: 'b, 'b>(&'a mut self, n: usize) -> Self::SkipFut<'b> {
+        self.1 += n;
+        self.0.skip(n)
+    }
+    type ReadIntoFut<'a> = impl Future<Output = ()> + 'a;
+    fn read_into<'a: 'c, 'b: 'c, 'c>(&'a mut self, buf: &'b mut [u8]) -> Self::ReadIntoFut<'c> {
+        self.1 += buf.len();
+        self.0.read_into(buf)
+    }
 }
 
 impl<R: Readable> ReadableLength for LengthTrack<R> {
@@ -242,6 +370,8 @@ macro_rules! number_parser {
 number_parser! { U16, 2, u16 }
 number_parser! { U32, 4, u32 }
 number_parser! { U64, 8, u64 }
+number_parser! { U128, 16, u128 }
+number_parser! { U256, 32, Uint256 }
 
 impl<T, const N: usize> HasOutput<Array<T, N>> for DefaultInterp
 where
@@ -296,7 +426,8 @@ where
                 Err(_) => reject(PARSE_ERROR_CODE).await,
             };
             let mut accumulator = ArrayVec::new();
-            for _ in 0..length {
+            for i in 0..length {
+                let _crumb = breadcrumbs::index(i);
                 match accumulator.try_push(self.0.parse(input).await) {
                     Ok(rv) => rv,
                     _ => reject(PARSE_ERROR_CODE).await,
@@ -332,7 +463,8 @@ where
                     Ok(a) => a,
                     Err(_) => reject(PARSE_ERROR_CODE).await,
                 };
-            for _ in 0..length {
+            for i in 0..length {
+                let _crumb = breadcrumbs::index(i);
                 <DefaultInterp as AsyncParser<I, BS>>::parse(&DefaultInterp, input).await;
             }
         }
@@ -407,6 +539,55 @@ impl<
     }
 }
 
+/// Runs the subparser, and returns the error it was rejected with instead of rejecting, so that
+/// the caller can recover from it.
+///
+/// The input is left where the subparser stopped. The error gets the name of the schema as
+/// context, if it has none yet.
+#[derive(Clone)]
+pub struct Fallible<S>(pub S);
+
+impl<T, S: HasOutput<T>> HasOutput<T> for Fallible<S> {
+    type Output = Result<S::Output, ParseError>;
+}
+
+impl<T, S: AsyncParser<T, BS>, BS: Readable> AsyncParser<T, BS> for Fallible<S> {
+    type State<'c>
+        = impl 'c + Future<Output = Self::Output>
+    where
+        BS: 'c,
+        S: 'c;
+    fn parse<'a: 'c, 'b: 'c, 'c>(&'b self, input: &'a mut BS) -> Self::State<'c> {
+        async move {
+            TryResultFuture(self.0.parse(input))
+                .await
+                .map_err(|e| e.with_context(core::any::type_name::<T>()))
+        }
+    }
+}
+
+/// Runs the subparser with the name of a field, variant or schema in the [breadcrumbs].
+#[derive(Clone)]
+pub struct Breadcrumb<S>(pub &'static str, pub S);
+
+impl<T, S: HasOutput<T>> HasOutput<T> for Breadcrumb<S> {
+    type Output = S::Output;
+}
+
+impl<T, S: AsyncParser<T, BS>, BS: Readable> AsyncParser<T, BS> for Breadcrumb<S> {
+    type State<'c>
+        = impl 'c + Future<Output = Self::Output>
+    where
+        BS: 'c,
+        S: 'c;
+    fn parse<'a: 'c, 'b: 'c, 'c>(&'b self, input: &'a mut BS) -> Self::State<'c> {
+        async move {
+            let _crumb = breadcrumbs::field(self.0);
+            self.1.parse(input).await
+        }
+    }
+}
+
 impl<A, R, S, SR> HasOutput<A> for Action<S, fn(&SR, &mut Option<R>) -> Option<()>> {
     type Output = R;
 }
@@ -486,6 +667,33 @@ impl<BS: 'static + Readable, X: 'static, F: Fn(&mut X, &[u8])> Readable
             d
         }
     }
+    type SkipFut<'a>
+        = impl core::future::Future<Output = ()> + 'a
+    where
+        F: 'a;
+    fn skip<'a: 'b, 'b>(&'a mut self, n: usize) -> Self::SkipFut<'b> {
+        // The skipped bytes are still observed
+        async move {
+            let mut buf = [0; 32];
+            let mut remaining = n;
+            while remaining > 0 {
+                let chunk = &mut buf[..core::cmp::min(remaining, 32)];
+                self.0.read_into(chunk).await;
+                self.2(&mut self.1, chunk);
+                remaining -= chunk.len();
+            }
+        }
+    }
+    type ReadIntoFut<'a>
+        = impl core::future::Future<Output = ()> + 'a
+    where
+        F: 'a;
+    fn read_into<'a: 'c, 'b: 'c, 'c>(&'a mut self, buf: &'b mut [u8]) -> Self::ReadIntoFut<'c> {
+        async move {
+            self.0.read_into(buf).await;
+            self.2(&mut self.1, buf);
+        }
+    }
 }
 
 /// ObserveBytes for AsyncParser operates by passing a HashIntercept to the sub-parser.
@@ -682,10 +890,6 @@ impl<Schema, BS: Readable> LengthDelimitedParser<Schema, BS> for DropInterp {
     where
         BS: 'c;
     fn parse<'a: 'c, 'b: 'c, 'c>(&'b self, input: &'a mut BS, length: usize) -> Self::State<'c> {
-        async move {
-            for _ in 0..length {
-                let [_]: [u8; 1] = input.read().await;
-            }
-        }
+        input.skip(length)
     }
 }
@@ -4,6 +4,8 @@ use crate::interp::*;
 
 use core::future::Future;
 
+pub use bcs_derive::BcsAsyncParser;
+
 impl HasOutput<bool> for DefaultInterp {
     type Output = bool;
 }
@@ -19,7 +21,7 @@ impl<BS: Readable> AsyncParser<bool, BS> for DefaultInterp {
             match byte {
                 0 => false,
                 1 => true,
-                _ => reject_on(core::file!(), core::line!(), PARSE_ERROR_CODE).await,
+                _ => reject_on(core::file!(), core::line!(), NON_CANONICAL_ERROR_CODE).await,
             }
         }
     }
@@ -43,7 +45,7 @@ impl<T, S: HasOutput<T> + AsyncParser<T, BS>, BS: Readable> AsyncParser<Option<T
             match byte {
                 0 => None,
                 1 => Some(self.0.parse(input).await),
-                _ => reject_on(core::file!(), core::line!(), PARSE_ERROR_CODE).await,
+                _ => reject_on(core::file!(), core::line!(), NON_CANONICAL_ERROR_CODE).await,
             }
         }
     }
@@ -75,6 +77,9 @@ integer and be in canonical form. For instance, the following values are rejecte
 * 80 80 80 80 80 01 (2^36) is too large.
 * 80 80 80 80 10 (2^33) is too large.
 * 80 00 is not a minimal encoding of 0.
+
+These parsers reject them, and bool and option tags other than 0 and 1, with
+NON_CANONICAL_ERROR_CODE.
 */
 
 pub struct ULEB128;
@@ -101,17 +106,20 @@ impl<BS: Readable> AsyncParser<ULEB128, BS> for DefaultInterp {
                     if shift > 0 && digit == 0 {
                         // We only accept canonical ULEB128 encodings, therefore the
                         // heaviest (and last) base-128 digit must be non-zero.
-                        reject_on(core::file!(), core::line!(), PARSE_ERROR_CODE).await
+                        reject_on(core::file!(), core::line!(), NON_CANONICAL_ERROR_CODE).await
                     }
-                    break;
+                    // Decoded integer must not overflow.
+                    use core::convert::TryFrom;
+                    return match u32::try_from(value) {
+                        Ok(v) => v,
+                        Err(_) => {
+                            reject_on(core::file!(), core::line!(), NON_CANONICAL_ERROR_CODE).await
+                        }
+                    };
                 }
             }
-            // Decoded integer must not overflow.
-            use core::convert::TryFrom;
-            match u32::try_from(value) {
-                Ok(v) => v,
-                Err(_) => reject_on::<u32>(core::file!(), core::line!(), PARSE_ERROR_CODE).await,
-            }
+            // More than 5 bytes can only encode integers larger than u32.
+            reject_on(core::file!(), core::line!(), NON_CANONICAL_ERROR_CODE).await
         }
     }
 }
@@ -121,7 +129,6 @@ pub type Vec<T, const N: usize> = DArray<ULEB128, T, N>;
 #[cfg(test)]
 mod test {
     use super::*;
-    use core::future::Future;
     pub use num_traits::FromPrimitive;
     // trace_macros!(true);
     // trace_macros!(false);
@@ -132,44 +139,9 @@ mod test {
     #[cfg(target_family = "bolos")]
     use testmacro::test_item as test;
 
-    #[derive(Clone)]
-    struct TestReadable<const N: usize>([u8; N], usize);
-    impl<const M: usize> Readable for TestReadable<M> {
-        type OutFut<'a, const N: usize> = impl 'a + Future<Output = [u8; N]>;
-        fn read<'a: 'b, 'b, const N: usize>(&'a mut self) -> Self::OutFut<'b, N> {
-            if self.1 + N <= self.0.len() {
-                let offset = self.1;
-                self.1 += N;
-                use core::convert::TryInto;
-                core::future::ready(self.0[offset..self.1].try_into().unwrap())
-            } else {
-                panic!("Read past end of input");
-            }
-        }
-    }
-
-    impl<const N: usize> ReadableLength for TestReadable<N> {
-        fn index(&self) -> usize {
-            self.1
-        }
-    }
-
+    use crate::bcs::testing::*;
     use core::task::*;
 
-    static RAW_WAKER_VTABLE: RawWakerVTable = RawWakerVTable::new(
-        |a| RawWaker::new(a, &RAW_WAKER_VTABLE),
-        |_| {},
-        |_| {},
-        |_| {},
-    );
-
-    fn poll_once<F: Future>(mut input: F) -> core::task::Poll<F::Output> {
-        let waker = unsafe { Waker::from_raw(RawWaker::new(&(), &RAW_WAKER_VTABLE)) };
-        let mut ctxd = Context::from_waker(&waker);
-        let mut pinned = unsafe { core::pin::Pin::new_unchecked(&mut input) };
-        pinned.as_mut().poll(&mut ctxd)
-    }
-
     #[test]
     fn test_varint() {
         let mut input = TestReadable([0, 0, 0], 0);
@@ -293,4 +265,293 @@ mod test {
             Poll::Ready((9487, (9487, 2_u32.pow(28))))
         );
     }
+    #[test]
+    fn test_skip_and_read_into() {
+        let mut input = TestReadable([1, 2, 3, 4, 5, 6], 0);
+        let mut buf = [0; 3];
+        assert_eq!(poll_once(input.skip(2)), Poll::Ready(()));
+        assert_eq!(poll_once(input.read_into(&mut buf)), Poll::Ready(()));
+        assert_eq!(buf, [3, 4, 5]);
+        assert_eq!(poll_once(input.read::<1>()), Poll::Ready([6]));
+    }
+
+    #[test]
+    fn test_truncated_skip_and_read_into() {
+        let mut input = TruncatedReadable::new([1, 2, 3]);
+        let mut buf = [0; 2];
+        assert_eq!(poll_once(input.read_into(&mut buf)), Poll::Ready(()));
+        assert_eq!(buf, [1, 2]);
+        assert_eq!(poll_once(input.skip(2)), Poll::Pending);
+        assert_eq!(poll_once(input.read_into(&mut buf)), Poll::Pending);
+    }
+
+    // Checks that the parse is rejected for not being canonical BCS
+    fn assert_non_canonical<F: Future>(parse: F) {
+        match poll_once(TryResultFuture(parse)) {
+            Poll::Ready(Err(e)) => assert_eq!(e.code, NON_CANONICAL_ERROR_CODE),
+            _ => panic!("not rejected"),
+        }
+    }
+
+    #[test]
+    fn test_varint_u32_max() {
+        let mut input = TestReadable([0xff, 0xff, 0xff, 0xff, 0x0f], 0);
+        assert_eq!(
+            poll_once(ULEB128.def_parse(&mut input)),
+            Poll::Ready(u32::MAX)
+        );
+    }
+
+    #[test]
+    fn test_varint_non_canonical() {
+        // The rejected values of the BCS specification
+        // 2^36 is too large
+        assert_non_canonical(
+            ULEB128.def_parse(&mut TestReadable([0x80, 0x80, 0x80, 0x80, 0x80, 0x01], 0)),
+        );
+        // 2^33 is too large
+        assert_non_canonical(
+            ULEB128.def_parse(&mut TestReadable([0x80, 0x80, 0x80, 0x80, 0x10], 0)),
+        );
+        // Not a minimal encoding of 0
+        assert_non_canonical(ULEB128.def_parse(&mut TestReadable([0x80, 0x00], 0)));
+        // Not a minimal encoding of 1
+        assert_non_canonical(ULEB128.def_parse(&mut TestReadable([0x81, 0x80, 0x00], 0)));
+        // 2^32 is too large
+        assert_non_canonical(
+            ULEB128.def_parse(&mut TestReadable([0xff, 0xff, 0xff, 0xff, 0x10], 0)),
+        );
+    }
+
+    fn parse_bool(input: &[u8]) -> impl Future<Output = bool> + '_ {
+        async move {
+            let mut input = TestReadable(input, 0);
+            <DefaultInterp as AsyncParser<bool, _>>::parse(&DefaultInterp, &mut input).await
+        }
+    }
+
+    #[test]
+    fn test_bool_tags() {
+        assert_eq!(poll_once(parse_bool(&[0])), Poll::Ready(false));
+        assert_eq!(poll_once(parse_bool(&[1])), Poll::Ready(true));
+        assert_non_canonical(parse_bool(&[2]));
+        assert_non_canonical(parse_bool(&[0xff]));
+    }
+
+    fn parse_option(input: &[u8]) -> impl Future<Output = Option<u8>> + '_ {
+        async move {
+            let mut input = TestReadable(input, 0);
+            <SubInterp<DefaultInterp> as AsyncParser<Option<Byte>, _>>::parse(
+                &SubInterp(DefaultInterp),
+                &mut input,
+            )
+            .await
+        }
+    }
+
+    #[test]
+    fn test_option_tags() {
+        assert_eq!(poll_once(parse_option(&[0])), Poll::Ready(None));
+        assert_eq!(poll_once(parse_option(&[1, 42])), Poll::Ready(Some(42)));
+        assert_non_canonical(parse_option(&[2, 42]));
+        assert_non_canonical(parse_option(&[0x80, 0x00]));
+    }
+
+    #[test]
+    fn test_wide_numbers() {
+        let mut bytes = [0; 32];
+        bytes[0] = 1;
+        bytes[15] = 2;
+        bytes[31] = 3;
+        assert_eq!(
+            poll_once(U128::<{ Endianness::Little }>.def_parse(&mut TestReadable(bytes, 0))),
+            Poll::Ready(1 + (2 << 120))
+        );
+        let value =
+            poll_once(U256::<{ Endianness::Little }>.def_parse(&mut TestReadable(bytes, 0)));
+        assert_eq!(
+            value,
+            Poll::Ready(crate::numbers::Uint256::from_le_bytes(bytes))
+        );
+        let mut big_endian = bytes;
+        big_endian.reverse();
+        assert_eq!(
+            poll_once(U256::<{ Endianness::Big }>.def_parse(&mut TestReadable(big_endian, 0))),
+            value
+        );
+    }
+
+    #[test]
+    fn test_fallible_recovers() {
+        let mut input = TestReadable([2, 5], 0);
+        let parse = async {
+            let flag = <Fallible<DefaultInterp> as AsyncParser<bool, _>>::parse(
+                &Fallible(DefaultInterp),
+                &mut input,
+            )
+            .await;
+            // The next parse goes on after the rejected tag
+            (flag, ULEB128.def_parse(&mut input).await)
+        };
+        let (flag, next) = match poll_once(parse) {
+            Poll::Ready(rv) => rv,
+            Poll::Pending => panic!("rejected"),
+        };
+        let e = flag.unwrap_err();
+        assert_eq!(e.code, NON_CANONICAL_ERROR_CODE);
+        assert_eq!(e.context, Some("bool"));
+        #[cfg(debug_assertions)]
+        assert!(e.file.ends_with("async_parser.rs") && e.line > 0);
+        assert_eq!(next, 5);
+        assert_eq!(last_rejection(), None);
+    }
+
+    #[test]
+    fn test_nested_try_future() {
+        let mut input = TestReadable([2, 5], 0);
+        let parse = async {
+            let inner = TryFuture(<DefaultInterp as AsyncParser<bool, _>>::parse(
+                &DefaultInterp,
+                &mut input,
+            ))
+            .await;
+            assert_eq!(inner, None);
+            // The rejection caught by the inner TryFuture is not seen by the outer one
+            core::future::pending::<()>().await;
+        };
+        assert_eq!(poll_once(TryFuture(parse)), Poll::Pending);
+    }
+
+    #[test]
+    fn test_unwrap_or_reject() {
+        let e = ParseError::at("file.rs", 1, PARSE_ERROR_CODE).with_context("Outer");
+        assert_eq!(
+            poll_once(TryResultFuture(unwrap_or_reject::<u8>(Err(e)))),
+            Poll::Ready(Err(e))
+        );
+        assert_eq!(
+            poll_once(TryResultFuture(unwrap_or_reject(Ok(1)))),
+            Poll::Ready(Ok(1))
+        );
+        // The innermost context is kept
+        assert_eq!(e.with_context("Inner").context, Some("Outer"));
+    }
+
+    #[derive(Debug, PartialEq, BcsAsyncParser)]
+    struct Point {
+        #[bcs(schema = ULEB128)]
+        x: u32,
+        #[bcs(schema = ULEB128)]
+        y: u32,
+    }
+
+    struct ShapeSchema;
+
+    const fn plus_one<BS: Readable>() -> impl AsyncParser<ULEB128, BS, Output = u32> {
+        Action(DefaultInterp, |x: u32| Some(x + 1))
+    }
+
+    #[derive(Debug, PartialEq, BcsAsyncParser)]
+    #[bcs(schema = ShapeSchema, reject = PARSE_ERROR_CODE)]
+    enum Shape {
+        Empty,
+        Dot(Point, bool),
+        #[bcs(tag = 3)]
+        Line {
+            from: Point,
+            to: Point,
+        },
+        #[bcs(reject)]
+        #[allow(dead_code)]
+        Circle,
+        #[bcs(tag = 7)]
+        Scaled(#[bcs(parser = plus_one())] u32),
+    }
+
+    fn parse_shape(input: &[u8]) -> Poll<Option<Shape>> {
+        poll_once(TryFuture(
+            ShapeSchema.def_parse(&mut TestReadable(input, 0)),
+        ))
+    }
+
+    #[test]
+    fn test_derive_struct() {
+        let mut input = TestReadable([0x8f, 0x4a, 1], 0);
+        assert_eq!(
+            poll_once(<DefaultInterp as AsyncParser<Point, _>>::parse(
+                &DefaultInterp,
+                &mut input
+            )),
+            Poll::Ready(Point { x: 9487, y: 1 })
+        );
+    }
+
+    #[test]
+    fn test_derive_enum() {
+        assert_eq!(parse_shape(&[0]), Poll::Ready(Some(Shape::Empty)));
+        assert_eq!(
+            parse_shape(&[1, 2, 3, 1]),
+            Poll::Ready(Some(Shape::Dot(Point { x: 2, y: 3 }, true)))
+        );
+        assert_eq!(
+            parse_shape(&[3, 0, 1, 2, 3]),
+            Poll::Ready(Some(Shape::Line {
+                from: Point { x: 0, y: 1 },
+                to: Point { x: 2, y: 3 }
+            }))
+        );
+        assert_eq!(parse_shape(&[7, 41]), Poll::Ready(Some(Shape::Scaled(42))));
+    }
+
+    #[test]
+    fn test_derive_enum_reject() {
+        // Unknown tags, and known ones marked reject
+        assert_eq!(parse_shape(&[2]), Poll::Ready(None));
+        assert_eq!(parse_shape(&[4]), Poll::Ready(None));
+        assert_eq!(parse_shape(&[8]), Poll::Ready(None));
+        // Fields are checked as usual
+        assert_eq!(parse_shape(&[1, 2, 3, 2]), Poll::Ready(None));
+    }
+
+    #[cfg(feature = "breadcrumbs")]
+    fn rejection_path<F: core::future::Future>(parse: F) -> arrayvec::ArrayString<128> {
+        use core::fmt::Write;
+        let e = match poll_once(TryResultFuture(parse)) {
+            Poll::Ready(Err(e)) => e,
+            _ => panic!("not rejected"),
+        };
+        // The crumbs are popped once the parse is dropped
+        assert!(crate::breadcrumbs::Path::current().is_empty());
+        let mut rv = arrayvec::ArrayString::new();
+        write!(rv, "{}", e.path).unwrap();
+        rv
+    }
+
+    #[cfg(feature = "breadcrumbs")]
+    #[test]
+    fn test_breadcrumbs() {
+        assert_eq!(
+            rejection_path(ShapeSchema.def_parse(&mut TestReadable([3, 0, 1, 2, 0x80, 0x00], 0)))
+                .as_str(),
+            "Line.to.y"
+        );
+        assert_eq!(
+            rejection_path(ShapeSchema.def_parse(&mut TestReadable([4], 0))).as_str(),
+            "Circle"
+        );
+        // Unknown tags are rejected before any variant
+        assert_eq!(
+            rejection_path(ShapeSchema.def_parse(&mut TestReadable([2], 0))).as_str(),
+            ""
+        );
+        let flags = Breadcrumb("flags", SubInterp(DefaultInterp));
+        assert_eq!(
+            rejection_path(<_ as AsyncParser<Vec<bool, 4>, _>>::parse(
+                &flags,
+                &mut TestReadable([3, 1, 0, 2], 0)
+            ))
+            .as_str(),
+            "flags[2]"
+        );
+    }
 }
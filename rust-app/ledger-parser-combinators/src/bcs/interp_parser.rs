@@ -163,46 +163,58 @@ mod test {
     #[test]
     fn test_bool_true() {
         let parser = DefaultInterp;
-        let mut state = parser.init();
+        let mut state = ParserCommon::<bool>::init(&parser);
         let mut dest = None;
-        assert_eq!(parser.parse(&mut state, &[1], &mut dest), Ok(&[][..]));
+        assert_eq!(
+            InterpParser::<bool>::parse(&parser, &mut state, &[1], &mut dest),
+            Ok(&[][..])
+        );
         assert_eq!(dest, Some(true));
     }
 
     #[test]
     fn test_bool_false() {
         let parser = DefaultInterp;
-        let mut state = parser.init();
+        let mut state = ParserCommon::<bool>::init(&parser);
         let mut dest = None;
-        assert_eq!(parser.parse(&mut state, &[0], &mut dest), Ok(&[][..]));
+        assert_eq!(
+            InterpParser::<bool>::parse(&parser, &mut state, &[0], &mut dest),
+            Ok(&[][..])
+        );
         assert_eq!(dest, Some(false));
     }
 
     #[test]
     fn test_bool_invalid() {
         let parser = DefaultInterp;
-        let mut state = parser.init();
+        let mut state = ParserCommon::<bool>::init(&parser);
         let mut dest = None;
-        assert!(matches!(parser.parse(&mut state, &[2], &mut dest), Err(_)));
+        assert!(matches!(
+            InterpParser::<bool>::parse(&parser, &mut state, &[2], &mut dest),
+            Err(_)
+        ));
     }
 
     #[test]
     fn test_uleb128_single_byte() {
         let parser = DefaultInterp;
-        let mut state = parser.init();
+        let mut state = ParserCommon::<ULEB128>::init(&parser);
         let mut dest = None;
-        assert_eq!(parser.parse(&mut state, &[0x01], &mut dest), Ok(&[][..]));
+        assert_eq!(
+            InterpParser::<ULEB128>::parse(&parser, &mut state, &[0x01], &mut dest),
+            Ok(&[][..])
+        );
         assert_eq!(dest, Some(1));
     }
 
     #[test]
     fn test_uleb128_multi_byte() {
         let parser = DefaultInterp;
-        let mut state = parser.init();
+        let mut state = ParserCommon::<ULEB128>::init(&parser);
         let mut dest = None;
         // 9487 = 0x8f 0x4a
         assert_eq!(
-            parser.parse(&mut state, &[0x8f, 0x4a], &mut dest),
+            InterpParser::<ULEB128>::parse(&parser, &mut state, &[0x8f, 0x4a], &mut dest),
             Ok(&[][..])
         );
         assert_eq!(dest, Some(9487));
@@ -211,16 +223,16 @@ mod test {
     #[test]
     fn test_uleb128_chunked() {
         let parser = DefaultInterp;
-        let mut state = parser.init();
+        let mut state = ParserCommon::<ULEB128>::init(&parser);
         let mut dest = None;
 
         // Feed first byte
-        let result = parser.parse(&mut state, &[0x8f], &mut dest);
+        let result = InterpParser::<ULEB128>::parse(&parser, &mut state, &[0x8f], &mut dest);
         assert!(matches!(result, Err((None, _))));
         assert_eq!(dest, None);
 
         // Feed second byte
-        let result = parser.parse(&mut state, &[0x4a], &mut dest);
+        let result = InterpParser::<ULEB128>::parse(&parser, &mut state, &[0x4a], &mut dest);
         assert_eq!(result, Ok(&[][..]));
         assert_eq!(dest, Some(9487));
     }
@@ -228,21 +240,24 @@ mod test {
     #[test]
     fn test_uleb128_2_pow_28() {
         let parser = DefaultInterp;
-        let mut state = parser.init();
+        let mut state = ParserCommon::<ULEB128>::init(&parser);
         let mut dest = None;
         let data = [0x80, 0x80, 0x80, 0x80, 0x01];
-        assert_eq!(parser.parse(&mut state, &data, &mut dest), Ok(&[][..]));
+        assert_eq!(
+            InterpParser::<ULEB128>::parse(&parser, &mut state, &data, &mut dest),
+            Ok(&[][..])
+        );
         assert_eq!(dest, Some(268435456));
     }
 
     #[test]
     fn test_uleb128_non_canonical() {
         let parser = DefaultInterp;
-        let mut state = parser.init();
+        let mut state = ParserCommon::<ULEB128>::init(&parser);
         let mut dest = None;
         // 0x80 0x00 is not canonical encoding of 0
         assert!(matches!(
-            parser.parse(&mut state, &[0x80, 0x00], &mut dest),
+            InterpParser::<ULEB128>::parse(&parser, &mut state, &[0x80, 0x00], &mut dest),
             Err(_)
         ));
     }
@@ -250,10 +265,13 @@ mod test {
     #[test]
     fn test_uleb128_overflow() {
         let parser = DefaultInterp;
-        let mut state = parser.init();
+        let mut state = ParserCommon::<ULEB128>::init(&parser);
         let mut dest = None;
         // Too large for u32
         let data = [0x80, 0x80, 0x80, 0x80, 0x80, 0x01];
-        assert!(matches!(parser.parse(&mut state, &data, &mut dest), Err(_)));
+        assert!(matches!(
+            InterpParser::<ULEB128>::parse(&parser, &mut state, &data, &mut dest),
+            Err(_)
+        ));
     }
 }
@@ -1,2 +1,3 @@
 pub mod async_parser;
 pub mod interp_parser;
+pub mod testing;
@@ -0,0 +1,177 @@
+//! Helpers to run the BCS parsers on in-memory input, for tests of this crate and of the apps using it.
+
+use crate::async_parser::*;
+
+use core::convert::TryInto;
+use core::future::Future;
+use core::task::*;
+
+/// Readable over a byte buffer and the current offset into it.
+///
+/// Reading past the end of the buffer panics.
+#[derive(Clone)]
+pub struct TestReadable<T>(pub T, pub usize);
+
+impl<T: AsRef<[u8]>> TestReadable<T> {
+    pub fn new(input: T) -> Self {
+        TestReadable(input, 0)
+    }
+}
+
+impl<T: AsRef<[u8]>> Readable for TestReadable<T> {
+    type OutFut<'a, const N: usize>
+        = impl 'a + Future<Output = [u8; N]>
+    where
+        Self: 'a;
+    fn read<'a: 'b, 'b, const N: usize>(&'a mut self) -> Self::OutFut<'b, N> {
+        let input = self.0.as_ref();
+        if self.1 + N <= input.len() {
+            let offset = self.1;
+            self.1 += N;
+            core::future::ready(input[offset..self.1].try_into().unwrap())
+        } else {
+            panic!("Read past end of input");
+        }
+    }
+    type SkipFut<'a>
+        = impl 'a + Future<Output = ()>
+    where
+        Self: 'a;
+    fn skip<'a: 'b, 'b>(&'a mut self, n: usize) -> Self::SkipFut<'b> {
+        if self.1 + n <= self.0.as_ref().len() {
+            self.1 += n;
+            core::future::ready(())
+        } else {
+            panic!("Read past end of input");
+        }
+    }
+    type ReadIntoFut<'a>
+        = impl 'a + Future<Output = ()>
+    where
+        Self: 'a;
+    fn read_into<'a: 'c, 'b: 'c, 'c>(&'a mut self, buf: &'b mut [u8]) -> Self::ReadIntoFut<'c> {
+        let input = self.0.as_ref();
+        if self.1 + buf.len() <= input.len() {
+            let offset = self.1;
+            self.1 += buf.len();
+            buf.copy_from_slice(&input[offset..self.1]);
+            core::future::ready(())
+        } else {
+            panic!("Read past end of input");
+        }
+    }
+}
+
+impl<T: AsRef<[u8]>> ReadableLength for TestReadable<T> {
+    fn index(&self) -> usize {
+        self.1
+    }
+}
+
+/// Readable over a byte buffer, for input that may be truncated.
+///
+/// Reading past the end of the buffer never completes, as when the host stops sending data,
+/// so the parser stays `Pending`.
+#[derive(Clone)]
+pub struct TruncatedReadable<T>(pub T, pub usize);
+
+impl<T: AsRef<[u8]>> TruncatedReadable<T> {
+    pub fn new(input: T) -> Self {
+        TruncatedReadable(input, 0)
+    }
+}
+
+impl<T: AsRef<[u8]>> Readable for TruncatedReadable<T> {
+    type OutFut<'a, const N: usize>
+        = impl 'a + Future<Output = [u8; N]>
+    where
+        Self: 'a;
+    fn read<'a: 'b, 'b, const N: usize>(&'a mut self) -> Self::OutFut<'b, N> {
+        let input = self.0.as_ref();
+        let bytes: Option<[u8; N]> = if self.1 + N <= input.len() {
+            let offset = self.1;
+            self.1 += N;
+            input[offset..self.1].try_into().ok()
+        } else {
+            self.1 = input.len();
+            None
+        };
+        async move {
+            match bytes {
+                Some(bytes) => bytes,
+                None => core::future::pending().await,
+            }
+        }
+    }
+    type SkipFut<'a>
+        = impl 'a + Future<Output = ()>
+    where
+        Self: 'a;
+    fn skip<'a: 'b, 'b>(&'a mut self, n: usize) -> Self::SkipFut<'b> {
+        let complete = self.advance(n).is_some();
+        async move {
+            if !complete {
+                core::future::pending::<()>().await
+            }
+        }
+    }
+    type ReadIntoFut<'a>
+        = impl 'a + Future<Output = ()>
+    where
+        Self: 'a;
+    fn read_into<'a: 'c, 'b: 'c, 'c>(&'a mut self, buf: &'b mut [u8]) -> Self::ReadIntoFut<'c> {
+        let complete = match self.advance(buf.len()) {
+            Some(offset) => {
+                buf.copy_from_slice(&self.0.as_ref()[offset..self.1]);
+                true
+            }
+            None => false,
+        };
+        async move {
+            if !complete {
+                core::future::pending::<()>().await
+            }
+        }
+    }
+}
+
+impl<T: AsRef<[u8]>> TruncatedReadable<T> {
+    /// Moves past the next n bytes, returning their offset, or to the end of the input if it is
+    /// shorter.
+    fn advance(&mut self, n: usize) -> Option<usize> {
+        let len = self.0.as_ref().len();
+        if self.1 + n <= len {
+            let offset = self.1;
+            self.1 += n;
+            Some(offset)
+        } else {
+            self.1 = len;
+            None
+        }
+    }
+}
+
+impl<T: AsRef<[u8]>> ReadableLength for TruncatedReadable<T> {
+    fn index(&self) -> usize {
+        self.1
+    }
+}
+
+static RAW_WAKER_VTABLE: RawWakerVTable = RawWakerVTable::new(
+    |a| RawWaker::new(a, &RAW_WAKER_VTABLE),
+    |_| {},
+    |_| {},
+    |_| {},
+);
+
+/// Polls the future once.
+///
+/// With a `TestReadable` the whole input is available, so a parser is either `Ready`,
+/// or `Pending` because it was rejected. With a `TruncatedReadable` it may also be `Pending`
+/// because it needs more input.
+pub fn poll_once<F: Future>(mut input: F) -> Poll<F::Output> {
+    let waker = unsafe { Waker::from_raw(RawWaker::new(&(), &RAW_WAKER_VTABLE)) };
+    let mut ctxd = Context::from_waker(&waker);
+    let mut pinned = unsafe { core::pin::Pin::new_unchecked(&mut input) };
+    pinned.as_mut().poll(&mut ctxd)
+}
@@ -0,0 +1,214 @@
+//! Trail of the fields being parsed, e.g. `TransactionData.V1.Kind.PTB.commands[3].MoveCall`,
+//! so that a rejection can tell which part of the input was not accepted.
+//!
+//! Parsers push a [Crumb] for the field, variant or element they are about to parse, and it is
+//! popped when the crumb is dropped. [reject_with](crate::async_parser::reject_with) records the
+//! trail at that point in the [Path] of the [ParseError](crate::async_parser::ParseError).
+//!
+//! The trail is only kept with the `breadcrumbs` feature, as it costs some RAM and cycles on
+//! every field; without it crumbs do nothing and paths are always empty.
+
+use core::fmt;
+
+/// Whether the trail is kept, i.e. the `breadcrumbs` feature is enabled.
+pub const ENABLED: bool = cfg!(feature = "breadcrumbs");
+
+/// Only the first segments of deeper paths are kept.
+pub const MAX_DEPTH: usize = 16;
+
+/// One step of a [Path].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Segment {
+    /// A field of a struct, a variant of an enum, or the name of a schema.
+    Field(&'static str),
+    /// An element of an array.
+    Index(u32),
+}
+
+/// A snapshot of the trail.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Path {
+    #[cfg(feature = "breadcrumbs")]
+    depth: usize,
+    #[cfg(feature = "breadcrumbs")]
+    segments: [Segment; MAX_DEPTH],
+}
+
+impl Path {
+    pub const EMPTY: Path = Path {
+        #[cfg(feature = "breadcrumbs")]
+        depth: 0,
+        #[cfg(feature = "breadcrumbs")]
+        segments: [Segment::Index(0); MAX_DEPTH],
+    };
+
+    /// The trail of the parsers currently running.
+    #[cfg(feature = "breadcrumbs")]
+    pub fn current() -> Path {
+        unsafe { TRAIL }
+    }
+
+    #[cfg(not(feature = "breadcrumbs"))]
+    pub fn current() -> Path {
+        Path::EMPTY
+    }
+
+    /// The segments kept, outermost first.
+    #[cfg(feature = "breadcrumbs")]
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments[..core::cmp::min(self.depth, MAX_DEPTH)]
+    }
+
+    #[cfg(not(feature = "breadcrumbs"))]
+    pub fn segments(&self) -> &[Segment] {
+        &[]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments().is_empty()
+    }
+
+    /// Whether segments past [MAX_DEPTH] were dropped.
+    #[cfg(feature = "breadcrumbs")]
+    pub fn is_truncated(&self) -> bool {
+        self.depth > MAX_DEPTH
+    }
+
+    #[cfg(not(feature = "breadcrumbs"))]
+    pub fn is_truncated(&self) -> bool {
+        false
+    }
+}
+
+impl Default for Path {
+    fn default() -> Self {
+        Path::EMPTY
+    }
+}
+
+/// Fields are separated with dots, and indices are in brackets, e.g. `commands[3].MoveCall`.
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.segments().iter().enumerate() {
+            match segment {
+                Segment::Field(name) if i == 0 => f.write_str(name)?,
+                Segment::Field(name) => write!(f, ".{}", name)?,
+                Segment::Index(ix) => write!(f, "[{}]", ix)?,
+            }
+        }
+        if self.is_truncated() {
+            f.write_str("...")?;
+        }
+        Ok(())
+    }
+}
+
+// The parsers run on a single thread on the device; on the host, each test has its own.
+#[cfg(feature = "breadcrumbs")]
+#[cfg_attr(not(target_family = "bolos"), thread_local)]
+static mut TRAIL: Path = Path::EMPTY;
+
+/// A segment of the trail, until dropped.
+///
+/// Keep it in a named binding (`let _crumb = ...`) for as long as the field is being parsed;
+/// `let _ = ...` would drop it immediately.
+#[must_use]
+pub struct Crumb {
+    #[cfg(feature = "breadcrumbs")]
+    depth: usize,
+}
+
+#[cfg(feature = "breadcrumbs")]
+fn push(segment: Segment) -> Crumb {
+    unsafe {
+        let depth = TRAIL.depth;
+        if depth < MAX_DEPTH {
+            TRAIL.segments[depth] = segment;
+        }
+        TRAIL.depth = depth + 1;
+        Crumb { depth }
+    }
+}
+
+#[cfg(not(feature = "breadcrumbs"))]
+#[inline(always)]
+fn push(_segment: Segment) -> Crumb {
+    Crumb {}
+}
+
+/// Pushes the name of a field, variant or schema.
+pub fn field(name: &'static str) -> Crumb {
+    push(Segment::Field(name))
+}
+
+/// Pushes the index of an array element.
+pub fn index(ix: usize) -> Crumb {
+    push(Segment::Index(ix as u32))
+}
+
+#[cfg(feature = "breadcrumbs")]
+impl Drop for Crumb {
+    fn drop(&mut self) {
+        // Futures are not always dropped innermost first, so this also pops the crumbs pushed
+        // after this one.
+        unsafe {
+            TRAIL.depth = core::cmp::min(TRAIL.depth, self.depth);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "breadcrumbs"))]
+mod test {
+    use super::*;
+    use arrayvec::ArrayString;
+    use core::fmt::Write;
+    #[cfg(target_family = "bolos")]
+    use testmacro::test_item as test;
+
+    fn current() -> ArrayString<128> {
+        let mut rv = ArrayString::new();
+        write!(rv, "{}", Path::current()).unwrap();
+        rv
+    }
+
+    #[test]
+    fn test_path() {
+        let outer = field("TransactionData");
+        {
+            let _v1 = field("V1");
+            let _commands = field("commands");
+            let _ix = index(3);
+            assert_eq!(current().as_str(), "TransactionData.V1.commands[3]");
+        }
+        assert_eq!(
+            Path::current().segments(),
+            &[Segment::Field("TransactionData")]
+        );
+        drop(outer);
+        assert!(Path::current().is_empty());
+    }
+
+    #[test]
+    fn test_drop_out_of_order() {
+        let outer = field("a");
+        let inner = field("b");
+        drop(outer);
+        assert!(Path::current().is_empty());
+        drop(inner);
+        assert!(Path::current().is_empty());
+        let _c = field("c");
+        assert_eq!(current().as_str(), "c");
+    }
+
+    #[test]
+    fn test_truncated() {
+        // Dropped outermost first
+        let crumbs: [Crumb; MAX_DEPTH + 2] = core::array::from_fn(index);
+        let path = Path::current();
+        assert_eq!(path.segments().len(), MAX_DEPTH);
+        assert!(path.is_truncated());
+        assert!(current().ends_with("[15]..."));
+        drop(crumbs);
+        assert!(Path::current().is_empty());
+    }
+}
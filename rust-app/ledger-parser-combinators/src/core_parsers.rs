@@ -1,3 +1,4 @@
+use crate::numbers::Uint256;
 pub use crate::schema::*;
 
 // use generic_array::{ArrayLength, GenericArray};
@@ -34,6 +35,8 @@ macro_rules! number_parser {
 number_parser! { U16, u16 }
 number_parser! { U32, u32 }
 number_parser! { U64, u64 }
+number_parser! { U128, u128 }
+number_parser! { U256, Uint256 }
 
 //pub enum OutOfBand {
 //    Prompt('a mut dyn Fn() -> usize),
@@ -1,3 +1,5 @@
+use crate::numbers::Uint256;
+
 #[derive(PartialEq, Eq)]
 #[cfg(version("1.75"))]
 #[derive(core::marker::ConstParamTy)]
@@ -39,3 +41,5 @@ macro_rules! impl_convert {
 impl_convert! { u16, 2 }
 impl_convert! { u32, 4 }
 impl_convert! { u64, 8 }
+impl_convert! { u128, 16 }
+impl_convert! { Uint256, 32 }
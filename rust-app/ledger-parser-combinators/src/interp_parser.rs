@@ -268,6 +268,8 @@ macro_rules! number_parser {
 number_parser! { U16, 2 }
 number_parser! { U32, 4 }
 number_parser! { U64, 8 }
+number_parser! { U128, 16 }
+number_parser! { U256, 32 }
 
 pub enum ForwardDArrayParserState<N, IS, I, const M: usize> {
     Length(N),
@@ -32,6 +32,13 @@ macro_rules! define_json_struct {
 
             pub struct [<$name Schema>];
 
+            /// Parser of the struct, with a parser for each field.
+            pub struct [<$name Interp>]<$([<Field $field:camel>]),*> {
+                $(pub [<field_ $field:snake>] : [<Field $field:camel>] ),*
+            }
+
+            $crate::define_json_struct_async_parser!{ $name $n { $($field : $schemaType),* } }
+
             #[macro_export]
             macro_rules! [<$name:snake _definition>] {
                 { } => { $crate::define_json_struct_interp!{ $name $n { $($field : $schemaType),* } } }
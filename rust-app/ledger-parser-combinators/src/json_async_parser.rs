@@ -0,0 +1,897 @@
+//! Async parsers for the JSON schemas of [crate::json], over any [Readable].
+//!
+//! JSON needs one byte of lookahead, to find the end of numbers, so the parsers read from a
+//! [JsonReadable] around the input; [Json] does the wrapping for a whole JSON value.
+//!
+//! Whitespace is skipped before and between tokens. Strings are unescaped, including `\u`
+//! escapes, which are encoded in UTF-8; other bytes of strings are kept as they are.
+use crate::async_parser::*;
+use crate::breadcrumbs;
+use crate::interp::*;
+use crate::json::*;
+use crate::json_interp::{AccumulateArray, JsonStringAccumulate};
+
+use arrayvec::ArrayVec;
+use core::future::Future;
+
+/// Limit on the nesting of arrays and objects skipped as [JsonAny].
+pub const MAX_JSON_DEPTH: usize = 32;
+
+/// Readable with one byte of lookahead.
+///
+/// Reading through it gives the byte looked at first, if any, then the rest of the input.
+#[derive(Clone)]
+pub struct JsonReadable<BS> {
+    input: BS,
+    peeked: Option<u8>,
+}
+
+impl<BS: Readable> JsonReadable<BS> {
+    pub fn new(input: BS) -> Self {
+        JsonReadable {
+            input,
+            peeked: None,
+        }
+    }
+
+    /// The underlying input, without the byte looked at, if any.
+    pub fn into_inner(self) -> BS {
+        self.input
+    }
+
+    pub async fn next_byte(&mut self) -> u8 {
+        match self.peeked.take() {
+            Some(b) => b,
+            None => {
+                let [b] = self.input.read().await;
+                b
+            }
+        }
+    }
+
+    /// Puts back the last byte read, to be read again next.
+    pub fn unread(&mut self, b: u8) {
+        debug_assert!(self.peeked.is_none());
+        self.peeked = Some(b);
+    }
+
+    /// The next byte which is not whitespace.
+    pub async fn next_token_byte(&mut self) -> u8 {
+        loop {
+            match self.next_byte().await {
+                b' ' | b'\t' | b'\n' | b'\r' => {}
+                b => return b,
+            }
+        }
+    }
+
+    /// Like [JsonReadable::next_token_byte], but leaves the byte to be read next.
+    pub async fn peek_token_byte(&mut self) -> u8 {
+        let b = self.next_token_byte().await;
+        self.unread(b);
+        b
+    }
+}
+
+impl<BS: Readable> Readable for JsonReadable<BS> {
+    type OutFut<'a, const N: usize>
+        = impl 'a + Future<Output = [u8; N]>
+    where
+        Self: 'a;
+    fn read<'a: 'b, 'b, const N: usize>(&'a mut self) -> Self::OutFut<'b, N> {
+        async move {
+            let mut buf = [0; N];
+            let rest = match self.peeked.take() {
+                Some(b) if N > 0 => {
+                    buf[0] = b;
+                    &mut buf[1..]
+                }
+                peeked => {
+                    self.peeked = peeked;
+                    &mut buf[..]
+                }
+            };
+            self.input.read_into(rest).await;
+            buf
+        }
+    }
+    type SkipFut<'a>
+        = impl 'a + Future<Output = ()>
+    where
+        Self: 'a;
+    fn skip<'a: 'b, 'b>(&'a mut self, n: usize) -> Self::SkipFut<'b> {
+        async move {
+            let n = match self.peeked {
+                Some(_) if n > 0 => {
+                    self.peeked = None;
+                    n - 1
+                }
+                _ => n,
+            };
+            self.input.skip(n).await
+        }
+    }
+    type ReadIntoFut<'a>
+        = impl 'a + Future<Output = ()>
+    where
+        Self: 'a;
+    fn read_into<'a: 'c, 'b: 'c, 'c>(&'a mut self, buf: &'b mut [u8]) -> Self::ReadIntoFut<'c> {
+        async move {
+            let rest = match self.peeked.take() {
+                Some(b) if !buf.is_empty() => {
+                    buf[0] = b;
+                    &mut buf[1..]
+                }
+                peeked => {
+                    self.peeked = peeked;
+                    buf
+                }
+            };
+            self.input.read_into(rest).await
+        }
+    }
+}
+
+async fn expect_literal<BS: Readable>(input: &mut JsonReadable<BS>, literal: &[u8]) {
+    for expected in literal {
+        if input.next_byte().await != *expected {
+            reject_on::<()>(core::file!(), core::line!(), PARSE_ERROR_CODE).await;
+        }
+    }
+}
+
+/// Expects this byte as the next token.
+pub async fn expect_token<BS: Readable>(input: &mut JsonReadable<BS>, token: u8) {
+    if input.next_token_byte().await != token {
+        reject_on::<()>(core::file!(), core::line!(), PARSE_ERROR_CODE).await;
+    }
+}
+
+async fn hex_digits<BS: Readable>(input: &mut JsonReadable<BS>) -> u32 {
+    let mut rv = 0;
+    for _ in 0..4 {
+        let digit = match char::from(input.next_byte().await).to_digit(16) {
+            Some(d) => d,
+            None => reject_on(core::file!(), core::line!(), PARSE_ERROR_CODE).await,
+        };
+        rv = rv * 16 + digit;
+    }
+    rv
+}
+
+/// Parses a string, passing its unescaped bytes to `push`, which returns false to reject it,
+/// e.g. when out of space.
+pub async fn parse_json_string<BS: Readable, F: FnMut(&[u8]) -> bool>(
+    input: &mut JsonReadable<BS>,
+    mut push: F,
+) {
+    expect_token(input, b'"').await;
+    loop {
+        let accepted = match input.next_byte().await {
+            b'"' => return,
+            b'\\' => match input.next_byte().await {
+                c @ (b'"' | b'\\' | b'/') => push(&[c]),
+                b'b' => push(b"\x08"),
+                b'f' => push(b"\x0c"),
+                b'n' => push(b"\n"),
+                b'r' => push(b"\r"),
+                b't' => push(b"\t"),
+                b'u' => {
+                    let mut code_point = hex_digits(input).await;
+                    // Characters outside of the BMP are escaped as a UTF-16 surrogate pair
+                    if (0xD800..0xDC00).contains(&code_point) {
+                        expect_literal(input, b"\\u").await;
+                        let low = hex_digits(input).await;
+                        if !(0xDC00..0xE000).contains(&low) {
+                            reject_on::<()>(core::file!(), core::line!(), PARSE_ERROR_CODE).await;
+                        }
+                        code_point = 0x10000 + ((code_point - 0xD800) << 10) + (low - 0xDC00);
+                    }
+                    // Lone low surrogates are not characters
+                    match char::from_u32(code_point) {
+                        Some(c) => push(c.encode_utf8(&mut [0; 4]).as_bytes()),
+                        None => false,
+                    }
+                }
+                _ => false,
+            },
+            // Control characters must be escaped
+            0..=0x1f => false,
+            c => push(&[c]),
+        };
+        if !accepted {
+            reject_on::<()>(core::file!(), core::line!(), PARSE_ERROR_CODE).await;
+        }
+    }
+}
+
+/// Parses a number, passing its bytes to `push`, which returns false to reject it.
+///
+/// The end of a number is only known from the byte after it, so a number at the end of the
+/// input is never complete.
+pub async fn parse_json_number<BS: Readable, F: FnMut(u8) -> bool>(
+    input: &mut JsonReadable<BS>,
+    mut push: F,
+) {
+    async fn digits<BS: Readable, F: FnMut(u8) -> bool>(
+        input: &mut JsonReadable<BS>,
+        push: &mut F,
+        mut c: u8,
+    ) -> u8 {
+        if !c.is_ascii_digit() {
+            reject_on::<()>(core::file!(), core::line!(), PARSE_ERROR_CODE).await;
+        }
+        while c.is_ascii_digit() {
+            if !push(c) {
+                reject_on::<()>(core::file!(), core::line!(), PARSE_ERROR_CODE).await;
+            }
+            c = input.next_byte().await;
+        }
+        c
+    }
+
+    let mut accepted = true;
+    let mut c = input.next_token_byte().await;
+    if c == b'-' {
+        accepted &= push(c);
+        c = input.next_byte().await;
+    }
+    // No leading zeros
+    c = if c == b'0' {
+        accepted &= push(c);
+        input.next_byte().await
+    } else {
+        digits(input, &mut push, c).await
+    };
+    if c == b'.' {
+        accepted &= push(c);
+        let next = input.next_byte().await;
+        c = digits(input, &mut push, next).await;
+    }
+    if c == b'e' || c == b'E' {
+        accepted &= push(c);
+        c = input.next_byte().await;
+        if c == b'+' || c == b'-' {
+            accepted &= push(c);
+            c = input.next_byte().await;
+        }
+        c = digits(input, &mut push, c).await;
+    }
+    if !accepted {
+        reject_on::<()>(core::file!(), core::line!(), PARSE_ERROR_CODE).await;
+    }
+    input.unread(c);
+}
+
+impl<T, S: HasOutput<T>> HasOutput<Json<T>> for Json<S> {
+    type Output = S::Output;
+}
+
+// Parses a whole JSON value with the parser for its schema. If the value is a number, the byte
+// after it is consumed too.
+impl<T, S: AsyncParser<T, JsonReadable<BS>>, BS: Clone + Readable> AsyncParser<Json<T>, BS>
+    for Json<S>
+{
+    type State<'c>
+        = impl Future<Output = Self::Output> + 'c
+    where
+        BS: 'c,
+        S: 'c;
+    fn parse<'a: 'c, 'b: 'c, 'c>(&'b self, input: &'a mut BS) -> Self::State<'c> {
+        async move {
+            let mut json = JsonReadable::new(input.clone());
+            let rv = self.0.parse(&mut json).await;
+            *input = json.into_inner();
+            rv
+        }
+    }
+}
+
+impl HasOutput<JsonBool> for DefaultInterp {
+    type Output = bool;
+}
+
+impl<BS: Readable> AsyncParser<JsonBool, JsonReadable<BS>> for DefaultInterp {
+    type State<'c>
+        = impl Future<Output = Self::Output> + 'c
+    where
+        BS: 'c;
+    fn parse<'a: 'c, 'b: 'c, 'c>(&'b self, input: &'a mut JsonReadable<BS>) -> Self::State<'c> {
+        async move {
+            match input.next_token_byte().await {
+                b't' => {
+                    expect_literal(input, b"rue").await;
+                    true
+                }
+                b'f' => {
+                    expect_literal(input, b"alse").await;
+                    false
+                }
+                _ => reject_on(core::file!(), core::line!(), PARSE_ERROR_CODE).await,
+            }
+        }
+    }
+}
+
+impl<BS: Readable> AsyncParser<JsonBool, JsonReadable<BS>> for DropInterp {
+    type State<'c>
+        = impl Future<Output = Self::Output> + 'c
+    where
+        BS: 'c;
+    fn parse<'a: 'c, 'b: 'c, 'c>(&'b self, input: &'a mut JsonReadable<BS>) -> Self::State<'c> {
+        async move {
+            <DefaultInterp as AsyncParser<JsonBool, _>>::parse(&DefaultInterp, input).await;
+        }
+    }
+}
+
+impl HasOutput<JsonNull> for DefaultInterp {
+    type Output = ();
+}
+
+impl<BS: Readable> AsyncParser<JsonNull, JsonReadable<BS>> for DefaultInterp {
+    type State<'c>
+        = impl Future<Output = Self::Output> + 'c
+    where
+        BS: 'c;
+    fn parse<'a: 'c, 'b: 'c, 'c>(&'b self, input: &'a mut JsonReadable<BS>) -> Self::State<'c> {
+        async move {
+            expect_token(input, b'n').await;
+            expect_literal(input, b"ull").await;
+        }
+    }
+}
+
+impl<BS: Readable> AsyncParser<JsonNull, JsonReadable<BS>> for DropInterp {
+    type State<'c>
+        = impl Future<Output = Self::Output> + 'c
+    where
+        BS: 'c;
+    fn parse<'a: 'c, 'b: 'c, 'c>(&'b self, input: &'a mut JsonReadable<BS>) -> Self::State<'c> {
+        <DefaultInterp as AsyncParser<JsonNull, _>>::parse(&DefaultInterp, input)
+    }
+}
+
+impl<const N: usize> HasOutput<JsonString> for JsonStringAccumulate<N> {
+    type Output = ArrayVec<u8, N>;
+}
+
+impl<const N: usize, BS: Readable> AsyncParser<JsonString, JsonReadable<BS>>
+    for JsonStringAccumulate<N>
+{
+    type State<'c>
+        = impl Future<Output = Self::Output> + 'c
+    where
+        BS: 'c;
+    fn parse<'a: 'c, 'b: 'c, 'c>(&'b self, input: &'a mut JsonReadable<BS>) -> Self::State<'c> {
+        async move {
+            let mut rv = ArrayVec::new();
+            parse_json_string(input, |bytes| rv.try_extend_from_slice(bytes).is_ok()).await;
+            rv
+        }
+    }
+}
+
+impl<BS: Readable> AsyncParser<JsonString, JsonReadable<BS>> for DropInterp {
+    type State<'c>
+        = impl Future<Output = Self::Output> + 'c
+    where
+        BS: 'c;
+    fn parse<'a: 'c, 'b: 'c, 'c>(&'b self, input: &'a mut JsonReadable<BS>) -> Self::State<'c> {
+        parse_json_string(input, |_| true)
+    }
+}
+
+/// The number as written, e.g. `-1.5e3`.
+impl<const N: usize> HasOutput<JsonNumber> for JsonStringAccumulate<N> {
+    type Output = ArrayVec<u8, N>;
+}
+
+impl<const N: usize, BS: Readable> AsyncParser<JsonNumber, JsonReadable<BS>>
+    for JsonStringAccumulate<N>
+{
+    type State<'c>
+        = impl Future<Output = Self::Output> + 'c
+    where
+        BS: 'c;
+    fn parse<'a: 'c, 'b: 'c, 'c>(&'b self, input: &'a mut JsonReadable<BS>) -> Self::State<'c> {
+        async move {
+            let mut rv = ArrayVec::new();
+            parse_json_number(input, |c| rv.try_push(c).is_ok()).await;
+            rv
+        }
+    }
+}
+
+impl<BS: Readable> AsyncParser<JsonNumber, JsonReadable<BS>> for DropInterp {
+    type State<'c>
+        = impl Future<Output = Self::Output> + 'c
+    where
+        BS: 'c;
+    fn parse<'a: 'c, 'b: 'c, 'c>(&'b self, input: &'a mut JsonReadable<BS>) -> Self::State<'c> {
+        parse_json_number(input, |_| true)
+    }
+}
+
+impl<T, S: HasOutput<T>, const N: usize> HasOutput<JsonArray<T>> for AccumulateArray<S, N> {
+    type Output = ArrayVec<S::Output, N>;
+}
+
+impl<T, S: AsyncParser<T, JsonReadable<BS>>, const N: usize, BS: Readable>
+    AsyncParser<JsonArray<T>, JsonReadable<BS>> for AccumulateArray<S, N>
+{
+    type State<'c>
+        = impl Future<Output = Self::Output> + 'c
+    where
+        BS: 'c,
+        S: 'c;
+    fn parse<'a: 'c, 'b: 'c, 'c>(&'b self, input: &'a mut JsonReadable<BS>) -> Self::State<'c> {
+        async move {
+            let mut rv = ArrayVec::new();
+            expect_token(input, b'[').await;
+            if input.peek_token_byte().await == b']' {
+                input.next_byte().await;
+                return rv;
+            }
+            loop {
+                {
+                    let _crumb = breadcrumbs::index(rv.len());
+                    let item = self.0.parse(input).await;
+                    if rv.try_push(item).is_err() {
+                        reject_on::<()>(core::file!(), core::line!(), PARSE_ERROR_CODE).await;
+                    }
+                }
+                match input.next_token_byte().await {
+                    b',' => {}
+                    b']' => return rv,
+                    _ => reject_on(core::file!(), core::line!(), PARSE_ERROR_CODE).await,
+                }
+            }
+        }
+    }
+}
+
+impl<T, BS: Readable> AsyncParser<JsonArray<T>, JsonReadable<BS>> for DropInterp
+where
+    DropInterp: AsyncParser<T, JsonReadable<BS>>,
+{
+    type State<'c>
+        = impl Future<Output = Self::Output> + 'c
+    where
+        BS: 'c;
+    fn parse<'a: 'c, 'b: 'c, 'c>(&'b self, input: &'a mut JsonReadable<BS>) -> Self::State<'c> {
+        async move {
+            expect_token(input, b'[').await;
+            if input.peek_token_byte().await == b']' {
+                input.next_byte().await;
+                return;
+            }
+            loop {
+                <DropInterp as AsyncParser<T, _>>::parse(&DropInterp, input).await;
+                match input.next_token_byte().await {
+                    b',' => {}
+                    b']' => return,
+                    _ => reject_on(core::file!(), core::line!(), PARSE_ERROR_CODE).await,
+                }
+            }
+        }
+    }
+}
+
+// Skips any value, without recursion, so that the nesting is only limited by MAX_JSON_DEPTH.
+impl<BS: Readable> AsyncParser<JsonAny, JsonReadable<BS>> for DropInterp {
+    type State<'c>
+        = impl Future<Output = Self::Output> + 'c
+    where
+        BS: 'c;
+    fn parse<'a: 'c, 'b: 'c, 'c>(&'b self, input: &'a mut JsonReadable<BS>) -> Self::State<'c> {
+        async move {
+            // The arrays and objects around the next value, true for objects
+            let mut stack: ArrayVec<bool, MAX_JSON_DEPTH> = ArrayVec::new();
+            loop {
+                match input.peek_token_byte().await {
+                    open @ (b'{' | b'[') => {
+                        input.next_byte().await;
+                        let close = if open == b'{' { b'}' } else { b']' };
+                        if input.peek_token_byte().await == close {
+                            input.next_byte().await;
+                        } else {
+                            if stack.try_push(open == b'{').is_err() {
+                                reject_on::<()>(core::file!(), core::line!(), PARSE_ERROR_CODE)
+                                    .await;
+                            }
+                            if open == b'{' {
+                                parse_json_string(input, |_| true).await;
+                                expect_token(input, b':').await;
+                            }
+                            continue;
+                        }
+                    }
+                    b'"' => parse_json_string(input, |_| true).await,
+                    b't' | b'f' => {
+                        <DefaultInterp as AsyncParser<JsonBool, _>>::parse(&DefaultInterp, input)
+                            .await;
+                    }
+                    b'n' => {
+                        <DefaultInterp as AsyncParser<JsonNull, _>>::parse(&DefaultInterp, input)
+                            .await
+                    }
+                    _ => parse_json_number(input, |_| true).await,
+                }
+                // After a value, close the arrays and objects it ends
+                loop {
+                    let in_object = match stack.last() {
+                        Some(in_object) => *in_object,
+                        None => return,
+                    };
+                    match input.next_token_byte().await {
+                        b',' => {
+                            if in_object {
+                                parse_json_string(input, |_| true).await;
+                                expect_token(input, b':').await;
+                            }
+                            break;
+                        }
+                        b'}' if in_object => {
+                            stack.pop();
+                        }
+                        b']' if !in_object => {
+                            stack.pop();
+                        }
+                        _ => reject_on(core::file!(), core::line!(), PARSE_ERROR_CODE).await,
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Async parsers for a struct declared with [define_json_struct](crate::define_json_struct),
+/// which expands this macro.
+///
+/// Each field is optional, and may only appear once; unknown fields are rejected.
+#[macro_export]
+macro_rules! define_json_struct_async_parser {
+    { $name:ident $n:literal { $($field:ident : $schemaType:ty),* } } => {
+        $crate::json::paste! {
+            impl<$([<Field $field:camel Interp>] : $crate::async_parser::HasOutput<$schemaType>),*>
+                $crate::async_parser::HasOutput<[<$name Schema>]>
+                for [<$name Interp>]<$([<Field $field:camel Interp>]),*>
+            {
+                type Output = $name<
+                    $(Option<<[<Field $field:camel Interp>] as $crate::async_parser::HasOutput<$schemaType>>::Output>),*
+                >;
+            }
+
+            impl<
+                BS: $crate::async_parser::Readable,
+                $([<Field $field:camel Interp>] : $crate::async_parser::AsyncParser<$schemaType, $crate::json_async_parser::JsonReadable<BS>>),*
+            >
+                $crate::async_parser::AsyncParser<[<$name Schema>], $crate::json_async_parser::JsonReadable<BS>>
+                for [<$name Interp>]<$([<Field $field:camel Interp>]),*>
+            {
+                type State<'c>
+                    = impl core::future::Future<Output = Self::Output> + 'c
+                where
+                    BS: 'c,
+                    $([<Field $field:camel Interp>]: 'c),*;
+                fn parse<'a: 'c, 'b: 'c, 'c>(
+                    &'b self,
+                    input: &'a mut $crate::json_async_parser::JsonReadable<BS>,
+                ) -> Self::State<'c> {
+                    async move {
+                        use $crate::json_async_parser::expect_token;
+                        let mut rv = $name { $([<field_ $field:snake>]: None),* };
+                        expect_token(input, b'{').await;
+                        if input.peek_token_byte().await == b'}' {
+                            input.next_byte().await;
+                            return rv;
+                        }
+                        loop {
+                            let key = <$crate::json_interp::JsonStringAccumulate<$n> as $crate::async_parser::AsyncParser<$crate::json::JsonString, _>>::parse(
+                                &$crate::json_interp::JsonStringAccumulate,
+                                input,
+                            )
+                            .await;
+                            expect_token(input, b':').await;
+                            match &key[..] {
+                                $(
+                                    $crate::json_interp::bstringify!($field) if rv.[<field_ $field:snake>].is_none() => {
+                                        let _crumb = $crate::breadcrumbs::field(core::stringify!($field));
+                                        rv.[<field_ $field:snake>] = Some(self.[<field_ $field:snake>].parse(input).await);
+                                    }
+                                )*
+                                _ => $crate::async_parser::reject_on(core::file!(), core::line!(), $crate::async_parser::PARSE_ERROR_CODE).await,
+                            }
+                            match input.next_token_byte().await {
+                                b',' => {}
+                                b'}' => return rv,
+                                _ => $crate::async_parser::reject_on(core::file!(), core::line!(), $crate::async_parser::PARSE_ERROR_CODE).await,
+                            }
+                        }
+                    }
+                }
+            }
+
+            impl<BS: $crate::async_parser::Readable>
+                $crate::async_parser::AsyncParser<[<$name Schema>], $crate::json_async_parser::JsonReadable<BS>>
+                for $crate::interp::DropInterp
+            {
+                type State<'c>
+                    = impl core::future::Future<Output = Self::Output> + 'c
+                where
+                    BS: 'c;
+                fn parse<'a: 'c, 'b: 'c, 'c>(
+                    &'b self,
+                    input: &'a mut $crate::json_async_parser::JsonReadable<BS>,
+                ) -> Self::State<'c> {
+                    async move {
+                        <[<$name Interp>]<$($crate::define_json_struct_async_parser!{ DROP $field }),*> as $crate::async_parser::AsyncParser<[<$name Schema>], _>>::parse(
+                            &[<$name Interp>] { $([<field_ $field:snake>]: $crate::interp::DropInterp),* },
+                            input,
+                        )
+                        .await;
+                    }
+                }
+            }
+        }
+    };
+    { DROP $a:ident } => { $crate::interp::DropInterp }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bcs::testing::*;
+    use crate::define_json_struct;
+    use core::convert::TryFrom;
+    use core::task::Poll;
+
+    #[cfg(target_family = "bolos")]
+    #[allow(unused_imports)]
+    use ledger_device_sdk::testing::TestType;
+    #[cfg(target_family = "bolos")]
+    use testmacro::test_item as test;
+
+    define_json_struct! {
+        Transfer 10 {
+            recipient : JsonString,
+            amounts : JsonArray<JsonNumber>,
+            memo : JsonAny
+        }
+    }
+
+    // Parses the input, which must end right after an accepted value
+    fn parse_json<T, P: AsyncParser<T, JsonReadable<TestReadable<&'static [u8]>>>>(
+        parser: &P,
+        input: &'static [u8],
+    ) -> Option<P::Output> {
+        let mut bs = JsonReadable::new(TestReadable::new(input));
+        match poll_once(TryFuture(parser.parse(&mut bs))) {
+            Poll::Ready(rv) => {
+                if rv.is_some() {
+                    assert_eq!(bs.clone().next_byte_now(), None);
+                }
+                rv
+            }
+            Poll::Pending => panic!("read past the end of the input"),
+        }
+    }
+
+    impl<T: AsRef<[u8]>> JsonReadable<TestReadable<T>> {
+        fn next_byte_now(self) -> Option<u8> {
+            let rest = &self.input.0.as_ref()[self.input.1..];
+            self.peeked.or(rest.first().copied())
+        }
+    }
+
+    fn bytes<const N: usize>(b: &[u8]) -> ArrayVec<u8, N> {
+        ArrayVec::try_from(b).unwrap()
+    }
+
+    #[test]
+    fn test_literals() {
+        let bool_parser = DefaultInterp;
+        assert_eq!(
+            parse_json::<JsonBool, _>(&bool_parser, b" true"),
+            Some(true)
+        );
+        assert_eq!(
+            parse_json::<JsonBool, _>(&bool_parser, b"false"),
+            Some(false)
+        );
+        assert_eq!(parse_json::<JsonBool, _>(&bool_parser, b"nul"), None);
+        assert_eq!(parse_json::<JsonBool, _>(&bool_parser, b"trUe"), None);
+        assert_eq!(
+            parse_json::<JsonNull, _>(&DefaultInterp, b"\nnull"),
+            Some(())
+        );
+    }
+
+    #[test]
+    fn test_string() {
+        let p = JsonStringAccumulate::<16>;
+        assert_eq!(
+            parse_json::<JsonString, _>(&p, b"\"one\""),
+            Some(bytes(b"one"))
+        );
+        assert_eq!(
+            parse_json::<JsonString, _>(&p, br#""a\"\\\/\n\t""#),
+            Some(bytes(b"a\"\\/\n\t"))
+        );
+        // BMP characters and surrogate pairs are encoded in UTF-8
+        assert_eq!(
+            parse_json::<JsonString, _>(&p, br#""\u00e9\ud83d\ude00""#),
+            Some(bytes("é😀".as_bytes()))
+        );
+        assert_eq!(
+            parse_json::<JsonString, _>(&p, "\"é\"".as_bytes()),
+            Some(bytes("é".as_bytes()))
+        );
+        // Lone surrogates, unknown escapes, control characters
+        assert_eq!(parse_json::<JsonString, _>(&p, br#""\ud83d""#), None);
+        assert_eq!(parse_json::<JsonString, _>(&p, br#""\ude00""#), None);
+        assert_eq!(parse_json::<JsonString, _>(&p, br#""\x""#), None);
+        assert_eq!(parse_json::<JsonString, _>(&p, b"\"\n\""), None);
+        // Too long
+        assert_eq!(
+            parse_json::<JsonString, _>(&p, b"\"0123456789abcdefg\""),
+            None
+        );
+        assert_eq!(
+            parse_json::<JsonString, _>(&DropInterp, b"\"0123456789abcdefg\""),
+            Some(())
+        );
+    }
+
+    #[test]
+    fn test_number() {
+        let p = JsonStringAccumulate::<8>;
+        for n in [&b"0"[..], b"-12", b"1.5", b"-0.25e+3", b"7E9"] {
+            let mut input = ArrayVec::<u8, 16>::new();
+            input.try_extend_from_slice(b" [").unwrap();
+            input.try_extend_from_slice(n).unwrap();
+            input.try_extend_from_slice(b"]").unwrap();
+            let mut bs = JsonReadable::new(TestReadable::new(input.clone()));
+            assert_eq!(
+                poll_once(<AccumulateArray<_, 1> as AsyncParser<
+                    JsonArray<JsonNumber>,
+                    _,
+                >>::parse(
+                    &AccumulateArray(JsonStringAccumulate::<8>), &mut bs
+                )),
+                Poll::Ready(ArrayVec::from([bytes(n)]))
+            );
+        }
+        for n in [&b"-]"[..], b"1.]", b".5]", b"1e]", b"+1]", b"123456789]"] {
+            let mut bs = JsonReadable::new(TestReadable::new(n));
+            assert_eq!(
+                poll_once(TryFuture(<_ as AsyncParser<JsonNumber, _>>::parse(
+                    &p, &mut bs
+                ))),
+                Poll::Ready(None)
+            );
+        }
+        // The byte after the number is left to be read
+        let mut bs = JsonReadable::new(TestReadable::new(&b"42,"[..]));
+        assert_eq!(
+            poll_once(<_ as AsyncParser<JsonNumber, _>>::parse(&p, &mut bs)),
+            Poll::Ready(bytes(b"42"))
+        );
+        assert_eq!(poll_once(bs.next_byte()), Poll::Ready(b','));
+    }
+
+    #[test]
+    fn test_array() {
+        let p = AccumulateArray::<_, 2>(JsonStringAccumulate::<4>);
+        assert_eq!(
+            parse_json::<JsonArray<JsonString>, _>(&p, b"[ ]"),
+            Some(ArrayVec::new())
+        );
+        assert_eq!(
+            parse_json::<JsonArray<JsonString>, _>(&p, b"[\"a\", \"b\" ]"),
+            Some(ArrayVec::from([bytes(b"a"), bytes(b"b")]))
+        );
+        // Too many, trailing or missing commas
+        assert_eq!(
+            parse_json::<JsonArray<JsonString>, _>(&p, b"[\"a\",\"b\",\"c\"]"),
+            None
+        );
+        assert_eq!(
+            parse_json::<JsonArray<JsonString>, _>(&p, b"[\"a\",]"),
+            None
+        );
+        assert_eq!(
+            parse_json::<JsonArray<JsonString>, _>(&p, b"[\"a\" \"b\"]"),
+            None
+        );
+        // A leading zero ends the number
+        assert_eq!(
+            parse_json::<JsonArray<JsonNumber>, _>(
+                &AccumulateArray::<_, 2>(JsonStringAccumulate::<4>),
+                b"[01]"
+            ),
+            None
+        );
+        assert_eq!(
+            parse_json::<JsonArray<JsonString>, _>(&DropInterp, b"[\"a\",\"b\",\"c\"]"),
+            Some(())
+        );
+    }
+
+    #[test]
+    fn test_any() {
+        let any = |input| parse_json::<JsonAny, _>(&DropInterp, input);
+        assert_eq!(any(b"\"x\""), Some(()));
+        assert_eq!(any(b"{}"), Some(()));
+        assert_eq!(
+            any(br#"{"a": [1, {"b": null}, [], true], "c": {"d": "]"}}"#),
+            Some(())
+        );
+        assert_eq!(any(b"[1, 2}"), None);
+        assert_eq!(any(b"{\"a\" 1}"), None);
+        assert_eq!(any(b"{1: 1}"), None);
+        assert_eq!(any(b"[,]"), None);
+        // Nesting is limited
+        assert_eq!(any(&[b'['; MAX_JSON_DEPTH + 2]), None);
+    }
+
+    #[test]
+    fn test_struct() {
+        let p = TransferInterp {
+            field_recipient: JsonStringAccumulate::<8>,
+            field_amounts: AccumulateArray::<_, 2>(JsonStringAccumulate::<8>),
+            field_memo: DropInterp,
+        };
+        assert_eq!(
+            parse_json::<TransferSchema, _>(
+                &p,
+                br#" { "amounts": [1, 20], "memo": {"x": []}, "recipient" : "0x1" }"#
+            ),
+            Some(Transfer {
+                field_recipient: Some(bytes(b"0x1")),
+                field_amounts: Some(ArrayVec::from([bytes(b"1"), bytes(b"20")])),
+                field_memo: Some(()),
+            })
+        );
+        // Fields are optional
+        assert_eq!(
+            parse_json::<TransferSchema, _>(&p, b"{}"),
+            Some(Transfer {
+                field_recipient: None,
+                field_amounts: None,
+                field_memo: None,
+            })
+        );
+        // Unknown and repeated fields
+        assert_eq!(
+            parse_json::<TransferSchema, _>(&p, br#"{"sender": "0x1"}"#),
+            None
+        );
+        assert_eq!(
+            parse_json::<TransferSchema, _>(&p, br#"{"memo": 1, "memo": 2}"#),
+            None
+        );
+        assert_eq!(
+            parse_json::<TransferSchema, _>(
+                &DropInterp,
+                br#"{"recipient": "0x1234567890", "amounts": [1, 2, 3]}"#
+            ),
+            Some(())
+        );
+    }
+
+    #[test]
+    fn test_json_wrapper() {
+        // Json reads from any Readable, and leaves it after the value
+        let mut bs = TestReadable::new(&b"[\"a\"] rest"[..]);
+        let p = Json(AccumulateArray::<_, 1>(JsonStringAccumulate::<1>));
+        assert_eq!(
+            poll_once(<_ as AsyncParser<Json<JsonArray<JsonString>>, _>>::parse(
+                &p, &mut bs
+            )),
+            Poll::Ready(ArrayVec::from([bytes(b"a")]))
+        );
+        assert_eq!(bs.1, 5);
+    }
+}
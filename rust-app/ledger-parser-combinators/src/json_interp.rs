@@ -1801,10 +1801,6 @@ macro_rules! define_json_struct_interp {
                 }
             }
 
-            pub struct [<$name Interp>]<$([<Field $field:camel>]: ParserCommon<$schemaType>),*> {
-                $(pub [<field_ $field:snake>] : [<Field $field:camel>] ),*
-            }
-
             #[derive(Debug)]
             pub enum [<$name State>]<$([<Field $field:camel>]),*> {
                 Start,
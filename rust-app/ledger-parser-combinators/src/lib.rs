@@ -9,6 +9,7 @@
 #![feature(generic_const_exprs)]
 #![feature(cfg_version)]
 #![feature(macro_metavar_expr)]
+#![cfg_attr(not(target_family = "bolos"), feature(thread_local))]
 #![cfg_attr(
     not(version("1.56")),
     feature(bindings_after_at),
@@ -31,6 +32,9 @@
 #[macro_use]
 extern crate enum_init;
 
+// So that the parsers derived with bcs_derive also work in this crate
+extern crate self as ledger_parser_combinators;
+
 #[macro_use]
 extern crate num_derive;
 
@@ -72,6 +76,7 @@ fn handle_panic(_: &PanicInfo) -> ! {
 pub mod core_parsers;
 
 pub mod endianness;
+pub mod numbers;
 pub mod interp;
 pub mod schema;
 
@@ -79,8 +84,12 @@ pub mod interp_parser;
 
 pub mod json;
 pub mod json_interp;
+pub mod json_async_parser;
 
 pub mod async_parser;
+pub mod breadcrumbs;
+#[cfg(feature = "alloc")]
+pub mod recursive;
 
 pub mod bcs;
 pub mod protobufs;
@@ -0,0 +1,283 @@
+//! Unsigned integers wider than the native ones, and their decimal formatting.
+//!
+//! Move uses u128 and u256 for e.g. prices and pool liquidity, so these have to be displayed
+//! without going through u64, and in no_std.
+
+use core::cmp::Ordering;
+use core::fmt;
+
+/// An unsigned 256-bit integer, as parsed by [U256](crate::schema::U256).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Uint256 {
+    // Least significant first
+    limbs: [u64; 4],
+}
+
+impl Uint256 {
+    pub const ZERO: Uint256 = Uint256 { limbs: [0; 4] };
+    pub const MAX: Uint256 = Uint256 {
+        limbs: [u64::MAX; 4],
+    };
+
+    pub fn from_le_bytes(bytes: [u8; 32]) -> Self {
+        let mut limbs = [0; 4];
+        for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+            let mut b = [0; 8];
+            b.copy_from_slice(chunk);
+            *limb = u64::from_le_bytes(b);
+        }
+        Uint256 { limbs }
+    }
+
+    pub fn from_be_bytes(mut bytes: [u8; 32]) -> Self {
+        bytes.reverse();
+        Self::from_le_bytes(bytes)
+    }
+
+    pub fn to_le_bytes(self) -> [u8; 32] {
+        let mut bytes = [0; 32];
+        for (chunk, limb) in bytes.chunks_exact_mut(8).zip(self.limbs.iter()) {
+            chunk.copy_from_slice(&limb.to_le_bytes());
+        }
+        bytes
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs == [0; 4]
+    }
+
+    /// The value, if it fits in a u128.
+    pub fn to_u128(self) -> Option<u128> {
+        if self.limbs[2] == 0 && self.limbs[3] == 0 {
+            Some(u128::from(self.limbs[0]) | u128::from(self.limbs[1]) << 64)
+        } else {
+            None
+        }
+    }
+
+    /// Divides in place by `divisor`, which must not be zero, and returns the remainder.
+    pub fn div_rem_u64(&mut self, divisor: u64) -> u64 {
+        let mut remainder: u64 = 0;
+        for limb in self.limbs.iter_mut().rev() {
+            let dividend = u128::from(remainder) << 64 | u128::from(*limb);
+            *limb = (dividend / u128::from(divisor)) as u64;
+            remainder = (dividend % u128::from(divisor)) as u64;
+        }
+        remainder
+    }
+}
+
+impl From<u64> for Uint256 {
+    fn from(value: u64) -> Self {
+        Uint256 {
+            limbs: [value, 0, 0, 0],
+        }
+    }
+}
+
+impl From<u128> for Uint256 {
+    fn from(value: u128) -> Self {
+        Uint256 {
+            limbs: [value as u64, (value >> 64) as u64, 0, 0],
+        }
+    }
+}
+
+impl Ord for Uint256 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs.iter().rev().cmp(other.limbs.iter().rev())
+    }
+}
+
+impl PartialOrd for Uint256 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Uint256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Decimal::new(*self, 0).fmt(f)
+    }
+}
+
+/// Number of digits of the largest [Uint256].
+pub const MAX_DIGITS: usize = 78;
+
+/// Unsigned integers that can be written in decimal.
+pub trait DecimalDigits: Copy {
+    /// Writes the digits at the end of `buf`, and returns them. Zero has a single digit.
+    fn decimal_digits(self, buf: &mut [u8; MAX_DIGITS]) -> &[u8];
+}
+
+macro_rules! decimal_digits {
+    ($($t:ty),*) => {
+        $(
+            impl DecimalDigits for $t {
+                fn decimal_digits(self, buf: &mut [u8; MAX_DIGITS]) -> &[u8] {
+                    let mut value = self;
+                    let mut start = MAX_DIGITS;
+                    loop {
+                        start -= 1;
+                        buf[start] = b'0' + (value % 10) as u8;
+                        value /= 10;
+                        if value == 0 {
+                            return &buf[start..];
+                        }
+                    }
+                }
+            }
+        )*
+    };
+}
+
+decimal_digits! { u8, u16, u32, u64, u128 }
+
+impl DecimalDigits for Uint256 {
+    fn decimal_digits(self, buf: &mut [u8; MAX_DIGITS]) -> &[u8] {
+        // 19 digits at a time, the most that fit in a u64
+        const CHUNK: u64 = 10_000_000_000_000_000_000;
+        let mut value = self;
+        let mut start = MAX_DIGITS;
+        loop {
+            let mut chunk = value.div_rem_u64(CHUNK);
+            if value.is_zero() {
+                let mut chunk_buf = [0; MAX_DIGITS];
+                let digits = chunk.decimal_digits(&mut chunk_buf);
+                start -= digits.len();
+                buf[start..start + digits.len()].copy_from_slice(digits);
+                return &buf[start..];
+            }
+            // Zero-padded
+            for _ in 0..19 {
+                start -= 1;
+                buf[start] = b'0' + (chunk % 10) as u8;
+                chunk /= 10;
+            }
+        }
+    }
+}
+
+/// Displays `value / 10^decimals`, e.g. an amount of a token with that many decimals.
+///
+/// Trailing zeros of the fraction are dropped, but one digit is kept, so that 1 SUI
+/// (1000000000 MIST) is displayed as `1.0`. There is no fraction when `decimals` is 0.
+#[derive(Clone, Copy, Debug)]
+pub struct Decimal<T> {
+    pub value: T,
+    pub decimals: u8,
+}
+
+impl<T: DecimalDigits> Decimal<T> {
+    pub fn new(value: T, decimals: u8) -> Self {
+        Decimal { value, decimals }
+    }
+}
+
+impl<T: DecimalDigits> fmt::Display for Decimal<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buf = [0; MAX_DIGITS];
+        let digits = self.value.decimal_digits(&mut buf);
+        let decimals = self.decimals as usize;
+        let (integer, fraction) = digits.split_at(digits.len().saturating_sub(decimals));
+        let integer = if integer.is_empty() { b"0" } else { integer };
+        let as_str = |digits| core::str::from_utf8(digits).map_err(|_| fmt::Error);
+        f.write_str(as_str(integer)?)?;
+        if decimals == 0 {
+            return Ok(());
+        }
+        f.write_str(".")?;
+        match fraction.iter().rposition(|d| *d != b'0') {
+            None => f.write_str("0"),
+            Some(last) => {
+                for _ in fraction.len()..decimals {
+                    f.write_str("0")?;
+                }
+                f.write_str(as_str(&fraction[..=last])?)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use arrayvec::ArrayString;
+    use core::fmt::Write;
+
+    #[cfg(target_family = "bolos")]
+    #[allow(unused_imports)]
+    use ledger_device_sdk::testing::TestType;
+    #[cfg(target_family = "bolos")]
+    use testmacro::test_item as test;
+
+    fn show<T: fmt::Display>(value: T) -> ArrayString<128> {
+        let mut rv = ArrayString::new();
+        write!(rv, "{}", value).unwrap();
+        rv
+    }
+
+    #[test]
+    fn test_decimal() {
+        assert_eq!(show(Decimal::new(1_000_000_000u64, 9)).as_str(), "1.0");
+        assert_eq!(show(Decimal::new(1_500_000_000u64, 9)).as_str(), "1.5");
+        assert_eq!(show(Decimal::new(1_234u64, 9)).as_str(), "0.000001234");
+        assert_eq!(show(Decimal::new(0u64, 9)).as_str(), "0.0");
+        assert_eq!(show(Decimal::new(0u8, 0)).as_str(), "0");
+        assert_eq!(show(Decimal::new(42u32, 0)).as_str(), "42");
+        assert_eq!(show(Decimal::new(42u32, 2)).as_str(), "0.42");
+        assert_eq!(
+            show(Decimal::new(u64::MAX, 6)).as_str(),
+            "18446744073709.551615"
+        );
+        assert_eq!(
+            show(Decimal::new(u128::MAX, 18)).as_str(),
+            "340282366920938463463.374607431768211455"
+        );
+    }
+
+    #[test]
+    fn test_uint256() {
+        assert_eq!(show(Uint256::ZERO).as_str(), "0");
+        assert_eq!(
+            show(Uint256::from(u64::MAX)).as_str(),
+            "18446744073709551615"
+        );
+        assert_eq!(
+            show(Uint256::from(u128::MAX)).as_str(),
+            "340282366920938463463374607431768211455"
+        );
+        // 10^19 and 10^38, at the boundaries of the chunks of digits
+        assert_eq!(
+            show(Uint256::from(10_000_000_000_000_000_000u64)).as_str(),
+            "10000000000000000000"
+        );
+        assert_eq!(
+            show(Uint256::from(10u128.pow(38))).as_str(),
+            "100000000000000000000000000000000000000"
+        );
+        assert_eq!(
+            show(Uint256::MAX).as_str(),
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+        );
+        assert_eq!(
+            show(Decimal::new(Uint256::MAX, 77)).as_str(),
+            "1.15792089237316195423570985008687907853269984665640564039457584007913129639935"
+        );
+        assert_eq!(
+            show(Decimal::new(Uint256::from(5u64), 80)).as_str().len(),
+            82
+        );
+
+        let mut bytes = [0; 32];
+        bytes[0] = 1;
+        bytes[16] = 2;
+        let value = Uint256::from_le_bytes(bytes);
+        assert_eq!(value.to_le_bytes(), bytes);
+        assert_eq!(value.to_u128(), None);
+        assert!(value > Uint256::from(u128::MAX));
+        let mut bytes = [0; 32];
+        bytes[16..].copy_from_slice(&u128::MAX.to_be_bytes());
+        assert_eq!(Uint256::from_be_bytes(bytes).to_u128(), Some(u128::MAX));
+    }
+}
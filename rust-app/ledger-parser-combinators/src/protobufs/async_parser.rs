@@ -168,6 +168,16 @@ impl<BS: 'static + Readable> Readable for TrackLength<BS> {
         self.1 += N;
         self.0.read()
     }
+    type SkipFut<'a> = impl Future<Output = ()> + 'a;
+    fn skip<'a: 'b, 'b>(&'a mut self, n: usize) -> Self::SkipFut<'b> {
+        self.1 += n;
+        self.0.skip(n)
+    }
+    type ReadIntoFut<'a> = impl Future<Output = ()> + 'a;
+    fn read_into<'a: 'c, 'b: 'c, 'c>(&'a mut self, buf: &'b mut [u8]) -> Self::ReadIntoFut<'c> {
+        self.1 += buf.len();
+        self.0.read_into(buf)
+    }
 }
 
 pub async fn skip_field<BS: Readable>(fmt: ProtobufWire, i: &mut BS) {
@@ -763,6 +773,26 @@ mod test {
                 panic!("Read past end of input");
             }
         }
+        type SkipFut<'a> = impl 'a + Future<Output = ()>;
+        fn skip<'a: 'b, 'b>(&'a mut self, n: usize) -> Self::SkipFut<'b> {
+            if self.1 + n <= self.0.len() {
+                self.1 += n;
+                core::future::ready(())
+            } else {
+                panic!("Read past end of input");
+            }
+        }
+        type ReadIntoFut<'a> = impl 'a + Future<Output = ()>;
+        fn read_into<'a: 'c, 'b: 'c, 'c>(&'a mut self, buf: &'b mut [u8]) -> Self::ReadIntoFut<'c> {
+            if self.1 + buf.len() <= self.0.len() {
+                let offset = self.1;
+                self.1 += buf.len();
+                buf.copy_from_slice(&self.0[offset..self.1]);
+                core::future::ready(())
+            } else {
+                panic!("Read past end of input");
+            }
+        }
     }
 
     impl<const N: usize> ReadableLength for TestReadable<N> {
@@ -0,0 +1,195 @@
+//! Parsers for recursive schemas, such as Move type tags, which may contain type tags.
+//!
+//! The future of an async parser can not contain itself, so a parser can not await itself
+//! directly. A [RecursiveParser] parses one level of the schema, and the levels within it with
+//! the [Nested] parser it is given, whose future is boxed. [Recursive] runs it with a maximum
+//! depth, so that the input can not exhaust the heap.
+//!
+//! This module needs the `alloc` feature, and a global allocator.
+
+extern crate alloc;
+
+use crate::async_parser::*;
+use alloc::boxed::Box;
+use core::future::Future;
+use core::pin::Pin;
+
+/// Parser of one level of a recursive schema.
+pub trait RecursiveParser<Schema, BS: Readable>: HasOutput<Schema> + Sized {
+    /// Parse one level of input, using `nested` for the values of `Schema` within it.
+    fn parse_level<'a: 'c, 'b: 'c, 'c>(
+        &'b self,
+        input: &'a mut BS,
+        nested: Nested<'b, Self>,
+    ) -> Self::LevelState<'c>;
+
+    /// Type synonym for the future returned by this parser.
+    type LevelState<'c>: Future<Output = Self::Output>
+    where
+        BS: 'c,
+        Self: 'c;
+}
+
+/// Parses a recursive schema with the [RecursiveParser] `I`, nesting at most `MAX_DEPTH` levels,
+/// the outermost one included.
+#[derive(Clone, Copy)]
+pub struct Recursive<I, const MAX_DEPTH: usize>(pub I);
+
+impl<Schema, I: HasOutput<Schema>, const MAX_DEPTH: usize> HasOutput<Schema>
+    for Recursive<I, MAX_DEPTH>
+{
+    type Output = I::Output;
+}
+
+impl<Schema, I: RecursiveParser<Schema, BS>, BS: Readable, const MAX_DEPTH: usize>
+    AsyncParser<Schema, BS> for Recursive<I, MAX_DEPTH>
+{
+    type State<'c>
+        = I::LevelState<'c>
+    where
+        BS: 'c,
+        Self: 'c;
+    fn parse<'a: 'c, 'b: 'c, 'c>(&'b self, input: &'a mut BS) -> Self::State<'c> {
+        self.0.parse_level(
+            input,
+            Nested {
+                interp: &self.0,
+                depth: 2,
+                max_depth: MAX_DEPTH,
+            },
+        )
+    }
+}
+
+/// Parser of the values nested in the level being parsed by a [RecursiveParser].
+///
+/// Rejects the input with [PARSE_ERROR_CODE] past the maximum depth.
+pub struct Nested<'r, I> {
+    interp: &'r I,
+    depth: usize,
+    max_depth: usize,
+}
+
+impl<I> Clone for Nested<'_, I> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<I> Copy for Nested<'_, I> {}
+
+impl<I> Nested<'_, I> {
+    /// The depth of the values parsed by this parser, the outermost level being 1.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+}
+
+impl<Schema, I: HasOutput<Schema>> HasOutput<Schema> for Nested<'_, I> {
+    type Output = I::Output;
+}
+
+impl<Schema: 'static, I: RecursiveParser<Schema, BS>, BS: Readable> AsyncParser<Schema, BS>
+    for Nested<'_, I>
+{
+    type State<'c>
+        = Pin<Box<dyn Future<Output = Self::Output> + 'c>>
+    where
+        BS: 'c,
+        Self: 'c;
+    fn parse<'a: 'c, 'b: 'c, 'c>(&'b self, input: &'a mut BS) -> Self::State<'c> {
+        if self.depth > self.max_depth {
+            Box::pin(reject_on(core::file!(), core::line!(), PARSE_ERROR_CODE))
+        } else {
+            Box::pin(self.interp.parse_level(
+                input,
+                Nested {
+                    depth: self.depth + 1,
+                    ..*self
+                },
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bcs::async_parser::*;
+    use crate::bcs::testing::*;
+    use crate::interp::*;
+    use crate::schema::*;
+    use core::task::Poll;
+
+    #[cfg(target_family = "bolos")]
+    #[allow(unused_imports)]
+    use ledger_device_sdk::testing::TestType;
+    #[cfg(target_family = "bolos")]
+    use testmacro::test_item as test;
+
+    // A leaf with a byte, or a node with up to two subtrees
+    struct Tree;
+
+    // The sum of the leaves, and the depth
+    impl<BS: Readable> RecursiveParser<Tree, BS> for DefaultInterp {
+        type LevelState<'c>
+            = impl Future<Output = Self::Output> + 'c
+        where
+            BS: 'c;
+        fn parse_level<'a: 'c, 'b: 'c, 'c>(
+            &'b self,
+            input: &'a mut BS,
+            nested: Nested<'b, Self>,
+        ) -> Self::LevelState<'c> {
+            async move {
+                match <DefaultInterp as AsyncParser<ULEB128, BS>>::parse(&DefaultInterp, input)
+                    .await
+                {
+                    0 => (
+                        <DefaultInterp as AsyncParser<Byte, BS>>::parse(&DefaultInterp, input).await
+                            as u32,
+                        1,
+                    ),
+                    1 => {
+                        let children =
+                            <SubInterp<Nested<Self>> as AsyncParser<Vec<Tree, 2>, BS>>::parse(
+                                &SubInterp(nested),
+                                input,
+                            )
+                            .await;
+                        let sum = children.iter().map(|c| c.0).sum();
+                        let depth = children.iter().map(|c| c.1).max().unwrap_or(0);
+                        (sum, depth + 1)
+                    }
+                    _ => reject(PARSE_ERROR_CODE).await,
+                }
+            }
+        }
+    }
+
+    impl HasOutput<Tree> for DefaultInterp {
+        type Output = (u32, usize);
+    }
+
+    fn parse_tree(input: &[u8]) -> Option<(u32, usize)> {
+        let mut bs = TestReadable::new(input);
+        match poll_once(TryFuture(<Recursive<DefaultInterp, 3> as AsyncParser<
+            Tree,
+            _,
+        >>::parse(
+            &Recursive(DefaultInterp), &mut bs
+        ))) {
+            Poll::Ready(rv) => rv,
+            Poll::Pending => panic!("read past the end of the input"),
+        }
+    }
+
+    #[test]
+    fn test_recursive() {
+        assert_eq!(parse_tree(&[0, 7]), Some((7, 1)));
+        assert_eq!(parse_tree(&[1, 2, 0, 1, 0, 2]), Some((3, 2)));
+        assert_eq!(parse_tree(&[1, 1, 1, 1, 0, 5]), Some((5, 3)));
+        assert_eq!(parse_tree(&[1, 2, 0, 1, 1, 1, 1, 1, 0, 5]), None);
+        assert_eq!(parse_tree(&[1, 1, 2]), None);
+    }
+}
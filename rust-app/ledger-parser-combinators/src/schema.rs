@@ -28,3 +28,5 @@ macro_rules! number_parser {
 number_parser! { U16, "16" }
 number_parser! { U32, "32" }
 number_parser! { U64, "64" }
+number_parser! { U128, "128" }
+number_parser! { U256, "256" }
@@ -159,39 +159,44 @@ impl<T: Copy + core::fmt::Debug, const N: usize> Trie<T, N> {
         current_trie
     }
 
-    // Dumb implementation
+    // Drops the slots that build left unused: a Terminal(None) followed by a Terminal. This is
+    // linear in N rather than quadratic, so that tries with long keys stay cheap to build in
+    // const eval.
     pub const fn repack(self) -> Self {
-        let Trie(mut trie) = self;
-        let mut i = 0; // index to the currently-under-consideration point in the trie
-        let mut k = 0; // Extra counter to stop when we've cleared the whole item
-        while i < N - 1 && k < N {
+        let Trie(trie) = self;
+        // removed[i]: number of slots dropped before i, which a pointer to i is moved back by
+        let mut removed = [0; N];
+        let mut dropped = 0;
+        let mut i = 0;
+        while i < N {
+            removed[i] = dropped;
+            if i > 0 && i < N - 1 {
+                if let (TrieInstruction::Terminal(None), TrieInstruction::Terminal(_)) =
+                    (&trie[i], &trie[i + 1])
+                {
+                    dropped += 1;
+                }
+            }
             i += 1;
-            k += 1;
-            if let TrieInstruction::Terminal(None) = trie[i] {
+        }
+
+        let mut rv = [TrieInstruction::Terminal(None); N];
+        let mut i = 0;
+        while i < N {
+            let is_dropped = if i + 1 < N {
+                removed[i + 1] > removed[i]
             } else {
-                continue;
-            }
-            if let TrieInstruction::Terminal(_) = trie[i + 1] {
-                // Now we know that trie[i] is redundant, remove it.
-                let mut j = 0;
-                while j < N {
-                    if let TrieInstruction::Node(item, n) = trie[j] {
-                        if n > i {
-                            trie[j] = TrieInstruction::Node(item, n - 1);
-                        }
-                    }
-                    j += 1;
-                }
-                let mut j = i;
-                while j < N - 1 {
-                    trie[j] = trie[j + 1];
-                    j += 1;
-                }
-                // New instruction in trie[i], we should check it too
-                i -= 1;
+                false
+            };
+            if !is_dropped {
+                rv[i - removed[i]] = match trie[i] {
+                    TrieInstruction::Node(item, n) => TrieInstruction::Node(item, n - removed[n]),
+                    instruction => instruction,
+                };
             }
+            i += 1;
         }
-        Trie(trie)
+        Trie(rv)
     }
 
     pub const fn length(&self) -> usize {
@@ -0,0 +1,290 @@
+use crate::parser::common::{SuiAddressRaw, SUI_ADDRESS_LENGTH};
+use crate::parser::tx::KnownTx;
+use arrayvec::{ArrayString, ArrayVec};
+
+#[cfg(target_family = "bolos")]
+pub use storage::Allowlist;
+
+pub const MAX_ALLOWLIST_ENTRIES: usize = 8;
+pub const ALLOWLIST_LABEL_LENGTH: usize = 20;
+pub type AllowlistLabel = ArrayString<ALLOWLIST_LABEL_LENGTH>;
+
+// Each entry is the address, then the length of the label and the label, padded with 0
+const ENTRY_SIZE: usize = SUI_ADDRESS_LENGTH + 1 + ALLOWLIST_LABEL_LENGTH;
+
+const ALLOWLIST_ONLY_IX: usize = 0;
+const COUNT_IX: usize = 1;
+const ENTRIES_IX: usize = 2;
+const ALLOWLIST_SIZE: usize = ENTRIES_IX + MAX_ALLOWLIST_ENTRIES * ENTRY_SIZE;
+
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct AllowlistData {
+    // Transfers to other recipients are refused, see allows
+    pub allowlist_only: bool,
+    pub entries: ArrayVec<(SuiAddressRaw, AllowlistLabel), MAX_ALLOWLIST_ENTRIES>,
+}
+
+impl AllowlistData {
+    // Entries which are not valid are left out
+    pub fn decode(raw: &[u8; ALLOWLIST_SIZE]) -> Self {
+        let count = core::cmp::min(raw[COUNT_IX] as usize, MAX_ALLOWLIST_ENTRIES);
+        let entries = raw[ENTRIES_IX..]
+            .chunks_exact(ENTRY_SIZE)
+            .take(count)
+            .filter_map(|entry| {
+                let (address, label) = entry.split_at(SUI_ADDRESS_LENGTH);
+                let label = core::str::from_utf8(label.get(1..1 + label[0] as usize)?).ok()?;
+                Some((address.try_into().ok()?, ArrayString::from(label).ok()?))
+            })
+            .collect();
+        AllowlistData {
+            allowlist_only: raw[ALLOWLIST_ONLY_IX] == 1,
+            entries,
+        }
+    }
+
+    pub fn encode(&self) -> [u8; ALLOWLIST_SIZE] {
+        let mut raw = [0u8; ALLOWLIST_SIZE];
+        raw[ALLOWLIST_ONLY_IX] = self.allowlist_only as u8;
+        raw[COUNT_IX] = self.entries.len() as u8;
+        for ((address, label), entry) in self
+            .entries
+            .iter()
+            .zip(raw[ENTRIES_IX..].chunks_exact_mut(ENTRY_SIZE))
+        {
+            entry[..SUI_ADDRESS_LENGTH].copy_from_slice(address);
+            entry[SUI_ADDRESS_LENGTH] = label.len() as u8;
+            entry[SUI_ADDRESS_LENGTH + 1..][..label.len()].copy_from_slice(label.as_bytes());
+        }
+        raw
+    }
+
+    pub fn get_label(&self, address: &SuiAddressRaw) -> Option<AllowlistLabel> {
+        self.entries
+            .iter()
+            .find(|(a, _)| a == address)
+            .map(|(_, label)| *label)
+    }
+
+    // Replaces the label of an address already in the list. Returns false if the list is full.
+    pub fn add(&mut self, address: SuiAddressRaw, label: AllowlistLabel) -> bool {
+        match self.entries.iter_mut().find(|(a, _)| *a == address) {
+            Some(entry) => {
+                entry.1 = label;
+                true
+            }
+            None => self.entries.try_push((address, label)).is_ok(),
+        }
+    }
+
+    // Returns false if the address is not in the list
+    pub fn remove(&mut self, address: &SuiAddressRaw) -> bool {
+        match self.entries.iter().position(|(a, _)| a == address) {
+            Some(i) => {
+                self.entries.remove(i);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Whether a transaction can be signed, None being a transaction which is not clear signed.
+    // In allowlist only mode, only the transfers to recipients in the allowlist, and the
+    // transactions whose coins stay with the sender or are staked, are allowed. The coins given to
+    // a described call, or moved by a blind signed transaction, could go to anyone.
+    pub fn allows(&self, tx: Option<&KnownTx>) -> bool {
+        if !self.allowlist_only {
+            return true;
+        }
+        match tx {
+            Some(KnownTx::TransferTx { recipient, .. }) => self.get_label(recipient).is_some(),
+            Some(
+                KnownTx::StakeTx { .. }
+                | KnownTx::UnstakeTx { .. }
+                | KnownTx::RestakeTx { .. }
+                | KnownTx::CoinManagementTx { .. },
+            ) => true,
+            Some(KnownTx::MoveCallTx { .. }) | None => false,
+        }
+    }
+}
+
+#[cfg(target_family = "bolos")]
+mod storage {
+    use super::*;
+    use ledger_device_sdk::nvm::*;
+    use ledger_device_sdk::NVMData;
+
+    // Kept apart from the settings, whose size is fixed by the settings menu
+    #[link_section = ".nvm_data"]
+    static mut ALLOWLIST: NVMData<AtomicStorage<[u8; ALLOWLIST_SIZE]>> =
+        NVMData::new(AtomicStorage::new(&[0u8; ALLOWLIST_SIZE]));
+
+    #[derive(Clone, Copy, Default)]
+    pub struct Allowlist;
+
+    impl Allowlist {
+        #[inline(never)]
+        pub fn get(&self) -> AllowlistData {
+            let data = &raw const ALLOWLIST;
+            let allowlist = unsafe { (*data).get_ref() };
+            AllowlistData::decode(allowlist.get_ref())
+        }
+
+        // The inline(never) is important, as for the settings
+        #[inline(never)]
+        pub fn set(&mut self, value: &AllowlistData) {
+            let data = &raw mut ALLOWLIST;
+            let allowlist = unsafe { (*data).get_mut() };
+            allowlist.update(&value.encode());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parser::common::SUI_COIN_TYPE;
+    use crate::parser::move_call::DescribedMoveCall;
+
+    extern crate alloc;
+    use alloc::boxed::Box;
+
+    #[cfg(target_family = "bolos")]
+    #[allow(unused_imports)]
+    use ledger_device_sdk::testing::TestType;
+    #[cfg(target_family = "bolos")]
+    use testmacro::test_item as test;
+
+    const FRIEND: SuiAddressRaw = [0x11; 32];
+    const STRANGER: SuiAddressRaw = [0x22; 32];
+
+    fn allowlist(allowlist_only: bool) -> AllowlistData {
+        let mut data = AllowlistData {
+            allowlist_only,
+            ..Default::default()
+        };
+        assert!(data.add(FRIEND, ArrayString::from("Friend").unwrap()));
+        data
+    }
+
+    fn transfer(recipient: SuiAddressRaw) -> KnownTx {
+        KnownTx::TransferTx {
+            recipient,
+            coin_type: SUI_COIN_TYPE,
+            total_amount: 1000000000,
+            gas_budget: 1000000,
+        }
+    }
+
+    fn move_call() -> KnownTx {
+        KnownTx::MoveCallTx {
+            call: Box::new(DescribedMoveCall {
+                label: ArrayString::from("swap coins").unwrap(),
+                args: ArrayVec::new(),
+            }),
+            gas_budget: 1000000,
+        }
+    }
+
+    #[test]
+    fn allowlist_only_transfers() {
+        let data = allowlist(true);
+        assert!(data.allows(Some(&transfer(FRIEND))));
+        assert!(!data.allows(Some(&transfer(STRANGER))));
+    }
+
+    #[test]
+    fn allowlist_only_blind_signing() {
+        assert!(!allowlist(true).allows(None));
+    }
+
+    #[test]
+    fn allowlist_only_described_call() {
+        assert!(!allowlist(true).allows(Some(&move_call())));
+    }
+
+    #[test]
+    fn allowlist_only_own_coins() {
+        let data = allowlist(true);
+        let stake = KnownTx::StakeTx {
+            stakes: [(STRANGER, 1000000000)].into_iter().collect(),
+            total_amount: 1000000000,
+            gas_budget: 1000000,
+        };
+        let unstake = KnownTx::UnstakeTx {
+            total_amount: 1000000000,
+            gas_budget: 1000000,
+        };
+        let restake = KnownTx::RestakeTx {
+            source_stake: [0x33; 32],
+            recipient: STRANGER,
+            total_amount: 1000000000,
+            gas_budget: 1000000,
+        };
+        let merge = KnownTx::CoinManagementTx {
+            coin_type: SUI_COIN_TYPE,
+            input_coin_count: 2,
+            output_amounts: ArrayVec::new(),
+            gas_budget: 1000000,
+        };
+        for tx in [stake, unstake, restake, merge] {
+            assert!(data.allows(Some(&tx)));
+        }
+    }
+
+    #[test]
+    fn allowlist_not_only() {
+        let data = allowlist(false);
+        assert!(data.allows(Some(&transfer(STRANGER))));
+        assert!(data.allows(Some(&move_call())));
+        assert!(data.allows(None));
+    }
+
+    fn full_allowlist() -> AllowlistData {
+        let mut data = allowlist(true);
+        for i in 1..MAX_ALLOWLIST_ENTRIES as u8 {
+            let label = ArrayString::from("Friend of a friend").unwrap();
+            assert!(data.add([0x11 + i; 32], label));
+        }
+        assert!(!data.add(STRANGER, ArrayString::from("Stranger").unwrap()));
+        data
+    }
+
+    fn entry_ix(i: usize) -> usize {
+        ENTRIES_IX + i * ENTRY_SIZE
+    }
+
+    #[test]
+    fn encode_decode() {
+        for data in [AllowlistData::default(), allowlist(false), full_allowlist()] {
+            assert_eq!(AllowlistData::decode(&data.encode()), data);
+        }
+        // A label of the maximum length
+        let mut data = allowlist(false);
+        assert!(data.add(STRANGER, ArrayString::from("A stranger, or not ?").unwrap()));
+        assert_eq!(AllowlistData::decode(&data.encode()), data);
+    }
+
+    #[test]
+    fn decode_count_above_max() {
+        let data = full_allowlist();
+        let mut raw = data.encode();
+        raw[COUNT_IX] = 0xff;
+        assert_eq!(AllowlistData::decode(&raw), data);
+    }
+
+    #[test]
+    fn decode_corrupt_labels() {
+        let data = full_allowlist();
+        let mut raw = data.encode();
+        // A label longer than the maximum, and a label which is not UTF-8
+        raw[entry_ix(1) + SUI_ADDRESS_LENGTH] = ALLOWLIST_LABEL_LENGTH as u8 + 1;
+        raw[entry_ix(2) + SUI_ADDRESS_LENGTH + 1] = 0xff;
+        let decoded = AllowlistData::decode(&raw);
+        assert_eq!(decoded.entries.len(), MAX_ALLOWLIST_ENTRIES - 2);
+        assert_eq!(decoded.entries[0], data.entries[0]);
+        assert_eq!(decoded.entries[1..], data.entries[3..]);
+        assert!(decoded.allowlist_only);
+    }
+}
@@ -45,6 +45,8 @@ pub fn app_main(ctx: &RunCtx) {
     let ctx: &'static _ = unsafe { &*(ctx as *const RunCtx) };
 
     let mut settings = Settings;
+    // The settings menu reads the NVM directly, so it must be in the current layout
+    settings.migrate();
 
     // Initialize reference to Comm instance for NBGL
     // API calls.
@@ -57,13 +59,29 @@ pub fn app_main(ctx: &RunCtx) {
         core::mem::size_of::<Option<APDUsFuture>>()
     );
 
-    let settings_strings = [[
-        "Blind Signing",
-        #[cfg(any(target_os = "stax", target_os = "flex", target_os = "apex_p"))]
-        "Sign transactions for which details cannot be verified",
-        #[cfg(any(target_os = "nanosplus", target_os = "nanox"))]
-        "Transactions",
-    ]];
+    let settings_strings = [
+        [
+            "Blind Signing",
+            #[cfg(any(target_os = "stax", target_os = "flex", target_os = "apex_p"))]
+            "Sign transactions for which details cannot be verified",
+            #[cfg(any(target_os = "nanosplus", target_os = "nanox"))]
+            "Transactions",
+        ],
+        [
+            "Expert mode",
+            #[cfg(any(target_os = "stax", target_os = "flex", target_os = "apex_p"))]
+            "Show gas, expiration, input objects and digest of transactions",
+            #[cfg(any(target_os = "nanosplus", target_os = "nanox"))]
+            "Transaction details",
+        ],
+        [
+            "High value warning",
+            #[cfg(any(target_os = "stax", target_os = "flex", target_os = "apex_p"))]
+            "Confirm again transfers above a threshold, in whole units of each coin",
+            #[cfg(any(target_os = "nanosplus", target_os = "nanox"))]
+            "Large transfers",
+        ],
+    ];
 
     let main_menu = SingleThreaded(RefCell::new(
         NbglHomeAndSettings::new()
@@ -14,18 +14,8 @@ pub trait Address<A, K>: fmt::Display {
     fn get_binary_address(&self) -> &[u8];
 }
 
-pub struct HexSlice<'a>(pub &'a [u8]);
-
-// You can choose to implement multiple traits, like Lower and UpperHex
-impl fmt::Display for HexSlice<'_> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for byte in self.0 {
-            // Decide if you want to pad the value or have spaces inbetween, etc.
-            write!(f, "{:02x}", byte)?;
-        }
-        Ok(())
-    }
-}
+// Also used by the parts of the app which are built on the host
+pub use crate::utils::HexSlice;
 
 #[derive(Debug)]
 pub enum CryptographyError {
@@ -1,4 +1,6 @@
 use crate::parser::common::{COIN_STRING_LENGTH, SUI_ADDRESS_LENGTH, SUI_COIN_DECIMALS};
+use crate::parser::move_call::MoveCallDescriptor;
+#[cfg(target_family = "bolos")]
 use crate::swap::params::TxParams;
 
 use arrayvec::ArrayString;
@@ -17,27 +19,34 @@ pub enum State {
 
 pub struct RunCtx {
     state: Cell<State>,
+    // Swap is only available on device
+    #[cfg(target_family = "bolos")]
     tx_params: TxParams,
     token_coin_id: Cell<[u8; SUI_ADDRESS_LENGTH]>,
     token_coin_module: Cell<ArrayString<COIN_STRING_LENGTH>>,
     token_coin_function: Cell<ArrayString<COIN_STRING_LENGTH>>,
     token_divisor: Cell<u8>,
     token_ticker: Cell<ArrayString<TICKER_LENGTH>>,
+    // Used by the next transaction to sign only
+    move_call_descriptor: Cell<Option<MoveCallDescriptor>>,
 }
 
 impl RunCtx {
     pub fn app() -> Self {
         RunCtx {
             state: Cell::new(State::App),
+            #[cfg(target_family = "bolos")]
             tx_params: TxParams::default(),
             token_coin_id: Cell::new([0; SUI_ADDRESS_LENGTH]),
             token_coin_module: Cell::new(ArrayString::zero_filled()),
             token_coin_function: Cell::new(ArrayString::zero_filled()),
             token_divisor: Cell::new(SUI_COIN_DECIMALS),
             token_ticker: Cell::new(ArrayString::zero_filled()),
+            move_call_descriptor: Cell::new(None),
         }
     }
 
+    #[cfg(target_family = "bolos")]
     pub fn lib_swap(tx_params: TxParams) -> Self {
         RunCtx {
             state: Cell::new(State::LibSwapIdle),
@@ -47,6 +56,7 @@ impl RunCtx {
             token_coin_function: Cell::new(ArrayString::zero_filled()),
             token_divisor: Cell::new(SUI_COIN_DECIMALS),
             token_ticker: Cell::new(ArrayString::zero_filled()),
+            move_call_descriptor: Cell::new(None),
         }
     }
 
@@ -78,6 +88,7 @@ impl RunCtx {
     }
 
     // Panics if not in swap mode
+    #[cfg(target_family = "bolos")]
     pub fn get_swap_tx_params(&self) -> &TxParams {
         assert!(self.is_swap(), "attempt to get swap tx params in app mode");
         &self.tx_params
@@ -117,4 +128,12 @@ impl RunCtx {
     pub fn get_token_ticker(&self) -> ArrayString<TICKER_LENGTH> {
         self.token_ticker.get()
     }
+
+    pub fn set_move_call_descriptor(&self, descriptor: MoveCallDescriptor) {
+        self.move_call_descriptor.set(Some(descriptor));
+    }
+
+    pub fn take_move_call_descriptor(&self) -> Option<MoveCallDescriptor> {
+        self.move_call_descriptor.take()
+    }
 }
@@ -48,6 +48,26 @@ pub fn handle_apdu_async(
                 trace!("Handling provide trusted dynamic descriptor");
                 NoinlineFut(validate_tlv(io, ctx)).await;
             }
+            Ins::ProvideMoveCallDescriptor => {
+                trace!("Handling provide Move call descriptor");
+                NoinlineFut(provide_move_call_descriptor(io, ctx)).await;
+            }
+            Ins::SetHighValueThreshold => {
+                trace!("Handling set high value threshold");
+                NoinlineFut(set_high_value_threshold_apdu(io, ctx, settings, ui)).await;
+            }
+            Ins::AddAllowlistEntry => {
+                trace!("Handling add allowlist entry");
+                NoinlineFut(add_allowlist_entry_apdu(io, ctx, ui)).await;
+            }
+            Ins::RemoveAllowlistEntry => {
+                trace!("Handling remove allowlist entry");
+                NoinlineFut(remove_allowlist_entry_apdu(io, ctx, ui)).await;
+            }
+            Ins::SetAllowlistOnly => {
+                trace!("Handling set allowlist only");
+                NoinlineFut(set_allowlist_only_apdu(io, ctx, ui)).await;
+            }
             Ins::GetVersionStr => {}
             Ins::Exit if ctx.is_swap() => unsafe { ledger_device_sdk::sys::os_lib_end() },
             Ins::Exit => ledger_device_sdk::exit_app(0),
@@ -1,21 +1,29 @@
+use crate::allowlist::{Allowlist, AllowlistData, AllowlistLabel, ALLOWLIST_LABEL_LENGTH};
 use crate::crypto_helpers::common::{try_option, Address};
 use crate::crypto_helpers::eddsa::{ed25519_public_key_bytes, eddsa_sign, with_public_keys};
 use crate::crypto_helpers::hasher::HexHash;
 use crate::ctx::{RunCtx, TICKER_LENGTH};
 use crate::interface::*;
 use crate::parser::common::{
-    CoinType, HasObjectData, ObjectData, ObjectDigest, COIN_STRING_LENGTH,
+    CoinType, HasObjectData, ObjectData, ObjectDigest, SuiAddressRaw, COIN_STRING_LENGTH,
 };
+use crate::parser::move_call_descriptor::{parse_move_call_descriptor, DescriptorError};
 use crate::parser::object::{compute_object_hash, object_parser};
 use crate::parser::tuid::{parse_tuid, Tuid};
-use crate::parser::tx::{tx_parser, KnownTx};
+use crate::parser::tx::{tx_parser, KnownTx, TxDetails};
 use crate::settings::*;
 use crate::swap;
 use crate::swap::params::TxParams;
+use crate::ui::common::get_known_coin_ticker;
+use crate::ui::review::{
+    add_recipient_label, allowlist_entry_review, high_value_warning, known_tx_review,
+};
 use crate::ui::*;
+use crate::utils::swap_checks::check_coin_type;
 use crate::utils::*;
 use alamgu_async_block::*;
 use arrayvec::{ArrayString, ArrayVec};
+use ledger_device_sdk::hash::sha2::Sha2_256;
 use ledger_device_sdk::hash::HashInit;
 use ledger_device_sdk::io::{StatusWords, SyscallError};
 use ledger_device_sdk::log::{info, trace};
@@ -82,19 +90,35 @@ pub async fn get_address_apdu(io: HostIO, ui: UserInterface, prompt: bool) {
     io.result_final(&rv).await;
 }
 
-async fn prompt_tx_params(
+async fn prompt_tx_review(
     ui: &UserInterface,
     path: &[u32],
-    TxParams {
-        amount,
-        fee,
-        destination_address,
-    }: TxParams,
-    coin_type: CoinType,
+    tx: &KnownTx,
+    expert: Option<(&TxDetails, &HexHash<32>)>,
     ctx: &RunCtx,
+    settings: Settings,
 ) {
+    let warning = settings
+        .get_high_value_threshold()
+        .and_then(|threshold| high_value_warning(tx, threshold, ctx));
+    let recipient_label = match tx {
+        KnownTx::TransferTx { recipient, .. } => Allowlist.get().get_label(recipient),
+        _ => None,
+    };
     if with_public_keys(path, true, |_, address: &SuiPubKeyAddress| {
-        try_option(ui.confirm_sign_tx(address, destination_address, amount, coin_type, fee, ctx))
+        let sender = address.get_binary_address().try_into().ok();
+        try_option(sender.and_then(|sender| {
+            let mut review = known_tx_review(
+                sender,
+                tx,
+                expert.map(|(details, hash)| (details, &hash.0)),
+                ctx,
+            );
+            if let Some(label) = &recipient_label {
+                add_recipient_label(&mut review, label);
+            }
+            ui.confirm_tx_review(&review, warning.as_deref())
+        }))
     })
     .ok()
     .is_none()
@@ -102,8 +126,22 @@ async fn prompt_tx_params(
         reject::<()>(StatusWords::UserCancelled as u16).await;
     };
 }
-async fn check_tx_params(expected: &TxParams, received: &TxParams) {
-    if !swap::check_tx_params(expected, received) {
+async fn check_tx_params(
+    expected: &TxParams,
+    received: &TxParams,
+    coin_type: &CoinType,
+    ctx: &RunCtx,
+) {
+    let ticker = get_known_coin_ticker(coin_type, ctx);
+    if !swap::check_tx_params(expected, received)
+        || !check_coin_type(
+            expected
+                .coin_config
+                .as_ref()
+                .map(|c| (c.ticker.as_str(), c.decimals)),
+            ticker.as_ref().map(|(t, d)| (t.as_str(), *d)),
+        )
+    {
         reject::<()>(SW_SWAP_TX_PARAM_MISMATCH).await;
     }
 }
@@ -128,27 +166,75 @@ pub async fn sign_apdu(io: HostIO, ctx: &RunCtx, settings: Settings, ui: UserInt
 
     info!("apdu sign tx length: {}\n", length);
 
-    let known_txn = {
-        let mut txn = input[0].clone();
+    // Also returns how far the parser read into the transaction
+    #[cfg_attr(not(feature = "speculos"), allow(unused_variables))]
+    let (known_txn, offset) = {
+        let mut txn = LengthTrack(input[0].clone(), 0);
         let object_data_source = input.get(2).map(|bs| WithObjectData { bs: bs.clone() });
+        // A descriptor only applies to the transaction that follows it
+        let move_call_descriptor = ctx.take_move_call_descriptor();
         NoinlineFut(async move {
             info!("Beginning tx_parse");
-            TryFuture(tx_parser(object_data_source).parse(&mut txn)).await
+            let rv = TryResultFuture(
+                tx_parser(object_data_source, move_call_descriptor.as_ref()).parse(&mut txn),
+            )
+            .await;
+            (rv, txn.index())
         })
         .await
     };
 
     info!("End of tx_parse");
 
+    #[cfg_attr(not(feature = "speculos"), allow(unused_variables))]
+    let (known_txn, tx_details, parse_error) = match known_txn {
+        Ok((tx, details)) => (Some(tx), Some(details), None),
+        Err(e) => (None, None, Some(e)),
+    };
     let is_unknown_txn = known_txn.is_none();
 
-    match known_txn {
-        Some(KnownTx::TransferTx {
-            recipient,
-            total_amount,
-            coin_type,
-            gas_budget,
-        }) => {
+    // The digest is computed before the review, as it is shown in expert mode
+    let hash: HexHash<32> = NoinlineFut(async {
+        let mut hasher = ledger_device_sdk::hash::blake2::Blake2b_256::new();
+        {
+            let mut txn = input[0].clone();
+            const CHUNK_SIZE: usize = 128;
+            let mut buf = [0; CHUNK_SIZE];
+            let mut remaining = length;
+            while remaining > 0 {
+                let chunk = &mut buf[..core::cmp::min(remaining, CHUNK_SIZE)];
+                txn.read_into(chunk).await;
+                let _ = hasher.update(chunk);
+                remaining -= chunk.len();
+            }
+        }
+        let mut hash: HexHash<32> = Default::default();
+        let _ = hasher.finalize(&mut hash.0);
+        hash
+    })
+    .await;
+
+    let expert = if settings.get_expert_mode() {
+        tx_details.as_ref().map(|details| (details, &hash))
+    } else {
+        None
+    };
+
+    // Swap transactions are checked against the swap parameters instead
+    if !ctx.is_swap() && !Allowlist.get().allows(known_txn.as_ref()) {
+        info!("Tx not allowed in allowlist only mode\n");
+        reject::<()>(SW_RECIPIENT_NOT_ALLOWLISTED).await;
+    }
+
+    match &known_txn {
+        Some(
+            tx @ KnownTx::TransferTx {
+                recipient,
+                total_amount,
+                coin_type,
+                gas_budget,
+            },
+        ) => {
             info!("Known transfer tx\n");
             let mut bs = input[1].clone();
             let path = BIP_PATH_PARSER.parse(&mut bs).await;
@@ -156,33 +242,36 @@ pub async fn sign_apdu(io: HostIO, ctx: &RunCtx, settings: Settings, ui: UserInt
                 reject::<()>(SyscallError::InvalidParameter as u16).await;
             }
 
-            let tx_params = TxParams {
-                amount: total_amount,
-                fee: gas_budget,
-                destination_address: recipient,
-            };
-
             if ctx.is_swap() {
+                let tx_params = TxParams {
+                    amount: *total_amount,
+                    fee: *gas_budget,
+                    destination_address: *recipient,
+                    coin_config: None,
+                };
                 let expected = ctx.get_swap_tx_params();
-                check_tx_params(expected, &tx_params).await;
+                check_tx_params(expected, &tx_params, coin_type, ctx).await;
             } else {
                 // Show prompts after all inputs have been parsed
-                NoinlineFut(prompt_tx_params(
+                NoinlineFut(prompt_tx_review(
                     &ui,
                     path.as_slice(),
-                    tx_params,
-                    coin_type,
+                    tx,
+                    expert,
                     ctx,
+                    settings,
                 ))
                 .await;
             }
         }
-        Some(KnownTx::StakeTx {
-            recipient,
-            total_amount,
-            gas_budget,
-        }) => {
-            info!("Known stake tx\n");
+        Some(
+            tx @ (KnownTx::StakeTx { .. }
+            | KnownTx::UnstakeTx { .. }
+            | KnownTx::RestakeTx { .. }
+            | KnownTx::CoinManagementTx { .. }
+            | KnownTx::MoveCallTx { .. }),
+        ) => {
+            info!("Known staking, coin management or described Move call tx\n");
             if ctx.is_swap() {
                 reject::<()>(SyscallError::NotSupported as u16).await;
             }
@@ -192,40 +281,24 @@ pub async fn sign_apdu(io: HostIO, ctx: &RunCtx, settings: Settings, ui: UserInt
                 reject::<()>(SyscallError::InvalidParameter as u16).await;
             }
 
-            if with_public_keys(&path, true, |_, address: &SuiPubKeyAddress| {
-                try_option(ui.confirm_stake_tx(address, recipient, total_amount, gas_budget))
-            })
-            .ok()
-            .is_none()
-            {
-                reject::<()>(StatusWords::UserCancelled as u16).await;
-            };
-        }
-        Some(KnownTx::UnstakeTx {
-            total_amount,
-            gas_budget,
-        }) => {
-            info!("Known unstake tx\n");
-            if ctx.is_swap() {
-                reject::<()>(SyscallError::NotSupported as u16).await;
-            }
-            let mut bs = input[1].clone();
-            let path = BIP_PATH_PARSER.parse(&mut bs).await;
-            if !path.starts_with(&BIP32_PREFIX[0..2]) {
-                reject::<()>(SyscallError::InvalidParameter as u16).await;
-            }
-
-            if with_public_keys(&path, true, |_, address: &SuiPubKeyAddress| {
-                try_option(ui.confirm_unstake_tx(address, total_amount, gas_budget))
-            })
-            .ok()
-            .is_none()
-            {
-                reject::<()>(StatusWords::UserCancelled as u16).await;
-            };
+            NoinlineFut(prompt_tx_review(
+                &ui,
+                path.as_slice(),
+                tx,
+                expert,
+                ctx,
+                settings,
+            ))
+            .await;
         }
         None => {
             info!("Unknown tx\n");
+            #[cfg(feature = "speculos")]
+            if let Some(e) = &parse_error {
+                if ctx.is_swap() || !settings.get_blind_sign() {
+                    append_parse_error(io, e, offset);
+                }
+            }
             if ctx.is_swap() {
                 // Reject unknown transactions in swap mode
                 reject::<()>(SyscallError::NotSupported as u16).await;
@@ -237,23 +310,6 @@ pub async fn sign_apdu(io: HostIO, ctx: &RunCtx, settings: Settings, ui: UserInt
     }
 
     NoinlineFut(async move {
-        let mut hasher = ledger_device_sdk::hash::blake2::Blake2b_256::new();
-        {
-            let mut txn = input[0].clone();
-            const CHUNK_SIZE: usize = 128;
-            let (chunks, rem) = (length / CHUNK_SIZE, length % CHUNK_SIZE);
-            for _ in 0..chunks {
-                let b: [u8; CHUNK_SIZE] = txn.read().await;
-                let _ = hasher.update(&b);
-            }
-            for _ in 0..rem {
-                let b: [u8; 1] = txn.read().await;
-                let _ = hasher.update(&b);
-            }
-        }
-        let mut hash: HexHash<32> = Default::default();
-        let _ = hasher.finalize(&mut hash.0);
-
         if is_unknown_txn {
             // Show prompts after all inputs have been parsed
             if ui.confirm_blind_sign_tx(&hash).is_none() {
@@ -276,6 +332,22 @@ pub async fn sign_apdu(io: HostIO, ctx: &RunCtx, settings: Settings, ui: UserInt
     ctx.set_swap_sign_success();
 }
 
+// Tells the host where the transaction was rejected, in the response data: how many bytes of the
+// transaction had been read, as a u32 LE, then the path of the field being parsed, e.g.
+// `TransactionData.V1.Kind.PTB.commands[3].MoveCall.args[1]`. The path is empty if the whole
+// transaction parsed, but is not one we can review.
+#[cfg(feature = "speculos")]
+fn append_parse_error(io: HostIO, error: &ParseError, offset: usize) {
+    use core::fmt::Write;
+    let mut path: ArrayString<200> = ArrayString::new();
+    // Cut short if too long
+    let _ = write!(path, "{}", error.path);
+    if let Ok(mut comm) = io.get_comm() {
+        comm.append(&(offset as u32).to_le_bytes());
+        comm.append(path.as_bytes());
+    }
+}
+
 pub async fn validate_tlv(io: HostIO, ctx: &RunCtx) {
     const TLV_ERROR_OFFSET: u16 = 0x7000;
 
@@ -295,16 +367,14 @@ pub async fn validate_tlv(io: HostIO, ctx: &RunCtx) {
 
     let mut b_arr: ArrayVec<u8, 1024> = ArrayVec::new();
 
-    const CHUNK_SIZE: usize = 10;
-    let (chunks, rem) = (length as usize / CHUNK_SIZE, length as usize % CHUNK_SIZE);
-    for _ in 0..chunks {
-        let b: [u8; CHUNK_SIZE] = tlv.read().await;
-        let _ = b_arr.try_extend_from_slice(&b);
-    }
-
-    for _ in 0..rem {
-        let b: [u8; 1] = tlv.read().await;
-        let _ = b_arr.try_extend_from_slice(&b);
+    const CHUNK_SIZE: usize = 64;
+    let mut buf = [0; CHUNK_SIZE];
+    let mut remaining = length as usize;
+    while remaining > 0 {
+        let chunk = &mut buf[..core::cmp::min(remaining, CHUNK_SIZE)];
+        tlv.read_into(chunk).await;
+        let _ = b_arr.try_extend_from_slice(chunk);
+        remaining -= chunk.len();
     }
 
     let mut out = DynamicTokenOut::default();
@@ -370,6 +440,220 @@ pub async fn validate_tlv(io: HostIO, ctx: &RunCtx) {
     io.result_final(&[]).await;
 }
 
+// Sets the threshold of the high value warning, in whole coins, or turns it off with 0
+pub async fn set_high_value_threshold_apdu(
+    io: HostIO,
+    ctx: &RunCtx,
+    mut settings: Settings,
+    ui: UserInterface,
+) {
+    if ctx.is_swap() {
+        reject::<()>(SyscallError::NotSupported as u16).await;
+    }
+
+    let mut input = match io.get_params::<1>() {
+        Some(bs) => bs,
+        None => reject(SyscallError::InvalidParameter as u16).await,
+    };
+
+    let threshold = u32::from_le_bytes(input[0].read().await);
+    let threshold = (threshold != 0).then_some(threshold);
+    if ui.confirm_high_value_threshold(threshold).is_none() {
+        reject::<()>(StatusWords::UserCancelled as u16).await;
+    }
+    settings.set_high_value_threshold(threshold);
+
+    io.result_final(&[]).await;
+}
+
+// Adds a recipient to the allowlist, or changes its label
+pub async fn add_allowlist_entry_apdu(io: HostIO, ctx: &RunCtx, ui: UserInterface) {
+    if ctx.is_swap() {
+        reject::<()>(SyscallError::NotSupported as u16).await;
+    }
+
+    let mut input = match io.get_params::<1>() {
+        Some(bs) => bs,
+        None => reject(SyscallError::InvalidParameter as u16).await,
+    };
+
+    let address: SuiAddressRaw = input[0].read().await;
+    let length = u8::from_le_bytes(input[0].read().await) as usize;
+    let mut buf = [0; ALLOWLIST_LABEL_LENGTH];
+    if length == 0 || length > buf.len() {
+        reject::<()>(SyscallError::InvalidParameter as u16).await;
+    }
+    input[0].read_into(&mut buf[..length]).await;
+    let label = match core::str::from_utf8(&buf[..length])
+        .ok()
+        .and_then(|label| AllowlistLabel::from(label).ok())
+    {
+        Some(label) => label,
+        None => reject(SyscallError::InvalidParameter as u16).await,
+    };
+
+    let mut allowlist = Allowlist;
+    let mut data = allowlist.get();
+    if !data.add(address, label) {
+        info!("Allowlist is full\n");
+        reject::<()>(SyscallError::NotSupported as u16).await;
+    }
+    let review = allowlist_entry_review(true, &address, label.as_str());
+    if ui.confirm_allowlist_entry(&review).is_none() {
+        reject::<()>(StatusWords::UserCancelled as u16).await;
+    }
+    allowlist.set(&data);
+
+    io.result_final(&[]).await;
+}
+
+pub async fn remove_allowlist_entry_apdu(io: HostIO, ctx: &RunCtx, ui: UserInterface) {
+    if ctx.is_swap() {
+        reject::<()>(SyscallError::NotSupported as u16).await;
+    }
+
+    let mut input = match io.get_params::<1>() {
+        Some(bs) => bs,
+        None => reject(SyscallError::InvalidParameter as u16).await,
+    };
+
+    let address: SuiAddressRaw = input[0].read().await;
+    let mut allowlist = Allowlist;
+    let mut data = allowlist.get();
+    let label = match data.get_label(&address) {
+        Some(label) => label,
+        None => reject(SyscallError::InvalidParameter as u16).await,
+    };
+    data.remove(&address);
+    let review = allowlist_entry_review(false, &address, label.as_str());
+    if ui.confirm_allowlist_entry(&review).is_none() {
+        reject::<()>(StatusWords::UserCancelled as u16).await;
+    }
+    allowlist.set(&data);
+
+    io.result_final(&[]).await;
+}
+
+// In allowlist only mode, transfers to recipients not in the allowlist are refused, see
+// AllowlistData::allows
+pub async fn set_allowlist_only_apdu(io: HostIO, ctx: &RunCtx, ui: UserInterface) {
+    if ctx.is_swap() {
+        reject::<()>(SyscallError::NotSupported as u16).await;
+    }
+
+    let mut input = match io.get_params::<1>() {
+        Some(bs) => bs,
+        None => reject(SyscallError::InvalidParameter as u16).await,
+    };
+
+    let mode: [u8; 1] = input[0].read().await;
+    let allowlist_only = match mode {
+        [0] => false,
+        [1] => true,
+        _ => reject(SyscallError::InvalidParameter as u16).await,
+    };
+    if ui.confirm_allowlist_only(allowlist_only).is_none() {
+        reject::<()>(StatusWords::UserCancelled as u16).await;
+    }
+    let mut allowlist = Allowlist;
+    let data = AllowlistData {
+        allowlist_only,
+        ..allowlist.get()
+    };
+    allowlist.set(&data);
+
+    io.result_final(&[]).await;
+}
+
+// Key usage of the PKI certificate used to sign the Move call descriptors
+const CERTIFICATE_PUBLIC_KEY_USAGE_CALLDATA: u8 = 0x0b;
+
+pub async fn provide_move_call_descriptor(io: HostIO, ctx: &RunCtx) {
+    const DESCRIPTOR_ERROR_OFFSET: u16 = 0x7100;
+
+    let mut input = match io.get_params::<1>() {
+        Some(bs) => bs,
+        None => reject(SyscallError::InvalidParameter as u16).await,
+    };
+
+    let length = u16::from_le_bytes(input[0].read().await) as usize;
+
+    let mut b_arr: ArrayVec<u8, 1024> = ArrayVec::new();
+    if length > b_arr.capacity() {
+        reject::<()>(SyscallError::InvalidParameter as u16).await;
+    }
+
+    const CHUNK_SIZE: usize = 64;
+    let mut buf = [0; CHUNK_SIZE];
+    let mut remaining = length;
+    while remaining > 0 {
+        let chunk = &mut buf[..core::cmp::min(remaining, CHUNK_SIZE)];
+        input[0].read_into(chunk).await;
+        let _ = b_arr.try_extend_from_slice(chunk);
+        remaining -= chunk.len();
+    }
+
+    let descriptor = match parse_move_call_descriptor(&b_arr) {
+        Ok((descriptor, signed_data, signature)) => {
+            if !check_descriptor_signature(signed_data, signature) {
+                trace!("Move call descriptor signature check failed\n");
+                reject::<()>(DESCRIPTOR_ERROR_OFFSET + DescriptorError::WrongSignature as u16)
+                    .await;
+            }
+            descriptor
+        }
+        Err(err) => {
+            trace!("Move call descriptor parsing failed: {}\n", err as u8);
+            trace!("descriptor data: {}\n", HexSlice(&b_arr));
+            reject(DESCRIPTOR_ERROR_OFFSET + err as u16).await
+        }
+    };
+
+    trace!(
+        "Move call descriptor: {}::{}::{}\n",
+        HexSlice(&descriptor.package),
+        descriptor.module.as_str(),
+        descriptor.function.as_str(),
+    );
+
+    ctx.set_move_call_descriptor(descriptor);
+
+    io.result_final(&[]).await;
+}
+
+// Checks the signature against the key of the PKI certificate loaded by the host
+fn check_descriptor_signature(signed_data: &[u8], signature: &[u8]) -> bool {
+    let mut hash = [0; 32];
+    let mut hasher = Sha2_256::new();
+    if hasher.update(signed_data).is_err() || hasher.finalize(&mut hash).is_err() {
+        return false;
+    }
+
+    let mut key_usage: u8 = 0;
+    let mut trusted_name = [0u8; 64];
+    let mut trusted_name_len: usize = 0;
+    unsafe {
+        let mut public_key: ledger_device_sdk::sys::cx_ecfp_384_public_key_t = core::mem::zeroed();
+        if ledger_device_sdk::sys::os_pki_get_info(
+            &mut key_usage,
+            trusted_name.as_mut_ptr(),
+            &mut trusted_name_len,
+            &mut public_key,
+        ) != 0
+            || key_usage != CERTIFICATE_PUBLIC_KEY_USAGE_CALLDATA
+        {
+            return false;
+        }
+        ledger_device_sdk::sys::cx_ecdsa_verify_no_throw(
+            &public_key as *const _ as *const ledger_device_sdk::sys::cx_ecfp_public_key_t,
+            hash.as_ptr(),
+            hash.len(),
+            signature.as_ptr(),
+            signature.len(),
+        )
+    }
+}
+
 #[derive(Clone)]
 struct WithObjectData {
     bs: ByteStream,
@@ -53,6 +53,11 @@ pub enum Ins {
     GetPubkey = 2,
     Sign = 3,
     ProvideTrustedDynamicDescriptor = 0x22,
+    ProvideMoveCallDescriptor = 0x23,
+    SetHighValueThreshold = 0x24,
+    AddAllowlistEntry = 0x25,
+    RemoveAllowlistEntry = 0x26,
+    SetAllowlistOnly = 0x27,
     GetVersionStr = 0xfe,
     Exit = 0xff,
 }
@@ -74,3 +79,7 @@ impl TryFrom<ApduHeader> for Ins {
 
 // Status word used when swap transaction parameters check failed
 pub const SW_SWAP_TX_PARAM_MISMATCH: u16 = 0x6e05;
+
+// Status word used when a transaction is refused in allowlist only mode, as its recipient is not
+// in the allowlist, or not known
+pub const SW_RECIPIENT_NOT_ALLOWLISTED: u16 = 0x6e06;
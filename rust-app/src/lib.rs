@@ -1,4 +1,4 @@
-#![no_std]
+#![cfg_attr(target_family = "bolos", no_std)]
 #![feature(stmt_expr_attributes)]
 #![feature(adt_const_params)]
 #![feature(type_alias_impl_trait)]
@@ -25,13 +25,17 @@ extern "C" fn sample_main() {
     exit_app(0);
 }
 
+// Only the parser, utils, ui, ctx, settings and allowlist modules are built on other
+// targets, so that they can be tested with `cargo test` on the host
+#[cfg(target_family = "bolos")]
 pub mod interface;
 
-#[cfg(target_family = "bolos")]
 pub mod utils;
 
+#[cfg(target_family = "bolos")]
 pub mod handle_apdu;
 
+#[cfg(target_family = "bolos")]
 pub mod implementation;
 
 #[cfg(target_family = "bolos")]
@@ -39,13 +43,16 @@ pub mod crypto_helpers;
 
 pub mod parser;
 
-#[cfg(target_family = "bolos")]
 pub mod settings;
 
+pub mod allowlist;
+
 pub mod ui;
 
+#[cfg(target_family = "bolos")]
 pub mod app_main;
 
+#[cfg(target_family = "bolos")]
 pub mod swap;
 
 pub mod ctx;
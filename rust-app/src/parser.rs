@@ -1,4 +1,10 @@
 pub mod common;
+pub mod move_call;
+pub mod move_call_descriptor;
 pub mod object;
+#[cfg(target_family = "bolos")]
 pub mod tuid;
 pub mod tx;
+
+#[cfg(test)]
+pub mod testing;
@@ -4,6 +4,28 @@ use ledger_parser_combinators::async_parser::*;
 use ledger_parser_combinators::core_parsers::*;
 use ledger_parser_combinators::interp::*;
 
+// The parsers are also built on the host for testing, where the SDK is not available
+#[cfg(target_family = "bolos")]
+pub use ledger_device_sdk::io::SyscallError;
+#[cfg(target_family = "bolos")]
+pub use ledger_device_sdk::log::info;
+
+// Only the error codes used by the parsers
+#[cfg(not(target_family = "bolos"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u16)]
+pub enum SyscallError {
+    NotSupported = 0x8,
+}
+
+// Like the SDK logs without the speculos feature, the arguments are not evaluated
+#[cfg(not(target_family = "bolos"))]
+macro_rules! info {
+    ($($arg:tt)*) => {{}};
+}
+#[cfg(not(target_family = "bolos"))]
+pub(crate) use info;
+
 // Schema
 pub type ObjectRefSchema = (ObjectID, SequenceNumber, ObjectDigestSchema);
 
@@ -21,6 +43,8 @@ pub type Amount = U64LE;
 
 pub type U64LE = U64<{ Endianness::Little }>;
 pub type U16LE = U16<{ Endianness::Little }>;
+pub type U128LE = U128<{ Endianness::Little }>;
+pub type U256LE = U256<{ Endianness::Little }>;
 
 pub type Sha3_256Hash = Array<Byte, 33>;
 
@@ -45,6 +69,8 @@ pub const SUI_COIN_ID: CoinID = [
     0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2,
 ];
 
+pub const SUI_FRAMEWORK_ID: CoinID = SUI_COIN_ID;
+
 pub const SUI_SYSTEM_ID: CoinID = [
     0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 3,
 ];
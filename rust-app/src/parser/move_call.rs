@@ -0,0 +1,263 @@
+// Registry of the Move functions whose calls the app can review
+//
+// A target is registered with its "package::module::function" name, the handler of its calls in
+// tx.rs, and the layout of its arguments, which is checked before the handler runs.
+//
+// Calls to other functions, e.g. of dApps, can be reviewed with a Move call descriptor signed by
+// Ledger, which gives the labels of the call and of its arguments, and how to display them.
+use crate::parser::common::{CoinID, CoinType, SuiAddressRaw};
+use crate::parser::tx::STRING_LENGTH;
+use arrayvec::{ArrayString, ArrayVec};
+use trie_enum::static_trie;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MoveCallHandler {
+    CoinFromBalance,
+    RequestAddStake,
+    RequestAddStakeMulCoin,
+    RequestWithdrawStake,
+    RequestWithdrawStakeNonEntry,
+    StakingPoolSplit,
+}
+
+/// An argument of a Move call, not counting the implicit `&mut TxContext`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MoveArg {
+    /// The shared `0x5` SuiSystemState object
+    SuiSystemState,
+    /// A pure address input
+    Address,
+    /// A pure u64 input
+    Amount,
+    /// A pure `Option<u64>` input
+    OptionalAmount,
+    /// A coin, vector of coins, balance or StakedSui, which the handler resolves
+    Value,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct MoveCallTarget {
+    pub handler: MoveCallHandler,
+    /// Number of type arguments
+    pub type_args: usize,
+    pub args: &'static [MoveArg],
+}
+
+const MOVE_CALL_TARGET_PAIRS: [(&[u8], MoveCallTarget); 6] = [
+    (
+        b"0x2::coin::from_balance",
+        MoveCallTarget {
+            handler: MoveCallHandler::CoinFromBalance,
+            type_args: 1,
+            args: &[MoveArg::Value],
+        },
+    ),
+    (
+        b"0x3::sui_system::request_add_stake",
+        MoveCallTarget {
+            handler: MoveCallHandler::RequestAddStake,
+            type_args: 0,
+            args: &[MoveArg::SuiSystemState, MoveArg::Value, MoveArg::Address],
+        },
+    ),
+    (
+        b"0x3::sui_system::request_add_stake_mul_coin",
+        MoveCallTarget {
+            handler: MoveCallHandler::RequestAddStakeMulCoin,
+            type_args: 0,
+            args: &[
+                MoveArg::SuiSystemState,
+                MoveArg::Value,
+                MoveArg::OptionalAmount,
+                MoveArg::Address,
+            ],
+        },
+    ),
+    (
+        b"0x3::sui_system::request_withdraw_stake",
+        MoveCallTarget {
+            handler: MoveCallHandler::RequestWithdrawStake,
+            type_args: 0,
+            args: &[MoveArg::SuiSystemState, MoveArg::Value],
+        },
+    ),
+    (
+        b"0x3::sui_system::request_withdraw_stake_non_entry",
+        MoveCallTarget {
+            handler: MoveCallHandler::RequestWithdrawStakeNonEntry,
+            type_args: 0,
+            args: &[MoveArg::SuiSystemState, MoveArg::Value],
+        },
+    ),
+    (
+        b"0x3::staking_pool::split",
+        MoveCallTarget {
+            handler: MoveCallHandler::StakingPoolSplit,
+            type_args: 0,
+            args: &[MoveArg::Value, MoveArg::Amount],
+        },
+    ),
+];
+
+static_trie! { MOVE_CALL_TARGETS <MoveCallTarget> = MOVE_CALL_TARGET_PAIRS }
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// Finds the registered target of a call; the package is named in hex without leading zeros,
+/// as in `0x2::coin::from_balance`.
+pub fn lookup_move_call_target(
+    package: &CoinID,
+    module: &[u8],
+    function: &[u8],
+) -> Option<MoveCallTarget> {
+    let mut cursor = MOVE_CALL_TARGETS.start().steps(b"0x")?;
+    let mut digits = package
+        .iter()
+        .flat_map(|b| [b >> 4, b & 0xf])
+        .skip_while(|d| *d == 0)
+        .peekable();
+    if digits.peek().is_none() {
+        cursor = cursor.step(b'0')?;
+    }
+    for d in digits {
+        cursor = cursor.step(HEX_DIGITS[d as usize])?;
+    }
+    cursor
+        .steps(b"::")?
+        .steps(module)?
+        .steps(b"::")?
+        .steps(function)?
+        .get_val()
+        .copied()
+}
+
+// Maximum length of the labels of a descriptor
+pub const DESCRIPTOR_LABEL_LENGTH: usize = 32;
+
+// Maximum number of arguments shown for a described call
+pub const MAX_DESCRIBED_ARGS: usize = 6;
+
+pub type DescriptorLabel = ArrayString<DESCRIPTOR_LABEL_LENGTH>;
+
+/// How an argument of a described call is displayed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArgFormat {
+    /// A pure u64 input, or a coin, of the coin type given by the type argument at this index
+    Amount { type_arg: u8 },
+    /// A pure address input
+    Address,
+    /// The id of an object input
+    Object,
+    /// A pure u64 input, in basis points
+    Percentage,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ArgDescriptor {
+    /// Index of the argument in the call, not counting the implicit `&mut TxContext`
+    pub arg: u8,
+    pub label: DescriptorLabel,
+    pub format: ArgFormat,
+}
+
+/// A Move call descriptor, whose signature has been checked.
+///
+/// Every argument of a call has to be described, for the call to be shown with the descriptor.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MoveCallDescriptor {
+    pub package: CoinID,
+    pub module: ArrayString<STRING_LENGTH>,
+    pub function: ArrayString<STRING_LENGTH>,
+    pub label: DescriptorLabel,
+    pub args: ArrayVec<ArgDescriptor, MAX_DESCRIBED_ARGS>,
+}
+
+impl MoveCallDescriptor {
+    pub fn describes(&self, package: &CoinID, module: &[u8], function: &[u8]) -> bool {
+        self.package == *package
+            && self.module.as_bytes() == module
+            && self.function.as_bytes() == function
+    }
+}
+
+/// Source of the descriptors of the calls of a transaction.
+pub trait MoveCallDescriptors {
+    fn get_descriptor(
+        &self,
+        package: &CoinID,
+        module: &[u8],
+        function: &[u8],
+    ) -> Option<&MoveCallDescriptor>;
+}
+
+// At most one descriptor is provided for a transaction
+impl MoveCallDescriptors for Option<&MoveCallDescriptor> {
+    fn get_descriptor(
+        &self,
+        package: &CoinID,
+        module: &[u8],
+        function: &[u8],
+    ) -> Option<&MoveCallDescriptor> {
+        self.filter(|d| d.describes(package, module, function))
+    }
+}
+
+/// The value of a described argument, as found in the transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DescribedValue {
+    Amount(u64, CoinType),
+    Address(SuiAddressRaw),
+    Object(SuiAddressRaw),
+    Percentage(u64),
+}
+
+/// A call reviewed with its descriptor.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DescribedMoveCall {
+    pub label: DescriptorLabel,
+    pub args: ArrayVec<(DescriptorLabel, DescribedValue), MAX_DESCRIBED_ARGS>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parser::common::{SUI_FRAMEWORK_ID, SUI_SYSTEM_ID};
+    use hex_literal::hex;
+
+    #[cfg(target_family = "bolos")]
+    #[allow(unused_imports)]
+    use ledger_device_sdk::testing::TestType;
+    #[cfg(target_family = "bolos")]
+    use testmacro::test_item as test;
+
+    #[test]
+    fn lookup_targets() {
+        let handler = |package: &CoinID, module: &[u8], function: &[u8]| {
+            lookup_move_call_target(package, module, function).map(|t| t.handler)
+        };
+        for (name, target) in MOVE_CALL_TARGET_PAIRS.iter() {
+            let name = core::str::from_utf8(name).unwrap();
+            let mut parts = name.split("::");
+            let package = match parts.next() {
+                Some("0x2") => SUI_FRAMEWORK_ID,
+                Some("0x3") => SUI_SYSTEM_ID,
+                _ => panic!("unexpected package in {name}"),
+            };
+            let module = parts.next().unwrap().as_bytes();
+            let function = parts.next().unwrap().as_bytes();
+            assert_eq!(handler(&package, module, function), Some(target.handler));
+        }
+
+        // Prefixes, other packages and other functions are not targets
+        assert_eq!(handler(&SUI_SYSTEM_ID, b"sui_system", b"request_add"), None);
+        assert_eq!(
+            handler(&SUI_FRAMEWORK_ID, b"sui_system", b"request_add_stake"),
+            None
+        );
+        assert_eq!(handler(&[0; 32], b"coin", b"from_balance"), None);
+        let other_package =
+            hex!("2000000000000000000000000000000000000000000000000000000000000002");
+        assert_eq!(handler(&other_package, b"coin", b"from_balance"), None);
+        assert_eq!(handler(&SUI_FRAMEWORK_ID, b"coin", b"from_balance_"), None);
+    }
+}
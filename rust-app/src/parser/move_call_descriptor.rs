@@ -0,0 +1,277 @@
+// TLV encoding of the Move call descriptors, see docs/apdu.md
+//
+// Tags and lengths are DER encoded, as in the token dynamic descriptor. The signature is the last
+// field, and signs all the fields before it.
+use crate::parser::common::SUI_ADDRESS_LENGTH;
+use crate::parser::move_call::*;
+use arrayvec::{ArrayString, ArrayVec};
+
+const STRUCTURE_TYPE_TAG: u8 = 0x01;
+const VERSION_TAG: u8 = 0x02;
+const PACKAGE_ADDRESS_TAG: u8 = 0x10;
+const MODULE_NAME_TAG: u8 = 0x11;
+const FUNCTION_NAME_TAG: u8 = 0x12;
+const LABEL_TAG: u8 = 0x13;
+const ARG_TAG: u8 = 0x14;
+const SIGNATURE_TAG: u8 = 0x15;
+
+pub const MOVE_CALL_DESCRIPTOR_TYPE: u8 = 0x91;
+pub const MOVE_CALL_DESCRIPTOR_VERSION: u8 = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum DescriptorError {
+    Malformed = 1,
+    UnknownTag,
+    DuplicateTag,
+    MissingTag,
+    WrongStructureType,
+    WrongVersion,
+    // A name or label is too long, or there are too many arguments
+    TooLong,
+    UnknownFormat,
+    WrongSignature,
+}
+
+// A DER encoded tag or length, of at most 2 bytes
+fn read_der(input: &mut &[u8]) -> Result<usize, DescriptorError> {
+    let (first, rest) = input.split_first().ok_or(DescriptorError::Malformed)?;
+    *input = rest;
+    if *first < 0x80 {
+        return Ok(*first as usize);
+    }
+    let len = (*first & 0x7f) as usize;
+    if len == 0 || len > 2 || input.len() < len {
+        return Err(DescriptorError::Malformed);
+    }
+    let (bytes, rest) = input.split_at(len);
+    *input = rest;
+    Ok(bytes.iter().fold(0, |v, b| v << 8 | *b as usize))
+}
+
+fn to_str<const N: usize>(value: &[u8]) -> Result<ArrayString<N>, DescriptorError> {
+    let s = core::str::from_utf8(value).map_err(|_| DescriptorError::Malformed)?;
+    if s.is_empty() {
+        return Err(DescriptorError::Malformed);
+    }
+    ArrayString::from(s).map_err(|_| DescriptorError::TooLong)
+}
+
+// Argument index, format, type argument of an amount (0 otherwise), then the label
+fn to_arg_descriptor(value: &[u8]) -> Result<ArgDescriptor, DescriptorError> {
+    match value {
+        [arg, format, type_arg, label @ ..] => Ok(ArgDescriptor {
+            arg: *arg,
+            label: to_str(label)?,
+            format: match format {
+                0 => ArgFormat::Amount {
+                    type_arg: *type_arg,
+                },
+                1 => ArgFormat::Address,
+                2 => ArgFormat::Object,
+                3 => ArgFormat::Percentage,
+                _ => return Err(DescriptorError::UnknownFormat),
+            },
+        }),
+        _ => Err(DescriptorError::Malformed),
+    }
+}
+
+/// Parses a descriptor, and returns it with the data it was signed over and its signature,
+/// which are for the caller to check.
+pub fn parse_move_call_descriptor(
+    payload: &[u8],
+) -> Result<(MoveCallDescriptor, &[u8], &[u8]), DescriptorError> {
+    let mut input = payload;
+    let mut seen: u32 = 0;
+    let mut package = None;
+    let mut module = None;
+    let mut function = None;
+    let mut label = None;
+    let mut args = ArrayVec::new();
+
+    loop {
+        let field_start = payload.len() - input.len();
+        let tag = read_der(&mut input)?;
+        let len = read_der(&mut input)?;
+        if input.len() < len {
+            return Err(DescriptorError::Malformed);
+        }
+        let (value, rest) = input.split_at(len);
+        input = rest;
+
+        let tag = u8::try_from(tag).map_err(|_| DescriptorError::UnknownTag)?;
+        if tag > 0x1f {
+            return Err(DescriptorError::UnknownTag);
+        }
+        if tag != ARG_TAG && seen & (1 << tag) != 0 {
+            return Err(DescriptorError::DuplicateTag);
+        }
+        seen |= 1 << tag;
+
+        match tag {
+            STRUCTURE_TYPE_TAG => {
+                if value != [MOVE_CALL_DESCRIPTOR_TYPE] {
+                    return Err(DescriptorError::WrongStructureType);
+                }
+            }
+            VERSION_TAG => {
+                if value != [MOVE_CALL_DESCRIPTOR_VERSION] {
+                    return Err(DescriptorError::WrongVersion);
+                }
+            }
+            PACKAGE_ADDRESS_TAG => {
+                let id: [u8; SUI_ADDRESS_LENGTH] =
+                    value.try_into().map_err(|_| DescriptorError::Malformed)?;
+                package = Some(id);
+            }
+            MODULE_NAME_TAG => module = Some(to_str(value)?),
+            FUNCTION_NAME_TAG => function = Some(to_str(value)?),
+            LABEL_TAG => label = Some(to_str(value)?),
+            ARG_TAG => args
+                .try_push(to_arg_descriptor(value)?)
+                .map_err(|_| DescriptorError::TooLong)?,
+            SIGNATURE_TAG => {
+                if !input.is_empty() {
+                    return Err(DescriptorError::Malformed);
+                }
+                if seen & (1 << STRUCTURE_TYPE_TAG) == 0 || seen & (1 << VERSION_TAG) == 0 {
+                    return Err(DescriptorError::MissingTag);
+                }
+                let descriptor = match (package, module, function, label) {
+                    (Some(package), Some(module), Some(function), Some(label)) => {
+                        MoveCallDescriptor {
+                            package,
+                            module,
+                            function,
+                            label,
+                            args,
+                        }
+                    }
+                    _ => return Err(DescriptorError::MissingTag),
+                };
+                return Ok((descriptor, &payload[..field_start], value));
+            }
+            _ => return Err(DescriptorError::UnknownTag),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use hex_literal::hex;
+
+    extern crate alloc;
+    use alloc::vec::Vec;
+
+    #[cfg(target_family = "bolos")]
+    #[allow(unused_imports)]
+    use ledger_device_sdk::testing::TestType;
+    #[cfg(target_family = "bolos")]
+    use testmacro::test_item as test;
+
+    const PACKAGE: [u8; 32] =
+        hex!("dee9000000000000000000000000000000000000000000000000000000000000");
+
+    fn tlv(out: &mut Vec<u8>, tag: u8, value: &[u8]) {
+        out.push(tag);
+        if value.len() >= 0x80 {
+            out.push(0x81);
+        }
+        out.push(value.len() as u8);
+        out.extend_from_slice(value);
+    }
+
+    fn fields(args: &[&[u8]]) -> Vec<u8> {
+        let mut out = Vec::new();
+        tlv(&mut out, STRUCTURE_TYPE_TAG, &[MOVE_CALL_DESCRIPTOR_TYPE]);
+        tlv(&mut out, VERSION_TAG, &[MOVE_CALL_DESCRIPTOR_VERSION]);
+        tlv(&mut out, PACKAGE_ADDRESS_TAG, &PACKAGE);
+        tlv(&mut out, MODULE_NAME_TAG, b"pool");
+        tlv(&mut out, FUNCTION_NAME_TAG, b"swap_exact_base_for_quote");
+        tlv(&mut out, LABEL_TAG, b"swap coins");
+        for arg in args {
+            tlv(&mut out, ARG_TAG, arg);
+        }
+        out
+    }
+
+    fn signed(mut out: Vec<u8>) -> Vec<u8> {
+        tlv(&mut out, SIGNATURE_TAG, &[0x30; 0x90]);
+        out
+    }
+
+    #[test]
+    fn parse_descriptor() {
+        let unsigned = fields(&[b"\x01\x00\x00Amount in", b"\x03\x03\x00Max slippage"]);
+        let payload = signed(unsigned.clone());
+        let (descriptor, signed_data, signature) = parse_move_call_descriptor(&payload).unwrap();
+        assert_eq!(signed_data, unsigned.as_slice());
+        assert_eq!(signature, &[0x30; 0x90]);
+        assert!(descriptor.describes(&PACKAGE, b"pool", b"swap_exact_base_for_quote"));
+        assert_eq!(descriptor.label.as_str(), "swap coins");
+        assert_eq!(
+            descriptor.args.as_slice(),
+            &[
+                ArgDescriptor {
+                    arg: 1,
+                    label: ArrayString::from("Amount in").unwrap(),
+                    format: ArgFormat::Amount { type_arg: 0 },
+                },
+                ArgDescriptor {
+                    arg: 3,
+                    label: ArrayString::from("Max slippage").unwrap(),
+                    format: ArgFormat::Percentage,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reject_descriptors() {
+        let parse = |payload: Vec<u8>| parse_move_call_descriptor(&payload).map(|_| ());
+
+        // No signature
+        assert_eq!(parse(fields(&[])), Err(DescriptorError::Malformed));
+        // Data after the signature
+        let mut payload = signed(fields(&[]));
+        tlv(&mut payload, LABEL_TAG, b"label");
+        assert_eq!(parse(payload), Err(DescriptorError::Malformed));
+        // Missing function name
+        let mut payload = fields(&[]);
+        let start = payload
+            .windows(2)
+            .position(|w| w == [FUNCTION_NAME_TAG, 25]);
+        payload.drain(start.unwrap()..start.unwrap() + 27);
+        assert_eq!(parse(signed(payload)), Err(DescriptorError::MissingTag));
+        // Twice the label
+        let mut payload = fields(&[]);
+        tlv(&mut payload, LABEL_TAG, b"label");
+        assert_eq!(parse(signed(payload)), Err(DescriptorError::DuplicateTag));
+        // Unknown tag, and unknown format of an argument
+        let mut payload = fields(&[]);
+        tlv(&mut payload, 0x16, b"");
+        assert_eq!(parse(signed(payload)), Err(DescriptorError::UnknownTag));
+        assert_eq!(
+            parse(signed(fields(&[b"\x01\x09\x00Amount"]))),
+            Err(DescriptorError::UnknownFormat)
+        );
+        // Too many arguments, and too long a label
+        let args = [&b"\x00\x01\x00To"[..]; MAX_DESCRIBED_ARGS + 1];
+        assert_eq!(parse(signed(fields(&args))), Err(DescriptorError::TooLong));
+        let mut long_label = b"\x00\x01\x00".to_vec();
+        long_label.extend_from_slice(&[b'a'; DESCRIPTOR_LABEL_LENGTH + 1]);
+        assert_eq!(
+            parse(signed(fields(&[&long_label]))),
+            Err(DescriptorError::TooLong)
+        );
+        // Descriptor of another type
+        let mut payload = fields(&[]);
+        payload[2] = 0x90;
+        assert_eq!(
+            parse(signed(payload)),
+            Err(DescriptorError::WrongStructureType)
+        );
+    }
+}
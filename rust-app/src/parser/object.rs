@@ -1,17 +1,18 @@
 #[cfg(feature = "speculos")]
 use crate::crypto_helpers::common::HexSlice;
+#[cfg(target_family = "bolos")]
 use crate::crypto_helpers::hasher::HexHash;
 use crate::parser::common::*;
 use arrayvec::ArrayVec;
 use core::convert::TryInto;
 use core::future::Future;
+#[cfg(target_family = "bolos")]
 use ledger_device_sdk::hash::HashInit;
-use ledger_device_sdk::io::SyscallError;
-use ledger_device_sdk::log::info;
 use ledger_parser_combinators::async_parser::*;
 use ledger_parser_combinators::bcs::async_parser::*;
 use ledger_parser_combinators::core_parsers::*;
 use ledger_parser_combinators::interp::*;
+use ledger_parser_combinators::recursive::*;
 
 // Object Schema
 pub type ObjectInnerSchema = (
@@ -41,12 +42,18 @@ pub const STRING_LENGTH: usize = 64;
 pub type String = Vec<Byte, STRING_LENGTH>;
 
 pub type StructTag = (SuiAddress, String, String, TypeParams);
-pub type TypeParams = Vec<TypeTag2, 5>;
+pub type TypeParams = Vec<TypeTag, 5>;
 
 pub struct TypeTag;
 
-// This is to avoid recursion of async parsers
-pub struct TypeTag2;
+/// Type tags within type tags, e.g. the `LP<A, B>` in `Coin<LP<A, B>>`, are only checked for their
+/// layout, and may nest this deep.
+pub const MAX_TYPE_TAG_DEPTH: usize = 8;
+
+/// Parser of the type tags nested in other ones.
+pub const fn nested_type_tag_parser() -> Recursive<DropInterp, MAX_TYPE_TAG_DEPTH> {
+    Recursive(DropInterp)
+}
 
 // Parsed data
 pub enum MoveObjectType {
@@ -222,7 +229,7 @@ pub const fn struct_tag_parser<BS: Clone + Readable>(
             DefaultInterp,
             SubInterp(DefaultInterp),
             SubInterp(DefaultInterp),
-            SubInterp(DefaultInterp),
+            SubInterp(nested_type_tag_parser()),
         ),
         |(address, mut module, mut name, _type_tags): (
             [u8; 32],
@@ -292,69 +299,24 @@ impl<BS: Clone + Readable> AsyncParser<TypeTag, BS> for DefaultInterp {
                 }
                 6 => {
                     info!("TypeTag: Vector(Box<TypeTag>)");
+                    nested_type_tag_parser().parse(input).await;
                     None
                 }
                 7 => {
                     info!("TypeTag: Struct(StructTag)");
                     Some(struct_tag_parser().parse(input).await)
                 }
-                _ => {
-                    reject_on(
-                        core::file!(),
-                        core::line!(),
-                        SyscallError::NotSupported as u16,
-                    )
-                    .await
-                }
-            }
-        }
-    }
-}
-
-impl HasOutput<TypeTag2> for DefaultInterp {
-    type Output = ();
-}
-
-impl<BS: Clone + Readable> AsyncParser<TypeTag2, BS> for DefaultInterp {
-    type State<'c>
-        = impl Future<Output = Self::Output> + 'c
-    where
-        BS: 'c;
-    fn parse<'a: 'c, 'b: 'c, 'c>(&'b self, input: &'a mut BS) -> Self::State<'c> {
-        async move {
-            let enum_variant =
-                <DefaultInterp as AsyncParser<ULEB128, BS>>::parse(&DefaultInterp, input).await;
-            match enum_variant {
-                0 => {
-                    info!("TypeTag2: Bool");
-                }
-                1 => {
-                    info!("TypeTag2: U8");
-                }
-                2 => {
-                    info!("TypeTag2: U64");
-                }
-                3 => {
-                    info!("TypeTag2: U128");
-                }
-                4 => {
-                    info!("TypeTag2: Address");
-                }
-                5 => {
-                    info!("TypeTag2: Signer");
+                8 => {
+                    info!("TypeTag: U16");
+                    None
                 }
-                6 => {
-                    info!("TypeTag2: Vector(Box<TypeTag2>)");
+                9 => {
+                    info!("TypeTag: U32");
+                    None
                 }
-                7 => {
-                    info!("TypeTag2: Struct(StructTag)");
-                    // Don't do recursion; ignore this object
-                    reject_on(
-                        core::file!(),
-                        core::line!(),
-                        SyscallError::NotSupported as u16,
-                    )
-                    .await
+                10 => {
+                    info!("TypeTag: U256");
+                    None
                 }
                 _ => {
                     reject_on(
@@ -369,45 +331,37 @@ impl<BS: Clone + Readable> AsyncParser<TypeTag2, BS> for DefaultInterp {
     }
 }
 
-impl HasOutput<OwnerSchema> for DefaultInterp {
-    type Output = ();
-}
-
-impl<BS: Clone + Readable> AsyncParser<OwnerSchema, BS> for DefaultInterp {
-    type State<'c>
+// Skips a type tag, and the ones within it
+impl<BS: Clone + Readable> RecursiveParser<TypeTag, BS> for DropInterp {
+    type LevelState<'c>
         = impl Future<Output = Self::Output> + 'c
     where
         BS: 'c;
-    fn parse<'a: 'c, 'b: 'c, 'c>(&'b self, input: &'a mut BS) -> Self::State<'c> {
+    fn parse_level<'a: 'c, 'b: 'c, 'c>(
+        &'b self,
+        input: &'a mut BS,
+        nested: Nested<'b, Self>,
+    ) -> Self::LevelState<'c> {
         async move {
             let enum_variant =
                 <DefaultInterp as AsyncParser<ULEB128, BS>>::parse(&DefaultInterp, input).await;
             match enum_variant {
-                0 => {
-                    info!("OwnerSchema: AddressOwner(SuiAddress)");
-                    let _owner = <DefaultInterp as AsyncParser<SuiAddress, BS>>::parse(
-                        &DefaultInterp,
+                0..=5 | 8..=10 => {}
+                6 => {
+                    <Nested<DropInterp> as AsyncParser<TypeTag, BS>>::parse(&nested, input).await;
+                }
+                7 => {
+                    <_ as AsyncParser<(SuiAddress, String, String), BS>>::parse(
+                        &(DefaultInterp, DropInterp, DropInterp),
                         input,
                     )
                     .await;
-                    info!("OwnerSchema: AddressOwner({})", HexSlice(&_owner));
-                }
-                1 => {
-                    info!("OwnerSchema: ObjectOwner(SuiAddress)");
-                    <DefaultInterp as AsyncParser<SuiAddress, BS>>::parse(&DefaultInterp, input)
-                        .await;
-                }
-                2 => {
-                    info!("OwnerSchema: Shared");
-                    <DefaultInterp as AsyncParser<SequenceNumber, BS>>::parse(
-                        &DefaultInterp,
+                    <SubInterp<Nested<DropInterp>> as AsyncParser<TypeParams, BS>>::parse(
+                        &SubInterp(nested),
                         input,
                     )
                     .await;
                 }
-                3 => {
-                    info!("OwnerSchema: Immutable");
-                }
                 _ => {
                     reject_on(
                         core::file!(),
@@ -421,23 +375,125 @@ impl<BS: Clone + Readable> AsyncParser<OwnerSchema, BS> for DefaultInterp {
     }
 }
 
+#[derive(BcsAsyncParser)]
+#[bcs(schema = OwnerSchema, reject = SyscallError::NotSupported as u16, log = info)]
+pub enum Owner {
+    AddressOwner(#[bcs(schema = SuiAddress)] SuiAddressRaw),
+    ObjectOwner(#[bcs(schema = SuiAddress)] SuiAddressRaw),
+    Shared(#[bcs(schema = SequenceNumber)] u64),
+    Immutable,
+}
+
+#[cfg(target_family = "bolos")]
 pub async fn compute_object_hash<BS: Clone + Readable>(bs: &mut BS, length: usize) -> HexHash<32> {
     let mut hasher = ledger_device_sdk::hash::blake2::Blake2b_256::new();
     let salt = b"Object::";
     let _ = hasher.update(salt);
 
     const CHUNK_SIZE: usize = 128;
-    let (chunks, rem) = (length / CHUNK_SIZE, length % CHUNK_SIZE);
-    for _ in 0..chunks {
-        let b: [u8; CHUNK_SIZE] = bs.read().await;
-        let _ = hasher.update(&b);
-    }
-    for _ in 0..rem {
-        let b: [u8; 1] = bs.read().await;
-        let _ = hasher.update(&b);
+    let mut buf = [0; CHUNK_SIZE];
+    let mut remaining = length;
+    while remaining > 0 {
+        let chunk = &mut buf[..core::cmp::min(remaining, CHUNK_SIZE)];
+        bs.read_into(chunk).await;
+        let _ = hasher.update(chunk);
+        remaining -= chunk.len();
     }
 
     let mut hash: HexHash<32> = Default::default();
     let _ = hasher.finalize(&mut hash.0);
     hash
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parser::testing::*;
+    extern crate alloc;
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use hex_literal::hex;
+
+    #[cfg(target_family = "bolos")]
+    #[allow(unused_imports)]
+    use ledger_device_sdk::testing::TestType;
+    #[cfg(target_family = "bolos")]
+    use testmacro::test_item as test;
+
+    fn parse_object(object: &'static [u8]) -> Option<CoinData> {
+        parse_all::<ObjectInnerSchema, _, _>(&object_parser(), object)
+    }
+
+    #[test]
+    fn gas_coin() {
+        assert_eq!(
+            parse_object(SUI_COIN_1C12.1),
+            Some((SUI_COIN_TYPE, 10000000))
+        );
+    }
+
+    #[test]
+    fn token_coin() {
+        let (coin_id, module, name) = parse_object(USDC_COIN_D3BA.1).unwrap().0;
+        assert_eq!(
+            coin_id,
+            hex!("dba34672e30cb065b1f93e3ab55318768fd6fef66c15942c9f7cb846e2f900e7")
+        );
+        assert_eq!(module.as_slice(), b"usdc");
+        assert_eq!(name.as_slice(), b"USDC");
+        assert_eq!(parse_object(USDC_COIN_D3BA.1).unwrap().1, 100023);
+    }
+
+    #[test]
+    fn staked_sui() {
+        // The principal of the stake
+        assert_eq!(
+            parse_object(STAKED_SUI_D263.1),
+            Some((SUI_COIN_TYPE, 3000000000))
+        );
+    }
+
+    #[test]
+    fn package_rejected() {
+        assert_eq!(parse_object(&[1]), None);
+    }
+
+    // BCS of a struct tag, without the TypeTag variant
+    fn struct_tag(address: u8, module: &str, name: &str, type_params: &[&[u8]]) -> Vec<u8> {
+        let mut rv = vec![address; 32];
+        for s in [module, name] {
+            rv.push(s.len() as u8);
+            rv.extend_from_slice(s.as_bytes());
+        }
+        rv.push(type_params.len() as u8);
+        for t in type_params {
+            rv.extend_from_slice(t);
+        }
+        rv
+    }
+
+    fn parse_type_tag(type_tag: Vec<u8>) -> Option<Option<CoinType>> {
+        parse_all::<TypeTag, _, _>(&DefaultInterp, type_tag)
+    }
+
+    #[test]
+    fn nested_type_tags() {
+        // Coin<0x2::lp::LP<0x3::a::A, vector<u8>>>
+        let a = [&[7][..], &struct_tag(3, "a", "A", &[])].concat();
+        let lp = [&[7][..], &struct_tag(2, "lp", "LP", &[&a, &[6, 1]])].concat();
+        let coin = [&[7][..], &struct_tag(2, "coin", "Coin", &[&lp])].concat();
+        let (coin_id, module, name) = parse_type_tag(coin).unwrap().unwrap();
+        assert_eq!(coin_id, [2; 32]);
+        assert_eq!(module.as_slice(), b"coin");
+        assert_eq!(name.as_slice(), b"Coin");
+
+        // vector<vector<...<u8>>>
+        let vector = |depth| [vec![6; depth], vec![1]].concat();
+        assert_eq!(parse_type_tag(vector(MAX_TYPE_TAG_DEPTH)), Some(None));
+        assert_eq!(parse_type_tag(vector(MAX_TYPE_TAG_DEPTH + 1)), None);
+
+        // Unknown type tag within a struct
+        let unknown = [&[7][..], &struct_tag(2, "coin", "Coin", &[&[11]])].concat();
+        assert_eq!(parse_type_tag(unknown), None);
+    }
+}
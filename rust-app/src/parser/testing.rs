@@ -0,0 +1,99 @@
+// Helpers for the host tests of the parsers
+use crate::parser::common::*;
+use crate::parser::object::object_parser;
+use core::future::Future;
+use hex_literal::hex;
+use ledger_parser_combinators::async_parser::*;
+pub use ledger_parser_combinators::bcs::testing::*;
+
+// Provides the objects of the transaction, like the object list sent along with it
+// Each object is given with its digest, as computing it requires the SDK
+#[derive(Clone)]
+pub struct MockObjectData(pub &'static [(ObjectDigest, &'static [u8])]);
+
+impl HasObjectData for MockObjectData {
+    type State<'c> = impl Future<Output = Option<ObjectData>> + 'c;
+
+    fn get_object_data<'a: 'c, 'b: 'c, 'c>(&'b self, digest: &'a ObjectDigest) -> Self::State<'c> {
+        async move {
+            let (_, object) = self.0.iter().find(|(d, _)| d == digest)?;
+            let mut bs = TestReadable::new(*object);
+            Some(object_parser().parse(&mut bs).await)
+        }
+    }
+}
+
+// Runs the parser on the whole input, returns None if it was rejected
+pub fn parse_all<S, P: AsyncParser<S, TestReadable<T>>, T: AsRef<[u8]>>(
+    parser: &P,
+    input: T,
+) -> Option<<P as HasOutput<S>>::Output> {
+    let mut bs = TestReadable::new(input);
+    match poll_once(parser.parse(&mut bs)) {
+        core::task::Poll::Ready(v) => Some(v),
+        core::task::Poll::Pending => None,
+    }
+}
+
+// Objects used by the transactions of the tests, from the object lists of the speculos tests
+// The digest is the blake2b hash of "Object::" followed by the object, prefixed by its length
+
+// 0x400dbdcfeda8e1e64ebbf5710ccf5a67bfa6e7f945c92bc8611e0cb44b7219de
+pub const SUI_COIN_400D: (ObjectDigest, &[u8]) = (
+    hex!("2066c5ab65498a9d3716001a034815ff4abca765f7f6f2375cc91d2addb79c208a"),
+    &hex!("000101d37642110000000028400dbdcfeda8e1e64ebbf5710ccf5a67bfa6e7f945c92bc8611e0cb44b7219de100e943900000000006fb21feead027da4873295affd6c4f3618fe176fa2fbf3e7b5ef1d9463b31e212074fe3dd473a96335b6f5b5b5a0be3e4f033ecc0a020ee9ef75894f5ab0b33dc760130f0000000000"),
+);
+
+// 0x1c12be5429384d00eeef61242f3aebabeac3012549dd6f888dc1087c4d00da80
+pub const SUI_COIN_1C12: (ObjectDigest, &[u8]) = (
+    hex!("20e10131885e3b30e7cb4561d53970d55cbf694c099ec6d2c789b2f2dc92ca7ff3"),
+    &hex!("000101d276421100000000281c12be5429384d00eeef61242f3aebabeac3012549dd6f888dc1087c4d00da808096980000000000001d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be488201d5b19bfcc11a93e8966df9067eb024a09728158a7a06295bd653d4be181145d60130f0000000000"),
+);
+
+// 0x1f876ff0144386dcf4e886c5de53b326c718cc1221e1ccea71ef8aa6231a40ea
+pub const SUI_COIN_1F87: (ObjectDigest, &[u8]) = (
+    hex!("2021c01bdc29b088d3b0151eab0176ff9501e04ab49e00124a5b6fae1a05f351ba"),
+    &hex!("000101d376421100000000281f876ff0144386dcf4e886c5de53b326c718cc1221e1ccea71ef8aa6231a40ea002d310100000000001d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be4882074fe3dd473a96335b6f5b5b5a0be3e4f033ecc0a020ee9ef75894f5ab0b33dc760130f0000000000"),
+);
+
+// 0xa93f6c464f8fb8b98fb3d02112902060c8f85ea4d71cfc7777dfdbd75e68ab6d
+pub const SUI_COIN_A93F: (ObjectDigest, &[u8]) = (
+    hex!("20ac558a57e3fe380a9d8159ef1a564002516e6bc6db04ae350f27a9ccc5a0afbd"),
+    &hex!("000101d47642110000000028a93f6c464f8fb8b98fb3d02112902060c8f85ea4d71cfc7777dfdbd75e68ab6d80c3c90100000000001d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be488201f54021a984ad1549b7806eaa8645636741680280690cebc6f563b8b720aea6660130f0000000000"),
+);
+
+// 0xd3baa97a46a20d65f1be60cbaa160856e6aae5078e16545071e2fd3e314105b9
+pub const USDC_COIN_D3BA: (ObjectDigest, &[u8]) = (
+    hex!("209c428fe35ca9b1917e8d0eedbd359d11c218abe23338f6e0438b89613e948a43"),
+    &hex!("000307dba34672e30cb065b1f93e3ab55318768fd6fef66c15942c9f7cb846e2f900e7047573646304555344430001762fa41d0000000028d3baa97a46a20d65f1be60cbaa160856e6aae5078e16545071e2fd3e314105b9b786010000000000000f2f8dd49e269da066f37624d33a8b4ed1b262d6a5fdf2b735be39f8d7c5acf520257da22cf6f10376f95160b375557286f7e73a1a66041fe567007bd81674dc96a02d140000000000"),
+);
+
+// 0x8ba6495be346c1be89e5c35cdabab145ea18990f48f2b0629ff016024c9ded45
+pub const USDC_COIN_8BA6: (ObjectDigest, &[u8]) = (
+    hex!("203e91489053c2e2c2c851e8f16afadb8213fd02cea1f3a024c603986c4c149637"),
+    &hex!("000307dba34672e30cb065b1f93e3ab55318768fd6fef66c15942c9f7cb846e2f900e70475736463045553444300013532a61d00000000288ba6495be346c1be89e5c35cdabab145ea18990f48f2b0629ff016024c9ded4501e2010000000000000f2f8dd49e269da066f37624d33a8b4ed1b262d6a5fdf2b735be39f8d7c5acf520e57b47f61fa4aef1202b0a89fb784bd1bab44dbeb783323c05f88121a6911218a02d140000000000"),
+);
+
+// 0x8ebf87449ed242216a3c94de4935c21792618850fc781222e866dc60bb1bc8d2
+pub const USDC_COIN_8EBF: (ObjectDigest, &[u8]) = (
+    hex!("20e43f0996520f3d5b2453fbca700a90caa6edbae90d701d1c9b0ee6a1405d6e9c"),
+    &hex!("000307dba34672e30cb065b1f93e3ab55318768fd6fef66c15942c9f7cb846e2f900e7047573646304555344430001e679a41d00000000288ebf87449ed242216a3c94de4935c21792618850fc781222e866dc60bb1bc8d201db010000000000000f2f8dd49e269da066f37624d33a8b4ed1b262d6a5fdf2b735be39f8d7c5acf520ad36fb86f17a6dc970a0b8d4d3406a74c3a10073c07b0b3bab9586d02225fa82a02d140000000000"),
+);
+
+// 0xebff16b4d2081ab06d1d5251c988208641e5c501c7fa8bdce9c8b7b0908ba76b
+pub const SUI_COIN_EBFF: (ObjectDigest, &[u8]) = (
+    hex!("203bff047cca0dba126b90f5e7f0f726abbda38756521bdc13a6f29bf9278db0e4"),
+    &hex!("000101d57642110000000028ebff16b4d2081ab06d1d5251c988208641e5c501c7fa8bdce9c8b7b0908ba76b409c710200000000001d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be488202eaba071c573f021b2ec0e85d624debf1268b412d5331899e59f9a492f88836160130f0000000000"),
+);
+
+// 0x493954c643a2aa96aa3d01cf12821e6d3612de6051a07b871683e82a4008d3ac
+pub const SUI_COIN_4939: (ObjectDigest, &[u8]) = (
+    hex!("2089e01e1fbfa66b4714d09dd401f84e3c06912f42f84c8a1c8e31aa4cbedcf2d2"),
+    &hex!("000101c4eb39000000000028493954c643a2aa96aa3d01cf12821e6d3612de6051a07b871683e82a4008d3ac3875ef7500000000001255d09304c5cc824b9ef4fd9196f5b8e96167b4fb0266f70dd36829ad519d5d209d6485ba97c7baff644a2a3ffcedfde54eea8eb8bcdac469f67bbdfb708f15dd60130f0000000000"),
+);
+
+// 0xd263dbcc9f13e9302b8b629518d2a12c5be755aba0c978606cc291367059015d
+pub const STAKED_SUI_D263: (ObjectDigest, &[u8]) = (
+    hex!("20c17ecaa3599cd8af7affcb659e4ae4128b7160a023d0813ea6c711d23bc910e5"),
+    &hex!("000200f71b0e000000000050d263dbcc9f13e9302b8b629518d2a12c5be755aba0c978606cc291367059015d0bbc40471c2e0c94776f07581a09c99b0c19112a0fbc6adde69b2c2ea8be8f4f0900000000000000005ed0b200000000001255d09304c5cc824b9ef4fd9196f5b8e96167b4fb0266f70dd36829ad519d5d20ca11020b472edde78eaa7e606b62bf8d81905d9b127de06957ff6fddfb500504e0b6130000000000"),
+);
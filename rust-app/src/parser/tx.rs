@@ -1,16 +1,17 @@
 use crate::parser::common::*;
-use crate::utils::{estimate_btree_map_usage, NoinlineFut};
+use crate::parser::move_call::*;
+use crate::parser::object::{nested_type_tag_parser, struct_tag_parser, TypeTag};
+use crate::utils::{estimate_btree_map_usage, estimate_btree_set_usage, NoinlineFut};
 
 extern crate alloc;
-use alloc::collections::BTreeMap;
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
 use arrayvec::ArrayVec;
 use core::convert::TryFrom;
 use core::future::Future;
-use either::*;
-use ledger_device_sdk::io::SyscallError;
-use ledger_device_sdk::log::info;
 use ledger_parser_combinators::async_parser::*;
 use ledger_parser_combinators::bcs::async_parser::*;
+use ledger_parser_combinators::breadcrumbs;
 use ledger_parser_combinators::core_parsers::*;
 use ledger_parser_combinators::interp::*;
 
@@ -69,7 +70,7 @@ pub enum CallArg {
     RecipientAddress(SuiAddressRaw),
     Amount(u64),
     OptionalAmount(Option<u64>),
-    ObjectRef(ObjectDigest),
+    ObjectRef(SuiAddressRaw, ObjectDigest),
     SharedObject(CoinID),
     Other,
 }
@@ -79,7 +80,7 @@ pub enum InputValue {
     RecipientAddress(SuiAddressRaw),
     Amount(u64),
     OptionalAmount(Option<u64>),
-    ObjectRef(ObjectDigest),
+    ObjectRef(SuiAddressRaw, ObjectDigest),
     SharedObject(CoinID),
     Object(CoinData),
     // ^ mutable via MergeCoins
@@ -100,6 +101,7 @@ impl<BS: Clone + Readable> AsyncParser<CallArgSchema, BS> for DefaultInterp {
                 <DefaultInterp as AsyncParser<ULEB128, BS>>::parse(&DefaultInterp, input).await;
             match enum_variant {
                 0 => {
+                    let _crumb = breadcrumbs::field("Pure");
                     let length =
                         <DefaultInterp as AsyncParser<ULEB128, BS>>::parse(&DefaultInterp, input)
                             .await;
@@ -127,24 +129,27 @@ impl<BS: Clone + Readable> AsyncParser<CallArgSchema, BS> for DefaultInterp {
                             .await,
                         ),
                         _ => {
-                            for _ in 0..length {
-                                let _: [u8; 1] = input.read().await;
-                            }
+                            input.skip(length as usize).await;
                             CallArg::Other
                         }
                     }
                 }
                 1 => {
+                    let _crumb = breadcrumbs::field("Object");
                     let enum_variant =
                         <DefaultInterp as AsyncParser<ULEB128, BS>>::parse(&DefaultInterp, input)
                             .await;
                     match enum_variant {
                         0 => {
                             info!("CallArgSchema: ObjectRef: ImmOrOwnedObject");
-                            CallArg::ObjectRef(object_ref_parser().parse(input).await)
+                            let _crumb = breadcrumbs::field("ImmOrOwnedObject");
+                            let (object_id, digest) =
+                                object_ref_with_id_parser().parse(input).await;
+                            CallArg::ObjectRef(object_id, digest)
                         }
                         1 => {
                             info!("CallArgSchema: ObjectRef: SharedObject");
+                            let _crumb = breadcrumbs::field("SharedObject");
                             let (object_id, _, _) =
                                 <(DefaultInterp, DefaultInterp, DefaultInterp) as AsyncParser<
                                     SharedObject,
@@ -182,7 +187,7 @@ impl<BS: Clone + Readable> AsyncParser<CallArgSchema, BS> for DefaultInterp {
 pub struct TypeInput;
 
 impl HasOutput<TypeInput> for DefaultInterp {
-    type Output = ();
+    type Output = Option<CoinType>;
 }
 
 impl<BS: Clone + Readable> AsyncParser<TypeInput, BS> for DefaultInterp {
@@ -197,48 +202,58 @@ impl<BS: Clone + Readable> AsyncParser<TypeInput, BS> for DefaultInterp {
             match enum_variant {
                 0 => {
                     info!("TypeInput: Bool");
+                    None
                 }
                 1 => {
                     info!("TypeInput: U8");
+                    None
                 }
                 2 => {
                     info!("TypeInput: U64");
+                    None
                 }
                 3 => {
                     info!("TypeInput: U128");
+                    None
                 }
                 4 => {
                     info!("TypeInput: Address");
+                    None
                 }
                 5 => {
                     info!("TypeInput: Signer");
+                    None
                 }
                 6 => {
                     info!("TypeInput: Vector(Box<TypeInput>)");
-                    reject_on(
-                        core::file!(),
-                        core::line!(),
-                        SyscallError::NotSupported as u16,
-                    )
-                    .await
+                    // TypeInput has the same layout as TypeTag
+                    <_ as AsyncParser<TypeTag, BS>>::parse(&nested_type_tag_parser(), input).await;
+                    None
                 }
                 7 => {
                     info!("TypeInput: Struct(Box<StructInput>)");
-                    reject_on(
-                        core::file!(),
-                        core::line!(),
-                        SyscallError::NotSupported as u16,
-                    )
-                    .await
+                    // StructInput has the same layout as StructTag
+                    let coin_type = struct_tag_parser().parse(input).await;
+                    if coin_type.0 == SUI_COIN_ID
+                        && coin_type.1.as_slice() == b"sui"
+                        && coin_type.2.as_slice() == b"SUI"
+                    {
+                        Some(SUI_COIN_TYPE)
+                    } else {
+                        Some(coin_type)
+                    }
                 }
                 8 => {
                     info!("TypeInput: U16");
+                    None
                 }
                 9 => {
                     info!("TypeInput: U32");
+                    None
                 }
                 10 => {
                     info!("TypeInput: U256");
+                    None
                 }
                 _ => {
                     reject_on(
@@ -254,6 +269,7 @@ impl<BS: Clone + Readable> AsyncParser<TypeInput, BS> for DefaultInterp {
 }
 
 pub const MOVE_CALL_ARGS_ARRAY_LENGTH: usize = 8;
+pub const MOVE_CALL_TYPE_ARGS_ARRAY_LENGTH: usize = 3;
 pub const TRANSFER_OBJECT_ARRAY_LENGTH: usize = 8;
 pub const SPLIT_COIN_ARRAY_LENGTH: usize = 8;
 pub const MERGE_COIN_ARRAY_LENGTH: usize = 8;
@@ -267,6 +283,8 @@ pub enum Command {
         CoinID,
         ArrayVec<u8, STRING_LENGTH>,
         ArrayVec<u8, STRING_LENGTH>,
+        // Boxed, as the type args are much larger than the other commands
+        Box<ArrayVec<Option<CoinType>, MOVE_CALL_TYPE_ARGS_ARRAY_LENGTH>>,
         ArrayVec<Argument, MOVE_CALL_ARGS_ARRAY_LENGTH>,
     ),
     TransferObject(ArrayVec<Argument, TRANSFER_OBJECT_ARRAY_LENGTH>, Argument),
@@ -279,7 +297,18 @@ pub enum CommandResult {
     SplitCoinAmounts(CoinType, ArrayVec<u64, SPLIT_COIN_ARRAY_LENGTH>),
     MergedCoin(CoinData),
     MoveVecMergedCoin(TotalCoinAmount),
-    StakingPoolSplitCoin(u64),
+    // The id of the StakedSui object being split, if it is an input, and the split amount
+    StakingPoolSplitCoin(Option<SuiAddressRaw>, u64),
+    // Balance<SUI> obtained by withdrawing a stake, and the same balance once
+    // converted to a Coin<SUI>. The amount is the principal of the StakedSui.
+    WithdrawnBalance(WithdrawnStake),
+    WithdrawnCoin(WithdrawnStake),
+}
+
+#[derive(Clone)]
+pub struct WithdrawnStake {
+    pub source_stake: SuiAddressRaw,
+    pub amount: u64,
 }
 
 impl HasOutput<CommandSchema> for DefaultInterp {
@@ -298,6 +327,7 @@ impl<BS: Clone + Readable> AsyncParser<CommandSchema, BS> for DefaultInterp {
             match enum_variant {
                 0 => {
                     info!("CommandSchema: MoveCall");
+                    let _crumb = breadcrumbs::field("MoveCall");
                     let package =
                         <DefaultInterp as AsyncParser<ObjectID, BS>>::parse(&DefaultInterp, input)
                             .await;
@@ -311,21 +341,27 @@ impl<BS: Clone + Readable> AsyncParser<CommandSchema, BS> for DefaultInterp {
                         input,
                     )
                     .await;
-                    // TypeInput is not supported, hence vec of length 0
-                    <SubInterp<DefaultInterp> as AsyncParser<Vec<TypeInput, 0>, BS>>::parse(
-                        &SubInterp(DefaultInterp),
-                        input,
+                    // A few type arguments, eg for 0x2::coin::from_balance<T>, or for the swap
+                    // of coin A for coin B of a described call
+                    let type_args = <Breadcrumb<SubInterp<DefaultInterp>> as AsyncParser<
+                        Vec<TypeInput, MOVE_CALL_TYPE_ARGS_ARRAY_LENGTH>,
+                        BS,
+                    >>::parse(
+                        &Breadcrumb("type_args", SubInterp(DefaultInterp)), input
                     )
                     .await;
-                    let args = <SubInterp<DefaultInterp> as AsyncParser<
+                    let args = <Breadcrumb<SubInterp<DefaultInterp>> as AsyncParser<
                         Vec<ArgumentSchema, MOVE_CALL_ARGS_ARRAY_LENGTH>,
                         BS,
-                    >>::parse(&SubInterp(DefaultInterp), input)
+                    >>::parse(
+                        &Breadcrumb("args", SubInterp(DefaultInterp)), input
+                    )
                     .await;
-                    Command::MoveCall(package, module, function, args)
+                    Command::MoveCall(package, module, function, Box::new(type_args), args)
                 }
                 1 => {
                     info!("CommandSchema: TransferObject");
+                    let _crumb = breadcrumbs::field("TransferObject");
                     let objects = <SubInterp<DefaultInterp> as AsyncParser<
                         Vec<ArgumentSchema, TRANSFER_OBJECT_ARRAY_LENGTH>,
                         BS,
@@ -340,6 +376,7 @@ impl<BS: Clone + Readable> AsyncParser<CommandSchema, BS> for DefaultInterp {
                 }
                 2 => {
                     info!("CommandSchema: SplitCoins");
+                    let _crumb = breadcrumbs::field("SplitCoins");
                     let coin = <DefaultInterp as AsyncParser<ArgumentSchema, BS>>::parse(
                         &DefaultInterp,
                         input,
@@ -354,6 +391,7 @@ impl<BS: Clone + Readable> AsyncParser<CommandSchema, BS> for DefaultInterp {
                 }
                 3 => {
                     info!("CommandSchema: MergeCoins");
+                    let _crumb = breadcrumbs::field("MergeCoins");
                     let destination_coin =
                         <DefaultInterp as AsyncParser<ArgumentSchema, BS>>::parse(
                             &DefaultInterp,
@@ -369,6 +407,7 @@ impl<BS: Clone + Readable> AsyncParser<CommandSchema, BS> for DefaultInterp {
                 }
                 5 => {
                     info!("CommandSchema: MakeMoveVec");
+                    let _crumb = breadcrumbs::field("MakeMoveVec");
                     // We don't support TypeInput, so we parse success only if
                     // the Option<TypeInput> is None (which is idential to a Vec of size 0)
                     <SubInterp<DefaultInterp> as AsyncParser<Vec<TypeInput, 0>, BS>>::parse(
@@ -397,70 +436,18 @@ impl<BS: Clone + Readable> AsyncParser<CommandSchema, BS> for DefaultInterp {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, BcsAsyncParser)]
+#[bcs(schema = ArgumentSchema, reject = SyscallError::NotSupported as u16, log = info)]
 pub enum Argument {
     GasCoin,
-    Input(u16),
-    Result(u16),
-    NestedResult(u16, u16),
-}
-
-impl HasOutput<ArgumentSchema> for DefaultInterp {
-    type Output = Argument;
-}
-
-impl<BS: Clone + Readable> AsyncParser<ArgumentSchema, BS> for DefaultInterp {
-    type State<'c>
-        = impl Future<Output = Self::Output> + 'c
-    where
-        BS: 'c;
-    fn parse<'a: 'c, 'b: 'c, 'c>(&'b self, input: &'a mut BS) -> Self::State<'c> {
-        async move {
-            let enum_variant =
-                <DefaultInterp as AsyncParser<ULEB128, BS>>::parse(&DefaultInterp, input).await;
-            match enum_variant {
-                0 => {
-                    info!("ArgumentSchema: GasCoin");
-                    Argument::GasCoin
-                }
-                1 => {
-                    info!("ArgumentSchema: Input");
-                    Argument::Input(
-                        <DefaultInterp as AsyncParser<U16LE, BS>>::parse(&DefaultInterp, input)
-                            .await,
-                    )
-                }
-                2 => {
-                    info!("ArgumentSchema: Result");
-                    Argument::Result(
-                        <DefaultInterp as AsyncParser<U16LE, BS>>::parse(&DefaultInterp, input)
-                            .await,
-                    )
-                }
-                3 => {
-                    info!("ArgumentSchema: NestedResult");
-                    Argument::NestedResult(
-                        <DefaultInterp as AsyncParser<U16LE, BS>>::parse(&DefaultInterp, input)
-                            .await,
-                        <DefaultInterp as AsyncParser<U16LE, BS>>::parse(&DefaultInterp, input)
-                            .await,
-                    )
-                }
-                _ => {
-                    reject_on(
-                        core::file!(),
-                        core::line!(),
-                        SyscallError::NotSupported as u16,
-                    )
-                    .await
-                }
-            }
-        }
-    }
+    Input(#[bcs(schema = U16LE)] u16),
+    Result(#[bcs(schema = U16LE)] u16),
+    NestedResult(#[bcs(schema = U16LE)] u16, #[bcs(schema = U16LE)] u16),
 }
 
-pub struct ProgrammableTransactionParser<OD> {
+pub struct ProgrammableTransactionParser<OD, MD> {
     object_data_source: OD,
+    move_call_descriptors: MD,
 }
 
 pub enum ProgrammableTransaction {
@@ -468,52 +455,144 @@ pub enum ProgrammableTransaction {
         recipient: <DefaultInterp as HasOutput<Recipient>>::Output,
         amount: <DefaultInterp as HasOutput<Amount>>::Output,
         includes_gas_coin: bool,
+        outputs: CoinOutputs,
     },
     TransferTokenTx {
         recipient: <DefaultInterp as HasOutput<Recipient>>::Output,
         amount: <DefaultInterp as HasOutput<Amount>>::Output,
         coin_type: CoinType,
+        outputs: CoinOutputs,
+    },
+    MergeCoinsTx {
+        coin_type: CoinType,
+        outputs: CoinOutputs,
     },
     StakeTx {
-        recipient: <DefaultInterp as HasOutput<Recipient>>::Output,
-        amount: <DefaultInterp as HasOutput<Amount>>::Output,
-        includes_gas_coin: bool,
+        stakes: ArrayVec<StakeAmount, MAX_STAKE_COUNT>,
     },
     UnstakeTx {
         total_amount: u64,
     },
+    RestakeTx {
+        source_stake: SuiAddressRaw,
+        recipient: <DefaultInterp as HasOutput<Recipient>>::Output,
+        amount: <DefaultInterp as HasOutput<Amount>>::Output,
+    },
+    DescribedMoveCallTx {
+        call: Box<DescribedMoveCall>,
+        // Where the results of the call are transferred, if they are
+        outputs_recipient: Option<SuiAddressRaw>,
+    },
+}
+
+// Maximum number of coins which we track individually in a merge/split of coins
+pub const MAX_COIN_OUTPUT_COUNT: usize = 8;
+
+// A coin owned by the sender at the end of the transaction
+#[derive(Clone)]
+pub struct CoinOutputAmount {
+    pub amount: u64,
+    pub includes_gas_coin: bool,
+}
+
+// Coins going in and out of a transaction which only merges or splits coins
+// The transfers are reported as such if the recipient is not the sender,
+// or if there are too many outputs to track
+pub struct CoinOutputs {
+    pub input_coin_count: usize,
+    pub amounts: Option<ArrayVec<CoinOutputAmount, MAX_COIN_OUTPUT_COUNT>>,
+    // An input coin was merged into and kept by the sender, so it is not in amounts
+    pub kept_merge_destination: bool,
+}
+
+// Maximum number of request_add_stake calls in a single StakeTx
+pub const MAX_STAKE_COUNT: usize = 4;
+
+pub struct StakeAmount {
+    pub validator: SuiAddressRaw,
+    pub amount: u64,
+    pub includes_gas_coin: bool,
 }
 
 // As we parse each Command we need to keep track of what kind of a transaction are we parsing
-// Currently only three are supported: TransferTx, StakeTx and UnstakeTx
-// Command::SplitCoins, Command::MergeCoins, and Command::MakeMoveVec can be present in any of the three
+// Currently only four are supported: TransferTx, StakeTx, UnstakeTx and RestakeTx
+// Command::SplitCoins, Command::MergeCoins, and Command::MakeMoveVec can be present in any of these
 // Command::TransferObject can be present only in TransferTx
-// Command::MoveCall can be present only in StakeTx/UnstakeTx
+// Command::MoveCall can be present only in StakeTx/UnstakeTx/RestakeTx
+// Multiple MoveCall are supported only in a StakeTx, to stake with several validators
+// A RestakeTx withdraws a stake and adds the withdrawn coin as a new stake, ie
+// request_withdraw_stake_non_entry, followed by coin::from_balance and request_add_stake
+// A DescribedMoveCallTx has a single MoveCall which has a descriptor, and its results can
+// only be transferred to the sender
 #[derive(PartialEq)]
 pub enum ProgrammableTransactionTypeState {
     UnknownTx,
     TransferTx,
     StakeTx,
     UnstakeTx,
+    RestakeTx,
+    DescribedMoveCallTx,
+}
+
+// The result of a MoveCall which determines the type of the transaction
+pub struct MoveCallTxInfo {
+    tx_type: ProgrammableTransactionTypeState,
+    total_amount: TotalCoinAmount,
+    validator: Option<SuiAddressRaw>,
+    source_stake: Option<SuiAddressRaw>,
 }
 
-impl<OD> HasOutput<ProgrammableTransactionSchema> for ProgrammableTransactionParser<OD> {
-    type Output = ProgrammableTransaction;
+// What a MoveCall adds to the transaction
+pub enum MoveCallOutput {
+    // The call determines the type of the transaction
+    TxInfo(MoveCallTxInfo),
+    // The call returns a value which is used by later commands
+    Result(CommandResult),
+    // The call is shown with its descriptor
+    Described(Box<DescribedMoveCall>),
+}
+
+// Maximum number of input object ids which are kept, to be shown in expert mode
+pub const MAX_INPUT_OBJECT_COUNT: usize = 8;
+
+// The ids of the objects used as inputs of the transaction
+#[derive(Default)]
+pub struct InputObjects {
+    pub ids: ArrayVec<SuiAddressRaw, MAX_INPUT_OBJECT_COUNT>,
+    // Total number of input objects, including those not in ids
+    pub count: usize,
+}
+
+impl InputObjects {
+    fn add(&mut self, id: SuiAddressRaw) {
+        self.count += 1;
+        let _ = self.ids.try_push(id);
+    }
 }
 
-impl<BS: Clone + Readable, OD: Clone + HasObjectData> AsyncParser<ProgrammableTransactionSchema, BS>
-    for ProgrammableTransactionParser<OD>
+impl<OD, MD> HasOutput<ProgrammableTransactionSchema> for ProgrammableTransactionParser<OD, MD> {
+    type Output = (ProgrammableTransaction, InputObjects);
+}
+
+impl<BS: Clone + Readable, OD: Clone + HasObjectData, MD: Clone + MoveCallDescriptors>
+    AsyncParser<ProgrammableTransactionSchema, BS> for ProgrammableTransactionParser<OD, MD>
 {
     type State<'c>
         = impl Future<Output = Self::Output> + 'c
     where
         BS: 'c,
-        OD: 'c;
+        OD: 'c,
+        MD: 'c;
     fn parse<'a: 'c, 'b: 'c, 'c>(&'b self, input: &'a mut BS) -> Self::State<'c> {
         async move {
             let mut inputs: BTreeMap<u16, InputValue> = BTreeMap::new();
+            let mut input_objects = InputObjects::default();
             let mut command_results: BTreeMap<u16, CommandResult> = BTreeMap::new();
 
+            // Input coins used by SplitCoins, MergeCoins and TransferObject
+            // None refers to the GasCoin
+            let mut input_coins: BTreeSet<Option<u16>> = BTreeSet::new();
+
             // By using heap we have the flexibility to handle transactions of various sizes
             // But if we exceed the heap usage it would crash the app while parsing the transaction.
             // It would be better to allow user to blind sign in such cases.
@@ -521,12 +600,14 @@ impl<BS: Clone + Readable, OD: Clone + HasObjectData> AsyncParser<ProgrammableTr
             async fn check_heap_use(
                 inputs: &BTreeMap<u16, InputValue>,
                 command_results: &BTreeMap<u16, CommandResult>,
+                input_coins: &BTreeSet<Option<u16>>,
             ) {
                 const MAX_HEAP_USAGE_ALLOWED: usize = 4800;
 
                 let v1 = estimate_btree_map_usage(inputs);
                 let v2 = estimate_btree_map_usage(command_results);
-                if v1 + v2 > MAX_HEAP_USAGE_ALLOWED {
+                let v3 = estimate_btree_set_usage(input_coins);
+                if v1 + v2 + v3 > MAX_HEAP_USAGE_ALLOWED {
                     info!("Heap usage exceeded during tx parse");
                     reject_on::<()>(
                         core::file!(),
@@ -541,12 +622,24 @@ impl<BS: Clone + Readable, OD: Clone + HasObjectData> AsyncParser<ProgrammableTr
             {
                 let length_u32 =
                     <DefaultInterp as AsyncParser<ULEB128, BS>>::parse(&DefaultInterp, input).await;
-                let length = u16::try_from(length_u32).expect("u16 expected");
+                let length = match u16::try_from(length_u32) {
+                    Ok(length) => length,
+                    Err(_) => {
+                        reject_on(
+                            core::file!(),
+                            core::line!(),
+                            SyscallError::NotSupported as u16,
+                        )
+                        .await
+                    }
+                };
 
                 info!("ProgrammableTransaction: Inputs: {}", length);
+                let _crumb = breadcrumbs::field("inputs");
                 for i in 0..length {
                     info!("Parsing input {}", i);
-                    check_heap_use(&inputs, &command_results).await;
+                    let _crumb = breadcrumbs::index(i as usize);
+                    check_heap_use(&inputs, &command_results, &input_coins).await;
                     let arg =
                         NoinlineFut(<DefaultInterp as AsyncParser<CallArgSchema, BS>>::parse(
                             &DefaultInterp,
@@ -569,12 +662,14 @@ impl<BS: Clone + Readable, OD: Clone + HasObjectData> AsyncParser<ProgrammableTr
                             info!("Input {}: OptionalAmount", i);
                             inputs.insert(i, InputValue::OptionalAmount(v));
                         }
-                        CallArg::ObjectRef(v) => {
+                        CallArg::ObjectRef(id, v) => {
                             info!("Input {}: ObjectRef", i);
-                            inputs.insert(i, InputValue::ObjectRef(v));
+                            input_objects.add(id);
+                            inputs.insert(i, InputValue::ObjectRef(id, v));
                         }
                         CallArg::SharedObject(v) => {
                             info!("Input {}: SharedObject", i);
+                            input_objects.add(v);
                             inputs.insert(i, InputValue::SharedObject(v));
                         }
                     }
@@ -590,12 +685,28 @@ impl<BS: Clone + Readable, OD: Clone + HasObjectData> AsyncParser<ProgrammableTr
                 .await;
             }
 
-            let mut recipient_addr = None;
+            // The validators and amounts, in case of a StakeTx
+            let mut stakes: ArrayVec<StakeAmount, MAX_STAKE_COUNT> = ArrayVec::new();
+
+            // The StakedSui object being withdrawn, in case of a RestakeTx
+            let mut source_stake_id: Option<SuiAddressRaw> = None;
+
+            // The coins being merged into, in case of a MergeCoinsTx
+            // Only input coins and the GasCoin are tracked, None refers to the GasCoin
+            let mut merge_coin_type: Option<CoinType> = None;
+            let mut merge_destinations: Option<ArrayVec<Option<u16>, MAX_COIN_OUTPUT_COUNT>> =
+                Some(ArrayVec::new());
+
+            // The input coins merged into, which stay with the sender unless they are transferred
+            // None if there are too many to track
+            let mut kept_merge_destinations: Option<ArrayVec<u16, MAX_COIN_OUTPUT_COUNT>> =
+                Some(ArrayVec::new());
 
-            // Total amount, that we know of, being transferred to recipient
-            // This does not contain the amount being transeferred from the
-            // GasCoin (in case the entire GasCoin is also being transferred)
-            let mut total_coin_amount: Option<TotalCoinAmount> = None;
+            let mut outputs = TxOutputs {
+                recipient_addr: None,
+                total_coin_amount: None,
+                transferred_coins: Some(ArrayVec::new()),
+            };
 
             // Amount added to GasCoin via MergeCoins
             // As we don't know the GasCoin coin balance, we only track how much
@@ -605,26 +716,42 @@ impl<BS: Clone + Readable, OD: Clone + HasObjectData> AsyncParser<ProgrammableTr
             let mut tx_type: ProgrammableTransactionTypeState =
                 ProgrammableTransactionTypeState::UnknownTx;
 
+            // The index of the command of a DescribedMoveCallTx, and its values
+            let mut described_call: Option<(u16, Box<DescribedMoveCall>)> = None;
+
             // Parse commands
             {
                 let length_u32 =
                     <DefaultInterp as AsyncParser<ULEB128, BS>>::parse(&DefaultInterp, input).await;
-                let length = u16::try_from(length_u32).expect("u16 expected");
+                let length = match u16::try_from(length_u32) {
+                    Ok(length) => length,
+                    Err(_) => {
+                        reject_on(
+                            core::file!(),
+                            core::line!(),
+                            SyscallError::NotSupported as u16,
+                        )
+                        .await
+                    }
+                };
                 info!("ProgrammableTransaction: Commands: {}", length);
+                let _crumb = breadcrumbs::field("commands");
                 for command_ix in 0..length {
-                    check_heap_use(&inputs, &command_results).await;
+                    let _crumb = breadcrumbs::index(command_ix as usize);
+                    check_heap_use(&inputs, &command_results, &input_coins).await;
                     let c = NoinlineFut(<DefaultInterp as AsyncParser<CommandSchema, BS>>::parse(
                         &DefaultInterp,
                         input,
                     ))
                     .await;
                     match c {
-                        Command::MoveCall(package, module, function, args) => {
+                        Command::MoveCall(package, module, function, type_args, args) => {
                             match tx_type {
-                                ProgrammableTransactionTypeState::UnknownTx => {}
+                                ProgrammableTransactionTypeState::UnknownTx
+                                | ProgrammableTransactionTypeState::StakeTx => {}
                                 _ => {
                                     // We don't support more than one MoveCall operation per tx
-                                    // (or MoveCall with TransferObject)
+                                    // (or MoveCall with TransferObject), except for stakes
                                     info!("MoveCall operation not supported");
                                     reject_on(
                                         core::file!(),
@@ -638,19 +765,38 @@ impl<BS: Clone + Readable, OD: Clone + HasObjectData> AsyncParser<ProgrammableTr
                                 package,
                                 module,
                                 function,
+                                &type_args,
                                 args,
-                                &inputs,
-                                self.object_data_source.clone(),
-                                &command_results,
+                                MoveCallContext {
+                                    inputs: &inputs,
+                                    object_data_source: self.object_data_source.clone(),
+                                    move_call_descriptors: &self.move_call_descriptors,
+                                    command_results: &command_results,
+                                },
                             ))
                             .await;
                             match res {
-                                Left((tx_type_, total_amt, maybe_validator_addr)) => {
+                                MoveCallOutput::TxInfo(MoveCallTxInfo {
+                                    tx_type: tx_type_,
+                                    total_amount: total_amt,
+                                    validator: maybe_validator_addr,
+                                    source_stake,
+                                }) => {
+                                    if tx_type == ProgrammableTransactionTypeState::StakeTx
+                                        && tx_type_ != ProgrammableTransactionTypeState::StakeTx
+                                    {
+                                        reject_on::<()>(
+                                            core::file!(),
+                                            core::line!(),
+                                            SyscallError::NotSupported as u16,
+                                        )
+                                        .await;
+                                    }
                                     tx_type = tx_type_;
                                     if tx_type == ProgrammableTransactionTypeState::StakeTx {
-                                        match (recipient_addr, maybe_validator_addr) {
-                                            (None, Some(addr)) => recipient_addr = Some(addr),
-                                            _ => {
+                                        let validator = match maybe_validator_addr {
+                                            Some(addr) => addr,
+                                            None => {
                                                 reject_on(
                                                     core::file!(),
                                                     core::line!(),
@@ -658,25 +804,120 @@ impl<BS: Clone + Readable, OD: Clone + HasObjectData> AsyncParser<ProgrammableTr
                                                 )
                                                 .await
                                             }
-                                        }
-                                    }
-                                    // As we only support one MoveCall,
-                                    // total_coin_amount should not be Some
-                                    match total_coin_amount {
-                                        None => total_coin_amount = Some(total_amt),
-                                        _ => {
-                                            reject_on(
+                                        };
+                                        let stake = StakeAmount {
+                                            validator,
+                                            amount: total_amt.total_amount,
+                                            includes_gas_coin: total_amt.includes_gas_coin,
+                                        };
+                                        if stakes.try_push(stake).is_err() {
+                                            info!("Too many stakes");
+                                            reject_on::<()>(
                                                 core::file!(),
                                                 core::line!(),
                                                 SyscallError::NotSupported as u16,
                                             )
-                                            .await
+                                            .await;
+                                        }
+                                        // Stakes are added up, they must all be SUI and
+                                        // at most one of them can include the GasCoin
+                                        outputs.total_coin_amount =
+                                            match outputs.total_coin_amount.take() {
+                                                None => Some(total_amt),
+                                                Some(t) => {
+                                                    match combine_total_coin_amounts(t, total_amt) {
+                                                        Some(v) => Some(v),
+                                                        None => {
+                                                            reject_on(
+                                                                core::file!(),
+                                                                core::line!(),
+                                                                SyscallError::NotSupported as u16,
+                                                            )
+                                                            .await
+                                                        }
+                                                    }
+                                                }
+                                            };
+                                    } else {
+                                        if tx_type == ProgrammableTransactionTypeState::RestakeTx {
+                                            match (outputs.recipient_addr, maybe_validator_addr) {
+                                                (None, Some(addr)) => {
+                                                    outputs.recipient_addr = Some(addr)
+                                                }
+                                                _ => {
+                                                    reject_on(
+                                                        core::file!(),
+                                                        core::line!(),
+                                                        SyscallError::NotSupported as u16,
+                                                    )
+                                                    .await
+                                                }
+                                            }
+                                        }
+                                        source_stake_id = source_stake;
+                                        // As we only support one MoveCall,
+                                        // total_coin_amount should not be Some
+                                        match outputs.total_coin_amount {
+                                            None => outputs.total_coin_amount = Some(total_amt),
+                                            _ => {
+                                                reject_on(
+                                                    core::file!(),
+                                                    core::line!(),
+                                                    SyscallError::NotSupported as u16,
+                                                )
+                                                .await
+                                            }
                                         }
                                     }
                                 }
-                                Right(v) => {
+                                MoveCallOutput::Result(v) => {
                                     command_results.insert(command_ix, v);
                                 }
+                                MoveCallOutput::Described(call) => {
+                                    if tx_type != ProgrammableTransactionTypeState::UnknownTx {
+                                        reject_on::<()>(
+                                            core::file!(),
+                                            core::line!(),
+                                            SyscallError::NotSupported as u16,
+                                        )
+                                        .await;
+                                    }
+                                    tx_type = ProgrammableTransactionTypeState::DescribedMoveCallTx;
+                                    described_call = Some((command_ix, call));
+                                }
+                            }
+                        }
+                        Command::TransferObject(coins, recipient_input)
+                            if tx_type == ProgrammableTransactionTypeState::DescribedMoveCallTx =>
+                        {
+                            // Results of the described call, whose recipient must be the
+                            // sender, which is checked once it is known
+                            let call_ix = described_call.as_ref().map(|(ix, _)| *ix);
+                            let from_call = coins.iter().all(|c| match c {
+                                Argument::Result(ix) | Argument::NestedResult(ix, _) => {
+                                    Some(*ix) == call_ix
+                                }
+                                _ => false,
+                            });
+                            let recipient = match recipient_input {
+                                Argument::Input(ix) => match inputs.get(&ix) {
+                                    Some(InputValue::RecipientAddress(addr)) => Some(*addr),
+                                    _ => None,
+                                },
+                                _ => None,
+                            };
+                            match recipient {
+                                Some(addr)
+                                    if from_call
+                                        && *outputs.recipient_addr.get_or_insert(addr) == addr => {}
+                                _ => {
+                                    reject_on(
+                                        core::file!(),
+                                        core::line!(),
+                                        SyscallError::NotSupported as u16,
+                                    )
+                                    .await
+                                }
                             }
                         }
                         Command::TransferObject(coins, recipient_input) => {
@@ -696,18 +937,24 @@ impl<BS: Clone + Readable, OD: Clone + HasObjectData> AsyncParser<ProgrammableTr
                                     .await
                                 }
                             }
+                            for coin in coins.iter() {
+                                add_input_coin(&mut input_coins, coin);
+                            }
+                            if let Some(ds) = kept_merge_destinations.as_mut() {
+                                ds.retain(|ix| !includes_input(&coins, *ix));
+                            }
                             NoinlineFut(handle_transfer_object(
                                 coins,
                                 recipient_input,
                                 &inputs,
-                                &mut recipient_addr,
-                                &mut total_coin_amount,
+                                &mut outputs,
                                 self.object_data_source.clone(),
                                 &command_results,
                             ))
                             .await;
                         }
                         Command::SplitCoins(coin, amounts) => {
+                            add_input_coin(&mut input_coins, &coin);
                             let res = NoinlineFut(handle_split_coins(
                                 coin,
                                 amounts,
@@ -719,7 +966,25 @@ impl<BS: Clone + Readable, OD: Clone + HasObjectData> AsyncParser<ProgrammableTr
                             command_results.insert(command_ix, res);
                         }
                         Command::MergeCoins(dest_coin, coins) => {
-                            NoinlineFut(handle_merge_coins(
+                            add_input_coin(&mut input_coins, &dest_coin);
+                            for coin in coins.iter() {
+                                add_input_coin(&mut input_coins, coin);
+                            }
+                            let dest = match dest_coin {
+                                Argument::GasCoin => Some(None),
+                                Argument::Input(ix) => Some(Some(ix)),
+                                _ => None,
+                            };
+                            if let Some(ds) = kept_merge_destinations.as_mut() {
+                                // The merged coins are consumed
+                                ds.retain(|ix| !includes_input(&coins, *ix));
+                                if let Argument::Input(ix) = dest_coin {
+                                    if !ds.contains(&ix) && ds.try_push(ix).is_err() {
+                                        kept_merge_destinations = None;
+                                    }
+                                }
+                            }
+                            let coin_type = NoinlineFut(handle_merge_coins(
                                 dest_coin,
                                 coins,
                                 &mut inputs,
@@ -728,6 +993,17 @@ impl<BS: Clone + Readable, OD: Clone + HasObjectData> AsyncParser<ProgrammableTr
                                 &mut added_amount_to_gas_coin,
                             ))
                             .await;
+                            // A MergeCoinsTx supports only a single coin type
+                            if *merge_coin_type.get_or_insert_with(|| coin_type.clone())
+                                != coin_type
+                            {
+                                merge_destinations = None;
+                            }
+                            if let (Some(ds), Some(d)) = (merge_destinations.as_mut(), dest) {
+                                if !ds.contains(&d) && ds.try_push(d).is_err() {
+                                    merge_destinations = None;
+                                }
+                            }
                         }
                         Command::MakeMoveVec(coins) => {
                             let res = NoinlineFut(handle_make_move_vec(
@@ -743,8 +1019,62 @@ impl<BS: Clone + Readable, OD: Clone + HasObjectData> AsyncParser<ProgrammableTr
                 }
             }
 
+            // A transaction which only merges coins into input coins or the GasCoin
+            if tx_type == ProgrammableTransactionTypeState::UnknownTx {
+                match (merge_coin_type, merge_destinations) {
+                    (Some(coin_type), Some(ds)) if !ds.is_empty() => {
+                        let mut amounts = ArrayVec::new();
+                        for d in ds {
+                            let output = match d {
+                                None => CoinOutputAmount {
+                                    amount: added_amount_to_gas_coin,
+                                    includes_gas_coin: true,
+                                },
+                                Some(ix) => match inputs.get(&ix) {
+                                    Some(InputValue::Object((_, amount))) => CoinOutputAmount {
+                                        amount: *amount,
+                                        includes_gas_coin: false,
+                                    },
+                                    _ => {
+                                        reject_on(
+                                            core::file!(),
+                                            core::line!(),
+                                            SyscallError::NotSupported as u16,
+                                        )
+                                        .await
+                                    }
+                                },
+                            };
+                            amounts.push(output);
+                        }
+                        return (
+                            ProgrammableTransaction::MergeCoinsTx {
+                                coin_type,
+                                outputs: CoinOutputs {
+                                    input_coin_count: input_coins.len(),
+                                    amounts: Some(amounts),
+                                    kept_merge_destination: false,
+                                },
+                            },
+                            input_objects,
+                        );
+                    }
+                    _ => {}
+                }
+            }
+
+            if let Some((_, call)) = described_call {
+                return (
+                    ProgrammableTransaction::DescribedMoveCallTx {
+                        call,
+                        outputs_recipient: outputs.recipient_addr,
+                    },
+                    input_objects,
+                );
+            }
+
             // We must have the coin_type info by now, irrespective of the tx type
-            let (coin_type, mut total_amount, includes_gas_coin) = match total_coin_amount {
+            let (coin_type, mut total_amount, includes_gas_coin) = match outputs.total_coin_amount {
                 Some(v) => (v.coin_type, v.total_amount, v.includes_gas_coin),
                 _ => {
                     reject_on(
@@ -756,9 +1086,11 @@ impl<BS: Clone + Readable, OD: Clone + HasObjectData> AsyncParser<ProgrammableTr
                 }
             };
 
-            match tx_type {
+            let kept_merge_destination = kept_merge_destinations.is_none_or(|ds| !ds.is_empty());
+
+            let tx = match tx_type {
                 ProgrammableTransactionTypeState::TransferTx => {
-                    let recipient = match recipient_addr {
+                    let recipient = match outputs.recipient_addr {
                         Some(addr) => addr,
                         _ => {
                             reject_on(
@@ -785,16 +1117,33 @@ impl<BS: Clone + Readable, OD: Clone + HasObjectData> AsyncParser<ProgrammableTr
                             recipient,
                             amount: total_amount,
                             coin_type,
+                            outputs: CoinOutputs {
+                                input_coin_count: input_coins.len(),
+                                amounts: outputs.transferred_coins,
+                                kept_merge_destination,
+                            },
                         }
                     } else {
                         if includes_gas_coin {
-                            total_amount += added_amount_to_gas_coin;
+                            add_coin_amount(&mut total_amount, added_amount_to_gas_coin).await;
+                        }
+
+                        // Same as above, for the GasCoin if it is transferred
+                        if let Some(coins) = outputs.transferred_coins.as_mut() {
+                            for c in coins.iter_mut().filter(|c| c.includes_gas_coin) {
+                                add_coin_amount(&mut c.amount, added_amount_to_gas_coin).await;
+                            }
                         }
 
                         ProgrammableTransaction::TransferSuiTx {
                             recipient,
                             amount: total_amount,
                             includes_gas_coin,
+                            outputs: CoinOutputs {
+                                input_coin_count: input_coins.len(),
+                                amounts: outputs.transferred_coins,
+                                kept_merge_destination,
+                            },
                         }
                     }
                 }
@@ -807,23 +1156,8 @@ impl<BS: Clone + Readable, OD: Clone + HasObjectData> AsyncParser<ProgrammableTr
                         )
                         .await
                     }
-                    let recipient = match recipient_addr {
-                        Some(addr) => addr,
-                        _ => {
-                            reject_on(
-                                core::file!(),
-                                core::line!(),
-                                SyscallError::NotSupported as u16,
-                            )
-                            .await
-                        }
-                    };
 
-                    ProgrammableTransaction::StakeTx {
-                        recipient,
-                        amount: total_amount,
-                        includes_gas_coin,
-                    }
+                    ProgrammableTransaction::StakeTx { stakes }
                 }
                 ProgrammableTransactionTypeState::UnstakeTx => {
                     if coin_type.0 != SUI_COIN_ID {
@@ -836,7 +1170,27 @@ impl<BS: Clone + Readable, OD: Clone + HasObjectData> AsyncParser<ProgrammableTr
                     }
                     ProgrammableTransaction::UnstakeTx { total_amount }
                 }
-                ProgrammableTransactionTypeState::UnknownTx => {
+                ProgrammableTransactionTypeState::RestakeTx => {
+                    match (outputs.recipient_addr, source_stake_id) {
+                        (Some(recipient), Some(source_stake)) if coin_type.0 == SUI_COIN_ID => {
+                            ProgrammableTransaction::RestakeTx {
+                                source_stake,
+                                recipient,
+                                amount: total_amount,
+                            }
+                        }
+                        _ => {
+                            reject_on(
+                                core::file!(),
+                                core::line!(),
+                                SyscallError::NotSupported as u16,
+                            )
+                            .await
+                        }
+                    }
+                }
+                ProgrammableTransactionTypeState::UnknownTx
+                | ProgrammableTransactionTypeState::DescribedMoveCallTx => {
                     reject_on(
                         core::file!(),
                         core::line!(),
@@ -844,28 +1198,68 @@ impl<BS: Clone + Readable, OD: Clone + HasObjectData> AsyncParser<ProgrammableTr
                     )
                     .await
                 }
-            }
+            };
+            (tx, input_objects)
         }
     }
 }
 
-async fn handle_move_call<OD: HasObjectData>(
+// The inputs and results of the earlier commands of a transaction, which its calls refer to
+struct MoveCallContext<'a, OD, MD> {
+    inputs: &'a BTreeMap<u16, InputValue>,
+    object_data_source: OD,
+    move_call_descriptors: &'a MD,
+    command_results: &'a BTreeMap<u16, CommandResult>,
+}
+
+async fn handle_move_call<OD: HasObjectData, MD: MoveCallDescriptors>(
     package: CoinID,
     module: ArrayVec<u8, STRING_LENGTH>,
     function: ArrayVec<u8, STRING_LENGTH>,
+    type_args: &[Option<CoinType>],
     args: ArrayVec<Argument, MOVE_CALL_ARGS_ARRAY_LENGTH>,
-    inputs: &BTreeMap<u16, InputValue>,
-    object_data_source: OD,
-    command_results: &BTreeMap<u16, CommandResult>,
-) -> Either<
-    (
-        ProgrammableTransactionTypeState,
-        TotalCoinAmount,
-        Option<SuiAddressRaw>,
-    ),
-    CommandResult,
-> {
-    if package != SUI_SYSTEM_ID {
+    context: MoveCallContext<'_, OD, MD>,
+) -> MoveCallOutput {
+    let MoveCallContext {
+        inputs,
+        object_data_source,
+        move_call_descriptors,
+        command_results,
+    } = context;
+    let target = match lookup_move_call_target(&package, &module, &function) {
+        Some(target) => target,
+        None => {
+            if let Some(descriptor) =
+                move_call_descriptors.get_descriptor(&package, &module, &function)
+            {
+                info!("MoveCall described by '{}'", descriptor.label.as_str());
+                return MoveCallOutput::Described(Box::new(
+                    handle_described_move_call(
+                        descriptor,
+                        type_args,
+                        &args,
+                        inputs,
+                        &object_data_source,
+                        command_results,
+                    )
+                    .await,
+                ));
+            }
+            info!(
+                "MoveCall NYI: '0x{}::{}::{}'",
+                HexSlice(&package),
+                core::str::from_utf8(module.as_slice()).unwrap_or("invalid utf-8"),
+                core::str::from_utf8(function.as_slice()).unwrap_or("invalid utf-8")
+            );
+            reject_on(
+                core::file!(),
+                core::line!(),
+                SyscallError::NotSupported as u16,
+            )
+            .await
+        }
+    };
+    if !check_arg_layout(&target, type_args, &args, inputs) {
         reject_on(
             core::file!(),
             core::line!(),
@@ -873,18 +1267,6 @@ async fn handle_move_call<OD: HasObjectData>(
         )
         .await
     }
-    fn is_sui_state(inp: &InputValue) -> Option<()> {
-        match inp {
-            InputValue::SharedObject(id_) => {
-                if *id_ == SUI_SYSTEM_STATE_ID {
-                    Some(())
-                } else {
-                    None
-                }
-            }
-            _ => None,
-        }
-    }
 
     let get_arg_input = |arg_ix: usize| -> Option<&InputValue> {
         args.get(arg_ix).and_then(|arg| match arg {
@@ -893,78 +1275,27 @@ async fn handle_move_call<OD: HasObjectData>(
         })
     };
 
-    if core::str::from_utf8(module.as_slice()) == Ok("sui_system")
-        && core::str::from_utf8(function.as_slice()) == Ok("request_add_stake")
-    {
-        info!("MoveCall 0x3::sui_system::request_add_stake");
+    match target.handler {
+        MoveCallHandler::CoinFromBalance => {
+            info!("MoveCall 0x2::coin::from_balance");
 
-        // Function args
-        // public entry fun request_add_stake(
-        //     wrapper: &mut SuiSystemState,
-        //     stake: Coin<SUI>,
-        //     validator_address: address,
-        //     ctx: &mut TxContext,
+            // Function args
+            // public fun from_balance<T>(balance: Balance<T>, ctx: &mut TxContext): Coin<T>
+            //
+            // Only the conversion of a withdrawn stake balance into Coin<SUI> is supported
 
-        if get_arg_input(0).and_then(is_sui_state).is_none() {
-            reject_on(
-                core::file!(),
-                core::line!(),
-                SyscallError::NotSupported as u16,
-            )
-            .await
-        }
-
-        // Obtain stake coin balance
-        let amt: CommandArgumentAmount = match args.get(1) {
-            None => {
-                reject_on(
-                    core::file!(),
-                    core::line!(),
-                    SyscallError::NotSupported as u16,
-                )
-                .await
-            }
-            Some(arg) => {
-                NoinlineFut(get_coin_arg_amount(
-                    arg,
-                    inputs,
-                    &object_data_source,
-                    command_results,
-                ))
-                .await
-            }
-        };
-
-        // Obtain validator_address
-        match get_arg_input(2) {
-            Some(InputValue::RecipientAddress(addr)) => Left((
-                ProgrammableTransactionTypeState::StakeTx,
-                to_total_coin_amount(amt),
-                Some(*addr),
-            )),
-            _ => {
-                reject_on(
-                    core::file!(),
-                    core::line!(),
-                    SyscallError::NotSupported as u16,
-                )
-                .await
+            match (type_args.first(), args.first()) {
+                (Some(Some(coin_type)), Some(Argument::Result(command_ix)))
+                    if *coin_type == SUI_COIN_TYPE =>
+                {
+                    if let Some(CommandResult::WithdrawnBalance(w)) =
+                        command_results.get(command_ix)
+                    {
+                        return MoveCallOutput::Result(CommandResult::WithdrawnCoin(w.clone()));
+                    }
+                }
+                _ => {}
             }
-        }
-    } else if core::str::from_utf8(module.as_slice()) == Ok("sui_system")
-        && core::str::from_utf8(function.as_slice()) == Ok("request_add_stake_mul_coin")
-    {
-        info!("MoveCall 0x3::sui_system::request_add_stake_mul_coin");
-
-        // Function args
-        // public entry fun request_add_stake_mul_coin(
-        //     wrapper: &mut SuiSystemState,
-        //     stakes: vector<Coin<SUI>>,
-        //     stake_amount: option::Option<u64>,
-        //     validator_address: address,
-        //     ctx: &mut TxContext,
-
-        if get_arg_input(0).and_then(is_sui_state).is_none() {
             reject_on(
                 core::file!(),
                 core::line!(),
@@ -972,81 +1303,303 @@ async fn handle_move_call<OD: HasObjectData>(
             )
             .await
         }
+        MoveCallHandler::RequestAddStake => {
+            info!("MoveCall 0x3::sui_system::request_add_stake");
+
+            // Function args
+            // public entry fun request_add_stake(
+            //     wrapper: &mut SuiSystemState,
+            //     stake: Coin<SUI>,
+            //     validator_address: address,
+            //     ctx: &mut TxContext,
+
+            // Obtain validator_address
+            let validator = match get_arg_input(2) {
+                Some(InputValue::RecipientAddress(addr)) => *addr,
+                _ => {
+                    reject_on(
+                        core::file!(),
+                        core::line!(),
+                        SyscallError::NotSupported as u16,
+                    )
+                    .await
+                }
+            };
 
-        // 'stakes' has to be a vector, ie a result of a MakeMoveVec
-        // We should already have the sum of amounts of all coins in the vector by now
-        let mut total_amt = if let Some(CommandResult::MoveVecMergedCoin(t)) =
-            args.get(1).and_then(|v| match v {
+            // Re-staking of a stake withdrawn earlier in this transaction
+            if let Some(CommandResult::WithdrawnCoin(w)) = args.get(1).and_then(|v| match v {
                 Argument::Result(ix) => command_results.get(ix),
                 _ => None,
             }) {
-            t.clone()
-        } else {
-            reject_on(
-                core::file!(),
-                core::line!(),
-                SyscallError::NotSupported as u16,
-            )
-            .await
-        };
-
-        // The stake_amount can be optionally specified by the user
-        // In the abscence of this the entire amount of 'stakes' will be staked
-        match get_arg_input(2) {
-            Some(InputValue::OptionalAmount(Some(amt))) => {
-                total_amt.total_amount = *amt;
+                return MoveCallOutput::TxInfo(MoveCallTxInfo {
+                    tx_type: ProgrammableTransactionTypeState::RestakeTx,
+                    total_amount: TotalCoinAmount {
+                        coin_type: SUI_COIN_TYPE,
+                        total_amount: w.amount,
+                        includes_gas_coin: false,
+                    },
+                    validator: Some(validator),
+                    source_stake: Some(w.source_stake),
+                });
             }
-            Some(InputValue::OptionalAmount(None)) => {}
-            _ => {
+
+            // Obtain stake coin balance
+            let amt: CommandArgumentAmount = match args.get(1) {
+                None => {
+                    reject_on(
+                        core::file!(),
+                        core::line!(),
+                        SyscallError::NotSupported as u16,
+                    )
+                    .await
+                }
+                Some(arg) => {
+                    NoinlineFut(get_coin_arg_amount(
+                        arg,
+                        inputs,
+                        &object_data_source,
+                        command_results,
+                    ))
+                    .await
+                }
+            };
+
+            MoveCallOutput::TxInfo(MoveCallTxInfo {
+                tx_type: ProgrammableTransactionTypeState::StakeTx,
+                total_amount: to_total_coin_amount(amt),
+                validator: Some(validator),
+                source_stake: None,
+            })
+        }
+        MoveCallHandler::RequestAddStakeMulCoin => {
+            info!("MoveCall 0x3::sui_system::request_add_stake_mul_coin");
+
+            // Function args
+            // public entry fun request_add_stake_mul_coin(
+            //     wrapper: &mut SuiSystemState,
+            //     stakes: vector<Coin<SUI>>,
+            //     stake_amount: option::Option<u64>,
+            //     validator_address: address,
+            //     ctx: &mut TxContext,
+
+            // 'stakes' has to be a vector, ie a result of a MakeMoveVec
+            // We should already have the sum of amounts of all coins in the vector by now
+            let mut total_amt = if let Some(CommandResult::MoveVecMergedCoin(t)) =
+                args.get(1).and_then(|v| match v {
+                    Argument::Result(ix) => command_results.get(ix),
+                    _ => None,
+                }) {
+                t.clone()
+            } else {
                 reject_on(
                     core::file!(),
                     core::line!(),
                     SyscallError::NotSupported as u16,
                 )
                 .await
+            };
+
+            // The stake_amount can be optionally specified by the user
+            // In the abscence of this the entire amount of 'stakes' will be staked
+            match get_arg_input(2) {
+                Some(InputValue::OptionalAmount(Some(amt))) => {
+                    total_amt.total_amount = *amt;
+                }
+                Some(InputValue::OptionalAmount(None)) => {}
+                _ => {
+                    reject_on(
+                        core::file!(),
+                        core::line!(),
+                        SyscallError::NotSupported as u16,
+                    )
+                    .await
+                }
+            }
+
+            // Obtain validator_address
+            match get_arg_input(3) {
+                Some(InputValue::RecipientAddress(addr)) => {
+                    MoveCallOutput::TxInfo(MoveCallTxInfo {
+                        tx_type: ProgrammableTransactionTypeState::StakeTx,
+                        total_amount: total_amt,
+                        validator: Some(*addr),
+                        source_stake: None,
+                    })
+                }
+                _ => {
+                    reject_on(
+                        core::file!(),
+                        core::line!(),
+                        SyscallError::NotSupported as u16,
+                    )
+                    .await
+                }
             }
         }
+        MoveCallHandler::RequestWithdrawStake | MoveCallHandler::RequestWithdrawStakeNonEntry => {
+            info!("MoveCall 0x3::sui_system::request_withdraw_stake");
+
+            // Function args
+            // public entry fun request_withdraw_stake(
+            //     wrapper: &mut SuiSystemState,
+            //     staked_sui: StakedSui,
+            //     ctx: &mut TxContext,
+            //
+            // public fun request_withdraw_stake_non_entry(
+            //     wrapper: &mut SuiSystemState,
+            //     staked_sui: StakedSui,
+            //     ctx: &mut TxContext,
+            // ) : Balance<SUI>
+
+            // Obtain staked_sui amount
+            // It is possible to unstake a part of staked amount by first doing
+            // 0x3::staking_pool::split on the staked sui coin
+            let (source_stake, total_amt) = match args.get(1) {
+                None => {
+                    reject_on(
+                        core::file!(),
+                        core::line!(),
+                        SyscallError::NotSupported as u16,
+                    )
+                    .await
+                }
+                Some(arg) => {
+                    if let Some((source_stake, amt)) = match arg {
+                        Argument::Result(command_ix) => match command_results.get(command_ix) {
+                            Some(CommandResult::StakingPoolSplitCoin(source_stake, amt)) => {
+                                Some((source_stake, amt))
+                            }
+                            _ => None,
+                        },
+                        _ => None,
+                    } {
+                        (
+                            *source_stake,
+                            TotalCoinAmount {
+                                coin_type: SUI_COIN_TYPE,
+                                total_amount: *amt,
+                                includes_gas_coin: false,
+                            },
+                        )
+                    } else {
+                        let source_stake = match arg {
+                            Argument::Input(ix) => match inputs.get(ix) {
+                                Some(InputValue::ObjectRef(id, _)) => Some(*id),
+                                _ => None,
+                            },
+                            _ => None,
+                        };
+                        (
+                            source_stake,
+                            to_total_coin_amount(
+                                NoinlineFut(get_coin_arg_amount(
+                                    arg,
+                                    inputs,
+                                    &object_data_source,
+                                    command_results,
+                                ))
+                                .await,
+                            ),
+                        )
+                    }
+                }
+            };
 
-        // Obtain validator_address
-        match get_arg_input(3) {
-            Some(InputValue::RecipientAddress(addr)) => Left((
-                ProgrammableTransactionTypeState::StakeTx,
-                total_amt,
-                Some(*addr),
-            )),
-            _ => {
-                reject_on(
-                    core::file!(),
-                    core::line!(),
-                    SyscallError::NotSupported as u16,
-                )
-                .await
+            if target.handler == MoveCallHandler::RequestWithdrawStake {
+                MoveCallOutput::TxInfo(MoveCallTxInfo {
+                    tx_type: ProgrammableTransactionTypeState::UnstakeTx,
+                    total_amount: total_amt,
+                    validator: None,
+                    source_stake,
+                })
+            } else {
+                // The withdrawn balance can only be used for a re-stake, so we need to know
+                // both the source and the amount of the stake
+                match (source_stake, total_amt) {
+                    (
+                        Some(source_stake),
+                        TotalCoinAmount {
+                            total_amount,
+                            coin_type,
+                            includes_gas_coin: false,
+                        },
+                    ) if coin_type == SUI_COIN_TYPE => {
+                        MoveCallOutput::Result(CommandResult::WithdrawnBalance(WithdrawnStake {
+                            source_stake,
+                            amount: total_amount,
+                        }))
+                    }
+                    _ => {
+                        reject_on(
+                            core::file!(),
+                            core::line!(),
+                            SyscallError::NotSupported as u16,
+                        )
+                        .await
+                    }
+                }
             }
         }
-    } else if core::str::from_utf8(module.as_slice()) == Ok("sui_system")
-        && core::str::from_utf8(function.as_slice()) == Ok("request_withdraw_stake")
-    {
-        info!("MoveCall 0x3::sui_system::request_withdraw_stake");
-
-        // Function args
-        // public entry fun request_withdraw_stake(
-        //     wrapper: &mut SuiSystemState,
-        //     staked_sui: StakedSui,
-        //     ctx: &mut TxContext,
-
-        if get_arg_input(0).and_then(is_sui_state).is_none() {
-            reject_on(
-                core::file!(),
-                core::line!(),
-                SyscallError::NotSupported as u16,
-            )
-            .await
+        MoveCallHandler::StakingPoolSplit => {
+            info!("MoveCall 0x3::staking_pool::split");
+
+            // We do not need to check the balance or CoinID of coin
+            // As incorrect values will be rejected on chain
+            // But we keep track of the StakedSui object id, to show it in case of a re-stake
+            let source_stake = match get_arg_input(0) {
+                Some(InputValue::ObjectRef(id, _)) => Some(*id),
+                _ => None,
+            };
+
+            match get_arg_input(1) {
+                Some(InputValue::Amount(amt)) => {
+                    MoveCallOutput::Result(CommandResult::StakingPoolSplitCoin(source_stake, *amt))
+                }
+                _ => {
+                    reject_on(
+                        core::file!(),
+                        core::line!(),
+                        SyscallError::NotSupported as u16,
+                    )
+                    .await
+                }
+            }
         }
+    }
+}
 
-        // Obtain staked_sui amount
-        // It is possible to unstake a part of staked amount by first doing
-        // 0x3::staking_pool::split on the staked sui coin
-        let total_amt = match args.get(1) {
+// Obtain the values of the arguments shown by the descriptor of a call
+async fn handle_described_move_call<OD: HasObjectData>(
+    descriptor: &MoveCallDescriptor,
+    type_args: &[Option<CoinType>],
+    args: &[Argument],
+    inputs: &BTreeMap<u16, InputValue>,
+    object_data_source: &OD,
+    command_results: &BTreeMap<u16, CommandResult>,
+) -> DescribedMoveCall {
+    // Each argument is described once, so that no coin, nor result of an earlier command, goes
+    // into the call without being shown
+    let described_once = |ix: usize| {
+        descriptor
+            .args
+            .iter()
+            .filter(|arg_descriptor| arg_descriptor.arg as usize == ix)
+            .count()
+            == 1
+    };
+    if args.len() != descriptor.args.len() || !(0..args.len()).all(described_once) {
+        reject_on::<()>(
+            core::file!(),
+            core::line!(),
+            SyscallError::NotSupported as u16,
+        )
+        .await;
+    }
+
+    let mut described_args = ArrayVec::new();
+    for arg_descriptor in descriptor.args.iter() {
+        let arg = match args.get(arg_descriptor.arg as usize) {
+            Some(arg) => arg,
             None => {
                 reject_on(
                     core::file!(),
@@ -1055,44 +1608,58 @@ async fn handle_move_call<OD: HasObjectData>(
                 )
                 .await
             }
-            Some(arg) => {
-                if let Some(amt) = match arg {
-                    Argument::Result(command_ix) => match command_results.get(command_ix) {
-                        Some(CommandResult::StakingPoolSplitCoin(amt)) => Some(amt),
-                        _ => None,
-                    },
-                    _ => None,
-                } {
-                    TotalCoinAmount {
-                        coin_type: SUI_COIN_TYPE,
-                        total_amount: *amt,
-                        includes_gas_coin: false,
+        };
+        let input = match arg {
+            Argument::Input(ix) => inputs.get(ix),
+            _ => None,
+        };
+        let value = match (arg_descriptor.format, input) {
+            (ArgFormat::Amount { type_arg }, _) => {
+                let coin_type = match type_args.get(type_arg as usize) {
+                    Some(Some(coin_type)) => coin_type.clone(),
+                    _ => {
+                        reject_on(
+                            core::file!(),
+                            core::line!(),
+                            SyscallError::NotSupported as u16,
+                        )
+                        .await
                     }
-                } else {
-                    to_total_coin_amount(
-                        NoinlineFut(get_coin_arg_amount(
-                            arg,
-                            inputs,
-                            &object_data_source,
-                            command_results,
-                        ))
-                        .await,
-                    )
-                }
+                };
+                let amount = match input {
+                    Some(InputValue::Amount(amount)) => *amount,
+                    _ => {
+                        match get_coin_arg_amount(arg, inputs, object_data_source, command_results)
+                            .await
+                        {
+                            CommandArgumentAmount::Coin {
+                                coin_type: coin_type_,
+                                amount,
+                            } if coin_type_ == coin_type => amount,
+                            // The amount of the GasCoin is not known
+                            _ => {
+                                reject_on(
+                                    core::file!(),
+                                    core::line!(),
+                                    SyscallError::NotSupported as u16,
+                                )
+                                .await
+                            }
+                        }
+                    }
+                };
+                DescribedValue::Amount(amount, coin_type)
+            }
+            (ArgFormat::Address, Some(InputValue::RecipientAddress(addr))) => {
+                DescribedValue::Address(*addr)
+            }
+            (
+                ArgFormat::Object,
+                Some(InputValue::ObjectRef(id, _)) | Some(InputValue::SharedObject(id)),
+            ) => DescribedValue::Object(*id),
+            (ArgFormat::Percentage, Some(InputValue::Amount(basis_points))) => {
+                DescribedValue::Percentage(*basis_points)
             }
-        };
-
-        Left((ProgrammableTransactionTypeState::UnstakeTx, total_amt, None))
-    } else if core::str::from_utf8(module.as_slice()) == Ok("staking_pool")
-        && core::str::from_utf8(function.as_slice()) == Ok("split")
-    {
-        info!("MoveCall 0x3::staking_pool::split");
-
-        // We do not need to check the balance or CoinID of coin
-        // As incorrect values will be rejected on chain
-
-        match get_arg_input(1) {
-            Some(InputValue::Amount(amt)) => Right(CommandResult::StakingPoolSplitCoin(*amt)),
             _ => {
                 reject_on(
                     core::file!(),
@@ -1101,21 +1668,52 @@ async fn handle_move_call<OD: HasObjectData>(
                 )
                 .await
             }
-        }
-    } else {
-        info!(
-            "MoveCall NYI: '0x{}::{}::{}'",
-            HexSlice(&package),
-            core::str::from_utf8(module.as_slice()).unwrap_or("invalid utf-8"),
-            core::str::from_utf8(function.as_slice()).unwrap_or("invalid utf-8")
-        );
-        reject_on(
-            core::file!(),
-            core::line!(),
-            SyscallError::NotSupported as u16,
-        )
-        .await
+        };
+        // There are as many described args as args of the descriptor
+        described_args.push((arg_descriptor.label, value));
     }
+    DescribedMoveCall {
+        label: descriptor.label,
+        args: described_args,
+    }
+}
+
+// Checks the arguments of a call against the layout of its target, before its handler looks at
+// them
+fn check_arg_layout(
+    target: &MoveCallTarget,
+    type_args: &[Option<CoinType>],
+    args: &[Argument],
+    inputs: &BTreeMap<u16, InputValue>,
+) -> bool {
+    type_args.len() == target.type_args
+        && args.len() == target.args.len()
+        && target.args.iter().zip(args).all(|(kind, arg)| {
+            let input = match arg {
+                Argument::Input(ix) => inputs.get(ix),
+                _ => None,
+            };
+            match kind {
+                MoveArg::SuiSystemState => {
+                    matches!(input, Some(InputValue::SharedObject(id)) if *id == SUI_SYSTEM_STATE_ID)
+                }
+                MoveArg::Address => matches!(input, Some(InputValue::RecipientAddress(_))),
+                MoveArg::Amount => matches!(input, Some(InputValue::Amount(_))),
+                MoveArg::OptionalAmount => matches!(input, Some(InputValue::OptionalAmount(_))),
+                MoveArg::Value => true,
+            }
+        })
+}
+
+// The recipient and the coins, that we know of, leaving the sender
+struct TxOutputs {
+    recipient_addr: Option<SuiAddressRaw>,
+    // Total amount, that we know of, being transferred to recipient
+    // This does not contain the amount being transeferred from the
+    // GasCoin (in case the entire GasCoin is also being transferred)
+    total_coin_amount: Option<TotalCoinAmount>,
+    // Amounts of the coins being transferred, None if there are too many of them
+    transferred_coins: Option<ArrayVec<CoinOutputAmount, MAX_COIN_OUTPUT_COUNT>>,
 }
 
 // Obtain the recipient address and total value of coins being transferred
@@ -1123,16 +1721,15 @@ async fn handle_transfer_object<OD: HasObjectData>(
     coins: ArrayVec<Argument, TRANSFER_OBJECT_ARRAY_LENGTH>,
     recipient_input: Argument,
     inputs: &BTreeMap<u16, InputValue>,
-    recipient_addr: &mut Option<SuiAddressRaw>,
-    total_coin_amount: &mut Option<TotalCoinAmount>,
+    outputs: &mut TxOutputs,
     object_data_source: OD,
     command_results: &BTreeMap<u16, CommandResult>,
 ) {
     match recipient_input {
         Argument::Input(inp_index) => match inputs.get(&inp_index) {
-            Some(InputValue::RecipientAddress(addr)) => match recipient_addr {
+            Some(InputValue::RecipientAddress(addr)) => match outputs.recipient_addr {
                 Some(addr_) => {
-                    if *addr != *addr_ {
+                    if *addr != addr_ {
                         info!("TransferObject multiple recipients");
                         reject_on::<()>(
                             core::file!(),
@@ -1142,7 +1739,7 @@ async fn handle_transfer_object<OD: HasObjectData>(
                         .await;
                     }
                 }
-                None => *recipient_addr = Some(*addr),
+                None => outputs.recipient_addr = Some(*addr),
             },
             _ => {
                 info!("TransferObject invalid inp_index");
@@ -1164,13 +1761,30 @@ async fn handle_transfer_object<OD: HasObjectData>(
         }
     }
     // set total_amount
-    *total_coin_amount = Some(
+    outputs.total_coin_amount = Some(
         get_total_amount_for_coins(
             coins.as_slice(),
-            total_coin_amount.clone(),
+            outputs.total_coin_amount.clone(),
             inputs,
             &object_data_source,
             command_results,
+            |amt| {
+                let output = match amt {
+                    CommandArgumentAmount::GasCoin => CoinOutputAmount {
+                        amount: 0,
+                        includes_gas_coin: true,
+                    },
+                    CommandArgumentAmount::Coin { amount, .. } => CoinOutputAmount {
+                        amount: *amount,
+                        includes_gas_coin: false,
+                    },
+                };
+                if let Some(coins) = outputs.transferred_coins.as_mut() {
+                    if coins.try_push(output).is_err() {
+                        outputs.transferred_coins = None;
+                    }
+                }
+            },
         )
         .await,
     );
@@ -1225,7 +1839,7 @@ fn add_to_total_coin_amount(
                 None
             } else {
                 Some(TotalCoinAmount {
-                    total_amount: t.total_amount + amount,
+                    total_amount: t.total_amount.checked_add(amount)?,
                     ..t
                 })
             }
@@ -1233,6 +1847,18 @@ fn add_to_total_coin_amount(
     }
 }
 
+fn combine_total_coin_amounts(t: TotalCoinAmount, u: TotalCoinAmount) -> Option<TotalCoinAmount> {
+    if t.coin_type != u.coin_type || (t.includes_gas_coin && u.includes_gas_coin) {
+        None
+    } else {
+        Some(TotalCoinAmount {
+            total_amount: t.total_amount.checked_add(u.total_amount)?,
+            includes_gas_coin: t.includes_gas_coin || u.includes_gas_coin,
+            ..t
+        })
+    }
+}
+
 // Add up the amount from all coins, while checking that the coin types match
 async fn get_total_amount_for_coins<OD: HasObjectData>(
     coins: &[Argument],
@@ -1240,9 +1866,11 @@ async fn get_total_amount_for_coins<OD: HasObjectData>(
     inputs: &BTreeMap<u16, InputValue>,
     object_data_source: &OD,
     command_results: &BTreeMap<u16, CommandResult>,
+    mut on_coin_amount: impl FnMut(&CommandArgumentAmount),
 ) -> TotalCoinAmount {
     if let Some((first, remaining)) = coins.split_first() {
         let amt = get_coin_arg_amount(first, inputs, object_data_source, command_results).await;
+        on_coin_amount(&amt);
         let mut total_amount = match maybe_total_amount {
             None => to_total_coin_amount(amt),
             Some(t) => match add_to_total_coin_amount(t, amt) {
@@ -1260,6 +1888,7 @@ async fn get_total_amount_for_coins<OD: HasObjectData>(
 
         for coin in remaining {
             let amt = get_coin_arg_amount(coin, inputs, object_data_source, command_results).await;
+            on_coin_amount(&amt);
             match add_to_total_coin_amount(total_amount.clone(), amt) {
                 Some(v) => total_amount = v,
                 None => {
@@ -1297,7 +1926,7 @@ async fn get_coin_arg_amount<OD: HasObjectData>(
     match coin {
         Argument::GasCoin => CommandArgumentAmount::GasCoin,
         Argument::Input(input_ix) => match inputs.get(input_ix) {
-            Some(InputValue::ObjectRef(digest)) => {
+            Some(InputValue::ObjectRef(_, digest)) => {
                 info!("get_coin_arg_amount trying object_data_source");
                 let coin_data = object_data_source.get_object_data(digest).await;
                 match coin_data {
@@ -1406,7 +2035,7 @@ async fn handle_split_coins<OD: HasObjectData>(
     let coin_type = match coin {
         Argument::GasCoin => SUI_COIN_TYPE,
         Argument::Input(input_ix) => match inputs.get(&input_ix) {
-            Some(InputValue::ObjectRef(digest)) => {
+            Some(InputValue::ObjectRef(_, digest)) => {
                 info!("SplitCoins trying object_data_source");
                 let coin_data = object_data_source.get_object_data(digest).await;
                 match coin_data {
@@ -1504,17 +2133,17 @@ async fn handle_merge_coins<OD: HasObjectData>(
     object_data_source: OD,
     command_results: &mut BTreeMap<u16, CommandResult>,
     added_amount_to_gas_coin: &mut u64,
-) {
+) -> CoinType {
     let mut total_amount_2: u64 = 0;
     let coin_type = match dest_coin {
         Argument::GasCoin => SUI_COIN_TYPE,
         Argument::Input(input_ix) => match inputs.get(&input_ix) {
-            Some(InputValue::ObjectRef(digest)) => {
+            Some(InputValue::ObjectRef(_, digest)) => {
                 info!("MergeCoins trying object_data_source");
                 let coin_data = object_data_source.get_object_data(digest).await;
                 match coin_data {
                     Some((v, amt)) => {
-                        total_amount_2 += amt;
+                        add_coin_amount(&mut total_amount_2, amt).await;
                         v
                     }
                     _ => {
@@ -1529,7 +2158,7 @@ async fn handle_merge_coins<OD: HasObjectData>(
                 }
             }
             Some(InputValue::Object((coin_type, amt))) => {
-                total_amount_2 += amt;
+                add_coin_amount(&mut total_amount_2, *amt).await;
                 coin_type.clone()
             }
             _ => {
@@ -1552,7 +2181,7 @@ async fn handle_merge_coins<OD: HasObjectData>(
                     _ => None,
                 })
             {
-                total_amount_2 += amt;
+                add_coin_amount(&mut total_amount_2, *amt).await;
                 v.clone()
             } else {
                 reject_on(
@@ -1585,7 +2214,7 @@ async fn handle_merge_coins<OD: HasObjectData>(
                 .await
             }
             Argument::Input(input_ix) => match inputs.get(input_ix) {
-                Some(InputValue::ObjectRef(digest)) => {
+                Some(InputValue::ObjectRef(_, digest)) => {
                     info!("MergeCoins trying object_data_source");
                     let coin_data = object_data_source.get_object_data(digest).await;
                     match coin_data {
@@ -1599,7 +2228,7 @@ async fn handle_merge_coins<OD: HasObjectData>(
                                 )
                                 .await
                             }
-                            total_amount_2 += amt;
+                            add_coin_amount(&mut total_amount_2, amt).await;
                         }
                         _ => {
                             info!("MergeCoins Coin Object not found");
@@ -1622,7 +2251,7 @@ async fn handle_merge_coins<OD: HasObjectData>(
                         )
                         .await
                     }
-                    total_amount_2 += amt;
+                    add_coin_amount(&mut total_amount_2, *amt).await;
                 }
                 _ => {
                     info!("MergeCoins input refers to non ObjectRef");
@@ -1648,7 +2277,7 @@ async fn handle_merge_coins<OD: HasObjectData>(
                         _ => None,
                     })
                 {
-                    total_amount_2 += amt;
+                    add_coin_amount(&mut total_amount_2, *amt).await;
                 } else {
                     reject_on(
                         core::file!(),
@@ -1669,7 +2298,7 @@ async fn handle_merge_coins<OD: HasObjectData>(
                         .await
                     }
                     for amt in coin_amounts {
-                        total_amount_2 += amt;
+                        add_coin_amount(&mut total_amount_2, *amt).await;
                     }
                 }
                 Some(CommandResult::MergedCoin((coin_type_, amt))) => {
@@ -1681,7 +2310,7 @@ async fn handle_merge_coins<OD: HasObjectData>(
                         )
                         .await
                     }
-                    total_amount_2 += amt;
+                    add_coin_amount(&mut total_amount_2, *amt).await;
                 }
                 _ => {
                     reject_on(
@@ -1698,15 +2327,18 @@ async fn handle_merge_coins<OD: HasObjectData>(
     // MergeCoins does an overwrite of existing coins
     match dest_coin {
         Argument::GasCoin => {
-            *added_amount_to_gas_coin += total_amount_2;
+            add_coin_amount(added_amount_to_gas_coin, total_amount_2).await;
         }
         Argument::Input(input_ix) => {
-            inputs.insert(input_ix, InputValue::Object((coin_type, total_amount_2)));
+            inputs.insert(
+                input_ix,
+                InputValue::Object((coin_type.clone(), total_amount_2)),
+            );
         }
         Argument::Result(command_ix) => {
             command_results.insert(
                 command_ix,
-                CommandResult::MergedCoin((coin_type, total_amount_2)),
+                CommandResult::MergedCoin((coin_type.clone(), total_amount_2)),
             );
         }
         Argument::NestedResult(command_ix, coin_ix) => {
@@ -1717,6 +2349,42 @@ async fn handle_merge_coins<OD: HasObjectData>(
             };
         }
     }
+    coin_type
+}
+
+// Adds a coin amount to a total, the transaction is rejected if the total overflows
+async fn add_coin_amount(total: &mut u64, amount: u64) {
+    match total.checked_add(amount) {
+        Some(v) => *total = v,
+        None => {
+            info!("Coin amount overflow");
+            reject_on(
+                core::file!(),
+                core::line!(),
+                SyscallError::NotSupported as u16,
+            )
+            .await
+        }
+    }
+}
+
+// Whether the coins include the input with the given index
+fn includes_input(coins: &[Argument], ix: u16) -> bool {
+    coins
+        .iter()
+        .any(|c| matches!(c, Argument::Input(i) if *i == ix))
+}
+
+fn add_input_coin(input_coins: &mut BTreeSet<Option<u16>>, coin: &Argument) {
+    match coin {
+        Argument::GasCoin => {
+            input_coins.insert(None);
+        }
+        Argument::Input(ix) => {
+            input_coins.insert(Some(*ix));
+        }
+        _ => {}
+    }
 }
 
 // Obtains the total amount of all coins which are part of the resultant vector and the coin type
@@ -1732,28 +2400,31 @@ async fn handle_make_move_vec<OD: HasObjectData>(
         inputs,
         &object_data_source,
         command_results,
+        |_| {},
     )
     .await;
     CommandResult::MoveVecMergedCoin(total_coin_amount)
 }
 
-pub struct TransactionKindParser<OD> {
+pub struct TransactionKindParser<OD, MD> {
     object_data_source: OD,
+    move_call_descriptors: MD,
 }
 
-impl<OD> HasOutput<TransactionKindSchema> for TransactionKindParser<OD> {
+impl<OD, MD> HasOutput<TransactionKindSchema> for TransactionKindParser<OD, MD> {
     type Output =
-        <ProgrammableTransactionParser<OD> as HasOutput<ProgrammableTransactionSchema>>::Output;
+        <ProgrammableTransactionParser<OD, MD> as HasOutput<ProgrammableTransactionSchema>>::Output;
 }
 
-impl<BS: Clone + Readable, OD: Clone + HasObjectData> AsyncParser<TransactionKindSchema, BS>
-    for TransactionKindParser<OD>
+impl<BS: Clone + Readable, OD: Clone + HasObjectData, MD: Clone + MoveCallDescriptors>
+    AsyncParser<TransactionKindSchema, BS> for TransactionKindParser<OD, MD>
 {
     type State<'c>
         = impl Future<Output = Self::Output> + 'c
     where
         BS: 'c,
-        OD: 'c;
+        OD: 'c,
+        MD: 'c;
     fn parse<'a: 'c, 'b: 'c, 'c>(&'b self, input: &'a mut BS) -> Self::State<'c> {
         async move {
             let enum_variant =
@@ -1761,12 +2432,14 @@ impl<BS: Clone + Readable, OD: Clone + HasObjectData> AsyncParser<TransactionKin
             match enum_variant {
                 0 => {
                     info!("TransactionKind: ProgrammableTransaction");
-                    <ProgrammableTransactionParser<OD> as AsyncParser<
+                    let _crumb = breadcrumbs::field("PTB");
+                    <ProgrammableTransactionParser<OD, MD> as AsyncParser<
                         ProgrammableTransactionSchema,
                         BS,
                     >>::parse(
                         &ProgrammableTransactionParser {
                             object_data_source: self.object_data_source.clone(),
+                            move_call_descriptors: self.move_call_descriptors.clone(),
                         },
                         input,
                     )
@@ -1787,7 +2460,8 @@ impl<BS: Clone + Readable, OD: Clone + HasObjectData> AsyncParser<TransactionKin
 }
 
 impl HasOutput<TransactionExpiration> for DefaultInterp {
-    type Output = ();
+    // The epoch, if any
+    type Output = Option<u64>;
 }
 
 impl<BS: Clone + Readable> AsyncParser<TransactionExpiration, BS> for DefaultInterp {
@@ -1802,10 +2476,14 @@ impl<BS: Clone + Readable> AsyncParser<TransactionExpiration, BS> for DefaultInt
             match enum_variant {
                 0 => {
                     info!("TransactionExpiration: None");
+                    None
                 }
                 1 => {
                     info!("TransactionExpiration: Epoch");
-                    <DefaultInterp as AsyncParser<EpochId, BS>>::parse(&DefaultInterp, input).await;
+                    Some(
+                        <DefaultInterp as AsyncParser<EpochId, BS>>::parse(&DefaultInterp, input)
+                            .await,
+                    )
                 }
                 _ => {
                     reject_on(
@@ -1820,7 +2498,12 @@ impl<BS: Clone + Readable> AsyncParser<TransactionExpiration, BS> for DefaultInt
     }
 }
 
-pub type GasDataParserOutput = (ArrayVec<ObjectDigest, MAX_GAS_COIN_COUNT>, u64);
+pub type GasDataParserOutput = (
+    ArrayVec<ObjectDigest, MAX_GAS_COIN_COUNT>, // payment
+    SuiAddressRaw,                              // owner
+    u64,                                        // price
+    u64,                                        // budget
+);
 
 const fn gas_data_parser<BS: Clone + Readable>(
 ) -> impl AsyncParser<GasDataSchema, BS, Output = GasDataParserOutput> {
@@ -1832,13 +2515,13 @@ const fn gas_data_parser<BS: Clone + Readable>(
             DefaultInterp,
         ),
         {
-            |(coins, _sender, _gas_price, gas_budget): (_, _, u64, u64)| {
+            |(coins, gas_owner, gas_price, gas_budget): (_, _, u64, u64)| {
                 // Gas price is per gas amount. Gas budget is total, reflecting the amount of gas *
-                // gas price. We only care about the total, not the price or amount in isolation , so we
-                // just ignore that field.
+                // gas price. We only care about the total, not the price or amount in isolation, so
+                // the price is only shown in expert mode.
                 //
                 // C.F. https://github.com/MystenLabs/sui/pull/8676
-                Some((coins, gas_budget))
+                Some((coins, gas_owner, gas_price, gas_budget))
             }
         },
     )
@@ -1852,6 +2535,14 @@ const fn object_ref_parser<BS: Readable>(
     )
 }
 
+const fn object_ref_with_id_parser<BS: Readable>(
+) -> impl AsyncParser<ObjectRefSchema, BS, Output = (SuiAddressRaw, ObjectDigest)> {
+    Action(
+        (DefaultInterp, DefaultInterp, DefaultInterp),
+        |(id, _, d)| Some((id, d)),
+    )
+}
+
 const fn intent_parser<BS: Readable>() -> impl AsyncParser<Intent, BS, Output = ()> {
     Action(
         (DefaultInterp, DefaultInterp, DefaultInterp),
@@ -1867,27 +2558,40 @@ const fn intent_parser<BS: Readable>() -> impl AsyncParser<Intent, BS, Output =
     )
 }
 
-type TransactionDataV1Output<OD> = (
-    <TransactionKindParser<OD> as HasOutput<TransactionKindSchema>>::Output,
+// Transaction data which is not part of the review, but is shown in expert mode
+pub struct TxDetails {
+    pub gas_price: u64,
+    pub gas_owner: SuiAddressRaw,
+    pub expiration: Option<u64>,
+    pub gas_coin_count: usize,
+    pub input_objects: InputObjects,
+}
+
+type TransactionDataV1Output = (
+    ProgrammableTransaction,
+    SuiAddressRaw, // sender
     GasData,
+    TxDetails,
 );
 
-pub struct TransactionDataParser<OD> {
+pub struct TransactionDataParser<OD, MD> {
     object_data_source: OD,
+    move_call_descriptors: MD,
 }
 
-impl<OD> HasOutput<TransactionDataSchema> for TransactionDataParser<OD> {
-    type Output = TransactionDataV1Output<OD>;
+impl<OD, MD> HasOutput<TransactionDataSchema> for TransactionDataParser<OD, MD> {
+    type Output = TransactionDataV1Output;
 }
 
-impl<BS: Clone + Readable, OD: Clone + HasObjectData> AsyncParser<TransactionDataSchema, BS>
-    for TransactionDataParser<OD>
+impl<BS: Clone + Readable, OD: Clone + HasObjectData, MD: Clone + MoveCallDescriptors>
+    AsyncParser<TransactionDataSchema, BS> for TransactionDataParser<OD, MD>
 {
     type State<'c>
         = impl Future<Output = Self::Output> + 'c
     where
         BS: 'c,
-        OD: 'c;
+        OD: 'c,
+        MD: 'c;
     fn parse<'a: 'c, 'b: 'c, 'c>(&'b self, input: &'a mut BS) -> Self::State<'c> {
         info!("====================> TransactionDataParser::parse");
         async move {
@@ -1896,16 +2600,26 @@ impl<BS: Clone + Readable, OD: Clone + HasObjectData> AsyncParser<TransactionDat
             match enum_variant {
                 0 => {
                     info!("TransactionData: V1");
-                    let v = (TransactionKindParser {
-                        object_data_source: self.object_data_source.clone(),
-                    })
-                    .parse(input)
-                    .await;
+                    let _crumb = breadcrumbs::field("V1");
+                    let (v, input_objects) = {
+                        let _crumb = breadcrumbs::field("Kind");
+                        (TransactionKindParser {
+                            object_data_source: self.object_data_source.clone(),
+                            move_call_descriptors: self.move_call_descriptors.clone(),
+                        })
+                        .parse(input)
+                        .await
+                    };
 
-                    <DefaultInterp as AsyncParser<SuiAddress, BS>>::parse(&DefaultInterp, input)
-                        .await;
+                    let sender = <DefaultInterp as AsyncParser<SuiAddress, BS>>::parse(
+                        &DefaultInterp,
+                        input,
+                    )
+                    .await;
 
-                    let (gas_coins, gas_budget) = gas_data_parser().parse(input).await;
+                    let (gas_coins, gas_owner, gas_price, gas_budget) =
+                        Breadcrumb("GasData", gas_data_parser()).parse(input).await;
+                    let gas_coin_count = gas_coins.len();
 
                     // Try to find the total amount of all gas payment objects
                     // This value may be necessary if the transaction contains transfer of entire GasCoin
@@ -1914,19 +2628,28 @@ impl<BS: Clone + Readable, OD: Clone + HasObjectData> AsyncParser<TransactionDat
                         if let Some(amt0) = total_gas_amount {
                             let coin_data = self.object_data_source.get_object_data(&digest).await;
                             match coin_data {
-                                Some((_, amt)) => total_gas_amount = Some(amt0 + amt),
+                                Some((_, amt)) => total_gas_amount = amt0.checked_add(amt),
                                 _ => total_gas_amount = None,
                             }
                         }
                     }
 
-                    <DefaultInterp as AsyncParser<TransactionExpiration, BS>>::parse(
-                        &DefaultInterp,
-                        input,
-                    )
-                    .await;
+                    let expiration =
+                        <DefaultInterp as AsyncParser<TransactionExpiration, BS>>::parse(
+                            &DefaultInterp,
+                            input,
+                        )
+                        .await;
 
-                    (v, (gas_budget, total_gas_amount))
+                    let details = TxDetails {
+                        gas_price,
+                        gas_owner,
+                        expiration,
+                        gas_coin_count,
+                        input_objects,
+                    };
+
+                    (v, sender, (gas_budget, total_gas_amount), details)
                 }
                 _ => {
                     reject_on(
@@ -1941,6 +2664,7 @@ impl<BS: Clone + Readable, OD: Clone + HasObjectData> AsyncParser<TransactionDat
     }
 }
 
+#[derive(Debug, PartialEq)]
 pub enum KnownTx {
     TransferTx {
         recipient: SuiAddressRaw,
@@ -1949,7 +2673,8 @@ pub enum KnownTx {
         gas_budget: u64,
     },
     StakeTx {
-        recipient: SuiAddressRaw,
+        // Validator address and the amount staked with it
+        stakes: ArrayVec<(SuiAddressRaw, u64), MAX_STAKE_COUNT>,
         total_amount: u64,
         gas_budget: u64,
     },
@@ -1957,86 +2682,729 @@ pub enum KnownTx {
         total_amount: u64,
         gas_budget: u64,
     },
+    RestakeTx {
+        source_stake: SuiAddressRaw,
+        recipient: SuiAddressRaw,
+        total_amount: u64,
+        gas_budget: u64,
+    },
+    // Merge and/or split of coins, where all resulting coins stay with the sender
+    CoinManagementTx {
+        coin_type: CoinType,
+        input_coin_count: usize,
+        output_amounts: ArrayVec<u64, MAX_COIN_OUTPUT_COUNT>,
+        gas_budget: u64,
+    },
+    // A call shown with its descriptor
+    MoveCallTx {
+        call: Box<DescribedMoveCall>,
+        gas_budget: u64,
+    },
+}
+
+fn coin_management_tx(
+    coin_type: CoinType,
+    outputs: CoinOutputs,
+    maybe_gas_coin_amount: Option<u64>,
+    gas_budget: u64,
+) -> Option<KnownTx> {
+    let mut output_amounts = ArrayVec::new();
+    for output in outputs.amounts? {
+        let amount = if output.includes_gas_coin {
+            output.amount.checked_add(maybe_gas_coin_amount?)?
+        } else {
+            output.amount
+        };
+        output_amounts.push(amount);
+    }
+    Some(KnownTx::CoinManagementTx {
+        coin_type,
+        input_coin_count: outputs.input_coin_count,
+        output_amounts,
+        gas_budget,
+    })
 }
 
 #[cfg(feature = "speculos")]
 use crate::crypto_helpers::common::HexSlice;
 
-pub const fn tx_parser<BS: Clone + Readable, OD: Clone + HasObjectData>(
+pub const fn tx_parser<
+    BS: Clone + Readable,
+    OD: Clone + HasObjectData,
+    MD: Clone + MoveCallDescriptors,
+>(
     object_data_source: OD,
-) -> impl AsyncParser<IntentMessage, BS, Output = KnownTx> {
+    move_call_descriptors: MD,
+) -> impl AsyncParser<IntentMessage, BS, Output = (KnownTx, TxDetails)> {
     Action(
         (
-            intent_parser(),
-            TransactionDataParser { object_data_source },
+            Breadcrumb("Intent", intent_parser()),
+            Breadcrumb(
+                "TransactionData",
+                TransactionDataParser {
+                    object_data_source,
+                    move_call_descriptors,
+                },
+            ),
         ),
-        |(_, d): (
+        |(_, (tx, sender, gas_data, details)): (
             _,
-            <TransactionDataParser<OD> as HasOutput<TransactionDataSchema>>::Output,
-        )| {
-            match d.0 {
-                ProgrammableTransaction::TransferSuiTx {
-                    recipient,
-                    amount,
-                    includes_gas_coin,
-                } => {
-                    let (gas_budget, maybe_gas_coin_amount) = d.1;
-                    let maybe_total_amount = if includes_gas_coin {
-                        // We will treat this as an unknown tx if we don't know the
-                        // total value of all gas payment objects
-                        maybe_gas_coin_amount.map(|amt| amount + amt)
-                    } else {
-                        Some(amount)
-                    };
+            <TransactionDataParser<OD, MD> as HasOutput<TransactionDataSchema>>::Output,
+        )| to_known_tx(tx, sender, gas_data).map(|known_tx| (known_tx, details)),
+    )
+}
 
-                    maybe_total_amount.map(|total_amount| KnownTx::TransferTx {
-                        recipient,
-                        coin_type: SUI_COIN_TYPE,
-                        total_amount,
-                        gas_budget,
-                    })
-                }
-                ProgrammableTransaction::TransferTokenTx {
-                    recipient,
-                    amount,
-                    coin_type,
-                } => {
-                    let (gas_budget, _) = d.1;
-                    Some(KnownTx::TransferTx {
-                        recipient,
-                        coin_type,
-                        total_amount: amount,
-                        gas_budget,
-                    })
+fn to_known_tx(
+    tx: ProgrammableTransaction,
+    sender: SuiAddressRaw,
+    gas_data: GasData,
+) -> Option<KnownTx> {
+    match tx {
+        ProgrammableTransaction::TransferSuiTx {
+            recipient,
+            amount,
+            includes_gas_coin,
+            outputs,
+        } => {
+            let (gas_budget, maybe_gas_coin_amount) = gas_data;
+            if recipient == sender {
+                // The coin kept by the sender could not be shown with the outputs
+                if outputs.kept_merge_destination {
+                    return None;
+                }
+                if let Some(tx) =
+                    coin_management_tx(SUI_COIN_TYPE, outputs, maybe_gas_coin_amount, gas_budget)
+                {
+                    return Some(tx);
                 }
-                ProgrammableTransaction::StakeTx {
-                    recipient,
-                    amount,
-                    includes_gas_coin,
-                } => {
-                    let (gas_budget, maybe_gas_coin_amount) = d.1;
-                    let maybe_total_amount = if includes_gas_coin {
-                        // We will treat this as an unknown tx if we don't know the
-                        // total value of all gas payment objects
-                        maybe_gas_coin_amount.map(|amt| amount + amt)
-                    } else {
-                        Some(amount)
-                    };
+            }
+            let maybe_total_amount = if includes_gas_coin {
+                // We will treat this as an unknown tx if we don't know the
+                // total value of all gas payment objects
+                maybe_gas_coin_amount.and_then(|amt| amount.checked_add(amt))
+            } else {
+                Some(amount)
+            };
 
-                    maybe_total_amount.map(|total_amount| KnownTx::StakeTx {
-                        recipient,
-                        total_amount,
-                        gas_budget,
-                    })
+            maybe_total_amount.map(|total_amount| KnownTx::TransferTx {
+                recipient,
+                coin_type: SUI_COIN_TYPE,
+                total_amount,
+                gas_budget,
+            })
+        }
+        ProgrammableTransaction::TransferTokenTx {
+            recipient,
+            amount,
+            coin_type,
+            outputs,
+        } => {
+            let (gas_budget, _) = gas_data;
+            if recipient == sender {
+                if outputs.kept_merge_destination {
+                    return None;
                 }
-                ProgrammableTransaction::UnstakeTx { total_amount } => {
-                    let (gas_budget, _) = d.1;
-                    Some(KnownTx::UnstakeTx {
-                        total_amount,
-                        gas_budget,
-                    })
+                if let Some(tx) = coin_management_tx(coin_type.clone(), outputs, None, gas_budget) {
+                    return Some(tx);
                 }
             }
-        },
-    )
+            Some(KnownTx::TransferTx {
+                recipient,
+                coin_type,
+                total_amount: amount,
+                gas_budget,
+            })
+        }
+        ProgrammableTransaction::MergeCoinsTx { coin_type, outputs } => {
+            let (gas_budget, maybe_gas_coin_amount) = gas_data;
+            coin_management_tx(coin_type, outputs, maybe_gas_coin_amount, gas_budget)
+        }
+        ProgrammableTransaction::StakeTx { stakes } => {
+            let (gas_budget, maybe_gas_coin_amount) = gas_data;
+            let mut known_stakes = ArrayVec::new();
+            let mut total_amount: u64 = 0;
+            for stake in stakes {
+                let amount = if stake.includes_gas_coin {
+                    // We will treat this as an unknown tx if we don't know the
+                    // total value of all gas payment objects
+                    stake.amount.checked_add(maybe_gas_coin_amount?)?
+                } else {
+                    stake.amount
+                };
+                total_amount = total_amount.checked_add(amount)?;
+                known_stakes.push((stake.validator, amount));
+            }
+
+            Some(KnownTx::StakeTx {
+                stakes: known_stakes,
+                total_amount,
+                gas_budget,
+            })
+        }
+        ProgrammableTransaction::UnstakeTx { total_amount } => {
+            let (gas_budget, _) = gas_data;
+            Some(KnownTx::UnstakeTx {
+                total_amount,
+                gas_budget,
+            })
+        }
+        ProgrammableTransaction::RestakeTx {
+            source_stake,
+            recipient,
+            amount,
+        } => {
+            let (gas_budget, _) = gas_data;
+            Some(KnownTx::RestakeTx {
+                source_stake,
+                recipient,
+                total_amount: amount,
+                gas_budget,
+            })
+        }
+        ProgrammableTransaction::DescribedMoveCallTx {
+            call,
+            outputs_recipient,
+        } => {
+            let (gas_budget, _) = gas_data;
+            // Results sent to another address are not shown, so such a transaction is unknown
+            if outputs_recipient.is_some_and(|r| r != sender) {
+                return None;
+            }
+            Some(KnownTx::MoveCallTx { call, gas_budget })
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parser::testing::*;
+    use arrayvec::ArrayString;
+    use hex_literal::hex;
+
+    #[cfg(target_family = "bolos")]
+    #[allow(unused_imports)]
+    use ledger_device_sdk::testing::TestType;
+    #[cfg(target_family = "bolos")]
+    use testmacro::test_item as test;
+
+    // Transactions of the speculos tests, with the intent
+    const SUI_WHOLE_GAS_COIN: [u8; 202] = hex!("00000000000100201d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be488010101000100006fb21feead027da4873295affd6c4f3618fe176fa2fbf3e7b5ef1d9463b31e2101400dbdcfeda8e1e64ebbf5710ccf5a67bfa6e7f945c92bc8611e0cb44b7219ded3764211000000002066c5ab65498a9d3716001a034815ff4abca765f7f6f2375cc91d2addb79c208a6fb21feead027da4873295affd6c4f3618fe176fa2fbf3e7b5ef1d9463b31e21e803000000000000e0972d000000000000");
+    const SUI_WHOLE_GAS_PLUS_INPUT_COIN: [u8; 280] = hex!("00000000000201001c12be5429384d00eeef61242f3aebabeac3012549dd6f888dc1087c4d00da80d27642110000000020e10131885e3b30e7cb4561d53970d55cbf694c099ec6d2c789b2f2dc92ca7ff300206fb21feead027da4873295affd6c4f3618fe176fa2fbf3e7b5ef1d9463b31e21010102000100000101001d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be488011f876ff0144386dcf4e886c5de53b326c718cc1221e1ccea71ef8aa6231a40ead3764211000000002021c01bdc29b088d3b0151eab0176ff9501e04ab49e00124a5b6fae1a05f351ba1d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be488e80300000000000078be2d000000000000");
+    const SUI_SPLIT_GAS_PLUS_INPUT_COIN: [u8; 300] = hex!("0000000000030008640000000000000001001c12be5429384d00eeef61242f3aebabeac3012549dd6f888dc1087c4d00da80d27642110000000020e10131885e3b30e7cb4561d53970d55cbf694c099ec6d2c789b2f2dc92ca7ff300206fb21feead027da4873295affd6c4f3618fe176fa2fbf3e7b5ef1d9463b31e2102020001010000010203000000000101000102001d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be488011f876ff0144386dcf4e886c5de53b326c718cc1221e1ccea71ef8aa6231a40ead3764211000000002021c01bdc29b088d3b0151eab0176ff9501e04ab49e00124a5b6fae1a05f351ba1d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be488e803000000000000d8d13c000000000000");
+    const SUI_MERGE_TWO_INPUT_COIN: [u8; 362] = hex!("0000000000030100a93f6c464f8fb8b98fb3d02112902060c8f85ea4d71cfc7777dfdbd75e68ab6dd47642110000000020ac558a57e3fe380a9d8159ef1a564002516e6bc6db04ae350f27a9ccc5a0afbd01001c12be5429384d00eeef61242f3aebabeac3012549dd6f888dc1087c4d00da80d27642110000000020e10131885e3b30e7cb4561d53970d55cbf694c099ec6d2c789b2f2dc92ca7ff300206fb21feead027da4873295affd6c4f3618fe176fa2fbf3e7b5ef1d9463b31e2102030100000101010001010100000102001d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be488011f876ff0144386dcf4e886c5de53b326c718cc1221e1ccea71ef8aa6231a40ead3764211000000002021c01bdc29b088d3b0151eab0176ff9501e04ab49e00124a5b6fae1a05f351ba1d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be488e803000000000000b0d11e000000000000");
+    // Same as SUI_MERGE_TWO_INPUT_COIN, with the sender as recipient
+    const SUI_MERGE_TWO_INPUT_COIN_TO_SENDER: [u8; 362] = hex!("0000000000030100a93f6c464f8fb8b98fb3d02112902060c8f85ea4d71cfc7777dfdbd75e68ab6dd47642110000000020ac558a57e3fe380a9d8159ef1a564002516e6bc6db04ae350f27a9ccc5a0afbd01001c12be5429384d00eeef61242f3aebabeac3012549dd6f888dc1087c4d00da80d27642110000000020e10131885e3b30e7cb4561d53970d55cbf694c099ec6d2c789b2f2dc92ca7ff300201d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be48802030100000101010001010100000102001d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be488011f876ff0144386dcf4e886c5de53b326c718cc1221e1ccea71ef8aa6231a40ead3764211000000002021c01bdc29b088d3b0151eab0176ff9501e04ab49e00124a5b6fae1a05f351ba1d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be488e803000000000000b0d11e000000000000");
+    // Merges 0x1c12 into 0xa93f, then splits 0.001 SUI from 0xa93f and sends it to the sender,
+    // who also keeps 0xa93f
+    const SUI_MERGE_SPLIT_KEEP_DESTINATION: [u8; 382] = hex!("0000000000040100a93f6c464f8fb8b98fb3d02112902060c8f85ea4d71cfc7777dfdbd75e68ab6dd47642110000000020ac558a57e3fe380a9d8159ef1a564002516e6bc6db04ae350f27a9ccc5a0afbd01001c12be5429384d00eeef61242f3aebabeac3012549dd6f888dc1087c4d00da80d27642110000000020e10131885e3b30e7cb4561d53970d55cbf694c099ec6d2c789b2f2dc92ca7ff3000840420f000000000000201d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be4880303010000010101000201000001010200010103010000000103001d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be488011f876ff0144386dcf4e886c5de53b326c718cc1221e1ccea71ef8aa6231a40ead3764211000000002021c01bdc29b088d3b0151eab0176ff9501e04ab49e00124a5b6fae1a05f351ba1d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be488e803000000000000b0d11e000000000000");
+    const USDC_WHOLE_COIN: [u8; 279] = hex!("0000000000020100d3baa97a46a20d65f1be60cbaa160856e6aae5078e16545071e2fd3e314105b9762fa41d00000000209c428fe35ca9b1917e8d0eedbd359d11c218abe23338f6e0438b89613e948a4300206fb21feead027da4873295affd6c4f3618fe176fa2fbf3e7b5ef1d9463b31e210101010100000101000f2f8dd49e269da066f37624d33a8b4ed1b262d6a5fdf2b735be39f8d7c5acf501efb63e8bb822099f7d878b7ac5154569eece8766446ddd5afb85660bafa011878ed1a91d000000002057702e5556fc3249af2f565c1c6f2b9898955a074848b13be93b360736a6c79e0f2f8dd49e269da066f37624d33a8b4ed1b262d6a5fdf2b735be39f8d7c5acf5ee02000000000000682a26000000000000");
+    const THREE_USDC_WHOLE_COIN: [u8; 435] = hex!("0000000000040100d3baa97a46a20d65f1be60cbaa160856e6aae5078e16545071e2fd3e314105b9762fa41d00000000209c428fe35ca9b1917e8d0eedbd359d11c218abe23338f6e0438b89613e948a4301008ba6495be346c1be89e5c35cdabab145ea18990f48f2b0629ff016024c9ded453532a61d00000000203e91489053c2e2c2c851e8f16afadb8213fd02cea1f3a024c603986c4c14963701008ebf87449ed242216a3c94de4935c21792618850fc781222e866dc60bb1bc8d2e679a41d0000000020e43f0996520f3d5b2453fbca700a90caa6edbae90d701d1c9b0ee6a1405d6e9c00206fb21feead027da4873295affd6c4f3618fe176fa2fbf3e7b5ef1d9463b31e210101030100000101000102000103000f2f8dd49e269da066f37624d33a8b4ed1b262d6a5fdf2b735be39f8d7c5acf501efb63e8bb822099f7d878b7ac5154569eece8766446ddd5afb85660bafa011878ed1a91d000000002057702e5556fc3249af2f565c1c6f2b9898955a074848b13be93b360736a6c79e0f2f8dd49e269da066f37624d33a8b4ed1b262d6a5fdf2b735be39f8d7c5acf5ee02000000000000b89126000000000000");
+    // Transfers the coins 0x5555 and 0x6666, of 2^63 USDC each
+    const HUGE_USDC_TWO_COINS: [u8; 357] = hex!("00000000000301005555555555555555555555555555555555555555555555555555555555555555762fa41d0000000020a07ad8b98c9a6850c9a3b7b9f24ba1b10770d0d2a915a3fc2dcfd2b6b398745301006666666666666666666666666666666666666666666666666666666666666666762fa41d00000000207fcf1eec3ad4b2e1d104243a408c82c9d0d38161a5adc873ac99dab09ec3151300206fb21feead027da4873295affd6c4f3618fe176fa2fbf3e7b5ef1d9463b31e210101020100000101000102000f2f8dd49e269da066f37624d33a8b4ed1b262d6a5fdf2b735be39f8d7c5acf501efb63e8bb822099f7d878b7ac5154569eece8766446ddd5afb85660bafa011878ed1a91d000000002057702e5556fc3249af2f565c1c6f2b9898955a074848b13be93b360736a6c79e0f2f8dd49e269da066f37624d33a8b4ed1b262d6a5fdf2b735be39f8d7c5acf5ee02000000000000682a26000000000000");
+    const USDC_SPLIT_COIN: [u8; 299] = hex!("0000000000030100d3baa97a46a20d65f1be60cbaa160856e6aae5078e16545071e2fd3e314105b9762fa41d00000000209c428fe35ca9b1917e8d0eedbd359d11c218abe23338f6e0438b89613e948a430008803801000000000000206fb21feead027da4873295affd6c4f3618fe176fa2fbf3e7b5ef1d9463b31e21020201000001010100010103000000000102000f2f8dd49e269da066f37624d33a8b4ed1b262d6a5fdf2b735be39f8d7c5acf501efb63e8bb822099f7d878b7ac5154569eece8766446ddd5afb85660bafa011878ed1a91d000000002057702e5556fc3249af2f565c1c6f2b9898955a074848b13be93b360736a6c79e0f2f8dd49e269da066f37624d33a8b4ed1b262d6a5fdf2b735be39f8d7c5acf5ee0200000000000008583a000000000000");
+    const STAKE_GAS_COIN: [u8; 529] = hex!("00000000000201010000000000000000000000000000000000000000000000000000000000000005010000000000000001002035f5f154f0117464e3379c45c7f3cb6b2d4efedf300acadff8a7e8e9e3a15109010000000000000000000000000000000000000000000000000000000000000000030a7375695f73797374656d11726571756573745f6164645f7374616b650003010000000101001d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be48804ebff16b4d2081ab06d1d5251c988208641e5c501c7fa8bdce9c8b7b0908ba76bd576421100000000203bff047cca0dba126b90f5e7f0f726abbda38756521bdc13a6f29bf9278db0e4a93f6c464f8fb8b98fb3d02112902060c8f85ea4d71cfc7777dfdbd75e68ab6dd47642110000000020ac558a57e3fe380a9d8159ef1a564002516e6bc6db04ae350f27a9ccc5a0afbd1f876ff0144386dcf4e886c5de53b326c718cc1221e1ccea71ef8aa6231a40ead3764211000000002021c01bdc29b088d3b0151eab0176ff9501e04ab49e00124a5b6fae1a05f351ba1c12be5429384d00eeef61242f3aebabeac3012549dd6f888dc1087c4d00da80d27642110000000020e10131885e3b30e7cb4561d53970d55cbf694c099ec6d2c789b2f2dc92ca7ff31d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be488e80300000000000078e001000000000000");
+    const STAKE_SPLIT_COIN: [u8; 553] = hex!("0000000000040100a93f6c464f8fb8b98fb3d02112902060c8f85ea4d71cfc7777dfdbd75e68ab6dd47642110000000020ac558a57e3fe380a9d8159ef1a564002516e6bc6db04ae350f27a9ccc5a0afbd0008808d5b000000000001010000000000000000000000000000000000000000000000000000000000000005010000000000000001002035f5f154f0117464e3379c45c7f3cb6b2d4efedf300acadff8a7e8e9e3a151090202010000010101000000000000000000000000000000000000000000000000000000000000000000030a7375695f73797374656d11726571756573745f6164645f7374616b65000301020003000000000103001d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be48803ebff16b4d2081ab06d1d5251c988208641e5c501c7fa8bdce9c8b7b0908ba76bd576421100000000203bff047cca0dba126b90f5e7f0f726abbda38756521bdc13a6f29bf9278db0e41f876ff0144386dcf4e886c5de53b326c718cc1221e1ccea71ef8aa6231a40ead3764211000000002021c01bdc29b088d3b0151eab0176ff9501e04ab49e00124a5b6fae1a05f351ba1c12be5429384d00eeef61242f3aebabeac3012549dd6f888dc1087c4d00da80d27642110000000020e10131885e3b30e7cb4561d53970d55cbf694c099ec6d2c789b2f2dc92ca7ff31d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be488e80300000000000078e001000000000000");
+    const MULTIPLE_MOVE_CALL: [u8; 642] = hex!("000000000005010100000000000000000000000000000000000000000000000000000000000000050100000000000000010100a93f6c464f8fb8b98fb3d02112902060c8f85ea4d71cfc7777dfdbd75e68ab6dd47642110000000020ac558a57e3fe380a9d8159ef1a564002516e6bc6db04ae350f27a9ccc5a0afbd002035f5f154f0117464e3379c45c7f3cb6b2d4efedf300acadff8a7e8e9e3a1510901001c12be5429384d00eeef61242f3aebabeac3012549dd6f888dc1087c4d00da80d27642110000000020e10131885e3b30e7cb4561d53970d55cbf694c099ec6d2c789b2f2dc92ca7ff3002035f5f154f0117464e3379c45c7f3cb6b2d4efedf300acadff8a7e8e9e3a15109020000000000000000000000000000000000000000000000000000000000000000030a7375695f73797374656d11726571756573745f6164645f7374616b6500030100000101000102000000000000000000000000000000000000000000000000000000000000000000030a7375695f73797374656d11726571756573745f6164645f7374616b6500030100000103000104001d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be48802ebff16b4d2081ab06d1d5251c988208641e5c501c7fa8bdce9c8b7b0908ba76bd576421100000000203bff047cca0dba126b90f5e7f0f726abbda38756521bdc13a6f29bf9278db0e41f876ff0144386dcf4e886c5de53b326c718cc1221e1ccea71ef8aa6231a40ead3764211000000002021c01bdc29b088d3b0151eab0176ff9501e04ab49e00124a5b6fae1a05f351ba1d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be488e80300000000000078e001000000000000");
+    // MULTIPLE_MOVE_CALL, with the second stake going to OTHER_VALIDATOR
+    const MULTIPLE_MOVE_CALL_TWO_VALIDATORS: [u8; 642] = hex!("000000000005010100000000000000000000000000000000000000000000000000000000000000050100000000000000010100a93f6c464f8fb8b98fb3d02112902060c8f85ea4d71cfc7777dfdbd75e68ab6dd47642110000000020ac558a57e3fe380a9d8159ef1a564002516e6bc6db04ae350f27a9ccc5a0afbd002035f5f154f0117464e3379c45c7f3cb6b2d4efedf300acadff8a7e8e9e3a1510901001c12be5429384d00eeef61242f3aebabeac3012549dd6f888dc1087c4d00da80d27642110000000020e10131885e3b30e7cb4561d53970d55cbf694c099ec6d2c789b2f2dc92ca7ff300204444444444444444444444444444444444444444444444444444444444444444020000000000000000000000000000000000000000000000000000000000000000030a7375695f73797374656d11726571756573745f6164645f7374616b6500030100000101000102000000000000000000000000000000000000000000000000000000000000000000030a7375695f73797374656d11726571756573745f6164645f7374616b6500030100000103000104001d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be48802ebff16b4d2081ab06d1d5251c988208641e5c501c7fa8bdce9c8b7b0908ba76bd576421100000000203bff047cca0dba126b90f5e7f0f726abbda38756521bdc13a6f29bf9278db0e41f876ff0144386dcf4e886c5de53b326c718cc1221e1ccea71ef8aa6231a40ead3764211000000002021c01bdc29b088d3b0151eab0176ff9501e04ab49e00124a5b6fae1a05f351ba1d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be488e80300000000000078e001000000000000");
+    // Splits the GasCoin into 0.001, 0.002, 0.003 and 0.004 SUI, and stakes each of them with
+    // VALIDATOR
+    const STAKE_MAX_COUNT: [u8; 667] = hex!("00000000000601010000000000000000000000000000000000000000000000000000000000000005010000000000000001002035f5f154f0117464e3379c45c7f3cb6b2d4efedf300acadff8a7e8e9e3a15109000840420f0000000000000880841e00000000000008c0c62d0000000000000800093d0000000000050200040102000103000104000105000000000000000000000000000000000000000000000000000000000000000000030a7375695f73797374656d11726571756573745f6164645f7374616b65000301000003000000000101000000000000000000000000000000000000000000000000000000000000000000030a7375695f73797374656d11726571756573745f6164645f7374616b65000301000003000001000101000000000000000000000000000000000000000000000000000000000000000000030a7375695f73797374656d11726571756573745f6164645f7374616b65000301000003000002000101000000000000000000000000000000000000000000000000000000000000000000030a7375695f73797374656d11726571756573745f6164645f7374616b65000301000003000003000101001d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be48802ebff16b4d2081ab06d1d5251c988208641e5c501c7fa8bdce9c8b7b0908ba76bd576421100000000203bff047cca0dba126b90f5e7f0f726abbda38756521bdc13a6f29bf9278db0e41f876ff0144386dcf4e886c5de53b326c718cc1221e1ccea71ef8aa6231a40ead3764211000000002021c01bdc29b088d3b0151eab0176ff9501e04ab49e00124a5b6fae1a05f351ba1d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be488e80300000000000078e001000000000000");
+    // The same with a fifth split and stake of 0.005 SUI
+    const STAKE_OVER_MAX_COUNT: [u8; 755] = hex!("00000000000701010000000000000000000000000000000000000000000000000000000000000005010000000000000001002035f5f154f0117464e3379c45c7f3cb6b2d4efedf300acadff8a7e8e9e3a15109000840420f0000000000000880841e00000000000008c0c62d0000000000000800093d00000000000008404b4c0000000000060200050102000103000104000105000106000000000000000000000000000000000000000000000000000000000000000000030a7375695f73797374656d11726571756573745f6164645f7374616b65000301000003000000000101000000000000000000000000000000000000000000000000000000000000000000030a7375695f73797374656d11726571756573745f6164645f7374616b65000301000003000001000101000000000000000000000000000000000000000000000000000000000000000000030a7375695f73797374656d11726571756573745f6164645f7374616b65000301000003000002000101000000000000000000000000000000000000000000000000000000000000000000030a7375695f73797374656d11726571756573745f6164645f7374616b65000301000003000003000101000000000000000000000000000000000000000000000000000000000000000000030a7375695f73797374656d11726571756573745f6164645f7374616b65000301000003000004000101001d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be48802ebff16b4d2081ab06d1d5251c988208641e5c501c7fa8bdce9c8b7b0908ba76bd576421100000000203bff047cca0dba126b90f5e7f0f726abbda38756521bdc13a6f29bf9278db0e41f876ff0144386dcf4e886c5de53b326c718cc1221e1ccea71ef8aa6231a40ead3764211000000002021c01bdc29b088d3b0151eab0176ff9501e04ab49e00124a5b6fae1a05f351ba1d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be488e80300000000000078e001000000000000");
+    const UNSTAKE_STAKED_COIN: [u8; 355] = hex!("000000000002010100000000000000000000000000000000000000000000000000000000000000050100000000000000010100d263dbcc9f13e9302b8b629518d2a12c5be755aba0c978606cc291367059015df71b0e000000000020c17ecaa3599cd8af7affcb659e4ae4128b7160a023d0813ea6c711d23bc910e5010000000000000000000000000000000000000000000000000000000000000000030a7375695f73797374656d16726571756573745f77697468647261775f7374616b6500020100000101001255d09304c5cc824b9ef4fd9196f5b8e96167b4fb0266f70dd36829ad519d5d01493954c643a2aa96aa3d01cf12821e6d3612de6051a07b871683e82a4008d3acc4eb3900000000002089e01e1fbfa66b4714d09dd401f84e3c06912f42f84c8a1c8e31aa4cbedcf2d21255d09304c5cc824b9ef4fd9196f5b8e96167b4fb0266f70dd36829ad519d5de8030000000000004858a6000000000000");
+    // Withdraws the StakedSui d263 and stakes the withdrawn coin with VALIDATOR
+    const RESTAKE_STAKED_COIN: [u8; 570] = hex!("000000000003010100000000000000000000000000000000000000000000000000000000000000050100000000000000010100d263dbcc9f13e9302b8b629518d2a12c5be755aba0c978606cc291367059015df71b0e000000000020c17ecaa3599cd8af7affcb659e4ae4128b7160a023d0813ea6c711d23bc910e5002035f5f154f0117464e3379c45c7f3cb6b2d4efedf300acadff8a7e8e9e3a15109030000000000000000000000000000000000000000000000000000000000000000030a7375695f73797374656d20726571756573745f77697468647261775f7374616b655f6e6f6e5f656e747279000201000001010000000000000000000000000000000000000000000000000000000000000000000204636f696e0c66726f6d5f62616c616e636501070000000000000000000000000000000000000000000000000000000000000002037375690353554900010200000000000000000000000000000000000000000000000000000000000000000000030a7375695f73797374656d11726571756573745f6164645f7374616b6500030100000201000102001255d09304c5cc824b9ef4fd9196f5b8e96167b4fb0266f70dd36829ad519d5d01493954c643a2aa96aa3d01cf12821e6d3612de6051a07b871683e82a4008d3acc4eb3900000000002089e01e1fbfa66b4714d09dd401f84e3c06912f42f84c8a1c8e31aa4cbedcf2d21255d09304c5cc824b9ef4fd9196f5b8e96167b4fb0266f70dd36829ad519d5de8030000000000004858a6000000000000");
+
+    // A call to 0xdee9::pool::swap<USDC, SUI>(pool, 2000000, 50), whose two results are
+    // transferred to the sender
+    const DEX_SWAP: [u8; 414] = hex!("00000000000401017f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7fe80300000000000001000880841e00000000000008320000000000000000201d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be4880200dee900000000000000000000000000000000000000000000000000000000000004706f6f6c04737761700207dba34672e30cb065b1f93e3ab55318768fd6fef66c15942c9f7cb846e2f900e70475736463045553444300070000000000000000000000000000000000000000000000000000000000000002037375690353554900030100000101000102000102030000000003000001000103001d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be488011f876ff0144386dcf4e886c5de53b326c718cc1221e1ccea71ef8aa6231a40ead3764211000000002021c01bdc29b088d3b0151eab0176ff9501e04ab49e00124a5b6fae1a05f351ba1d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be488e80300000000000080841e000000000000");
+    // The same call, with the coin 1c12 as a fourth argument
+    const DEX_SWAP_WITH_COIN: [u8; 492] = hex!("00000000000501017f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7fe80300000000000001000880841e00000000000008320000000000000000201d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be48801001c12be5429384d00eeef61242f3aebabeac3012549dd6f888dc1087c4d00da80d27642110000000020e10131885e3b30e7cb4561d53970d55cbf694c099ec6d2c789b2f2dc92ca7ff30200dee900000000000000000000000000000000000000000000000000000000000004706f6f6c04737761700207dba34672e30cb065b1f93e3ab55318768fd6fef66c15942c9f7cb846e2f900e70475736463045553444300070000000000000000000000000000000000000000000000000000000000000002037375690353554900040100000101000102000104000102030000000003000001000103001d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be488011f876ff0144386dcf4e886c5de53b326c718cc1221e1ccea71ef8aa6231a40ead3764211000000002021c01bdc29b088d3b0151eab0176ff9501e04ab49e00124a5b6fae1a05f351ba1d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be488e80300000000000080841e000000000000");
+
+    const VALIDATOR: SuiAddressRaw =
+        hex!("35f5f154f0117464e3379c45c7f3cb6b2d4efedf300acadff8a7e8e9e3a15109");
+    const OTHER_VALIDATOR: SuiAddressRaw = [0x44; 32];
+
+    // Coins whose total amount does not fit in a u64
+    const USDC_COIN_5555: (ObjectDigest, &[u8]) = (
+        hex!("20a07ad8b98c9a6850c9a3b7b9f24ba1b10770d0d2a915a3fc2dcfd2b6b3987453"),
+        &hex!("000307dba34672e30cb065b1f93e3ab55318768fd6fef66c15942c9f7cb846e2f900e7047573646304555344430001762fa41d000000002855555555555555555555555555555555555555555555555555555555555555550000000000000080000f2f8dd49e269da066f37624d33a8b4ed1b262d6a5fdf2b735be39f8d7c5acf520257da22cf6f10376f95160b375557286f7e73a1a66041fe567007bd81674dc96a02d140000000000"),
+    );
+    const USDC_COIN_6666: (ObjectDigest, &[u8]) = (
+        hex!("207fcf1eec3ad4b2e1d104243a408c82c9d0d38161a5adc873ac99dab09ec31513"),
+        &hex!("000307dba34672e30cb065b1f93e3ab55318768fd6fef66c15942c9f7cb846e2f900e7047573646304555344430001762fa41d000000002866666666666666666666666666666666666666666666666666666666666666660000000000000080000f2f8dd49e269da066f37624d33a8b4ed1b262d6a5fdf2b735be39f8d7c5acf520257da22cf6f10376f95160b375557286f7e73a1a66041fe567007bd81674dc96a02d140000000000"),
+    );
+
+    fn parse_tx<T: AsRef<[u8]> + Clone>(
+        tx: T,
+        objects: &'static [(ObjectDigest, &'static [u8])],
+    ) -> Option<KnownTx> {
+        parse_described_tx(tx, objects, None)
+    }
+
+    fn parse_described_tx<T: AsRef<[u8]> + Clone>(
+        tx: T,
+        objects: &'static [(ObjectDigest, &'static [u8])],
+        descriptor: Option<&MoveCallDescriptor>,
+    ) -> Option<KnownTx> {
+        parse_all::<IntentMessage, _, _>(&tx_parser(MockObjectData(objects), descriptor), tx)
+            .map(|(known_tx, _)| known_tx)
+    }
+
+    fn usdc_coin_type() -> CoinType {
+        (
+            hex!("dba34672e30cb065b1f93e3ab55318768fd6fef66c15942c9f7cb846e2f900e7"),
+            ArrayVec::try_from(&b"usdc"[..]).unwrap(),
+            ArrayVec::try_from(&b"USDC"[..]).unwrap(),
+        )
+    }
+
+    #[test]
+    fn sui_whole_gas_coin() {
+        assert_eq!(
+            parse_tx(SUI_WHOLE_GAS_COIN, &[SUI_COIN_400D]),
+            Some(KnownTx::TransferTx {
+                recipient: hex!("1d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be488"),
+                coin_type: SUI_COIN_TYPE,
+                total_amount: 966004240,
+                gas_budget: 2988000,
+            })
+        );
+    }
+
+    #[test]
+    fn sui_whole_gas_coin_missing_object() {
+        // The amount of the GasCoin is unknown
+        assert_eq!(parse_tx(SUI_WHOLE_GAS_COIN, &[]), None);
+    }
+
+    #[test]
+    fn sui_whole_gas_plus_input_coin() {
+        assert_eq!(
+            parse_tx(
+                SUI_WHOLE_GAS_PLUS_INPUT_COIN,
+                &[SUI_COIN_1C12, SUI_COIN_1F87]
+            ),
+            Some(KnownTx::TransferTx {
+                recipient: hex!("6fb21feead027da4873295affd6c4f3618fe176fa2fbf3e7b5ef1d9463b31e21"),
+                coin_type: SUI_COIN_TYPE,
+                total_amount: 30000000,
+                gas_budget: 2997880,
+            })
+        );
+    }
+
+    #[test]
+    fn sui_split_gas_plus_input_coin() {
+        assert_eq!(
+            parse_tx(
+                SUI_SPLIT_GAS_PLUS_INPUT_COIN,
+                &[SUI_COIN_1C12, SUI_COIN_1F87]
+            ),
+            Some(KnownTx::TransferTx {
+                recipient: hex!("6fb21feead027da4873295affd6c4f3618fe176fa2fbf3e7b5ef1d9463b31e21"),
+                coin_type: SUI_COIN_TYPE,
+                total_amount: 10000100,
+                gas_budget: 3985880,
+            })
+        );
+    }
+
+    #[test]
+    fn sui_merge_two_input_coin() {
+        assert_eq!(
+            parse_tx(
+                SUI_MERGE_TWO_INPUT_COIN,
+                &[SUI_COIN_1C12, SUI_COIN_1F87, SUI_COIN_A93F]
+            ),
+            Some(KnownTx::TransferTx {
+                recipient: hex!("6fb21feead027da4873295affd6c4f3618fe176fa2fbf3e7b5ef1d9463b31e21"),
+                coin_type: SUI_COIN_TYPE,
+                total_amount: 40000000,
+                gas_budget: 2019760,
+            })
+        );
+    }
+
+    #[test]
+    fn sui_merge_two_input_coin_to_sender() {
+        let mut output_amounts = ArrayVec::new();
+        output_amounts.push(40000000);
+        assert_eq!(
+            parse_tx(
+                SUI_MERGE_TWO_INPUT_COIN_TO_SENDER,
+                &[SUI_COIN_1C12, SUI_COIN_1F87, SUI_COIN_A93F]
+            ),
+            Some(KnownTx::CoinManagementTx {
+                coin_type: SUI_COIN_TYPE,
+                input_coin_count: 2,
+                output_amounts,
+                gas_budget: 2019760,
+            })
+        );
+    }
+
+    #[test]
+    fn sui_merge_split_keep_destination() {
+        // 0xa93f is not among the outputs, the transaction is not recognized
+        assert_eq!(
+            parse_tx(
+                SUI_MERGE_SPLIT_KEEP_DESTINATION,
+                &[SUI_COIN_1C12, SUI_COIN_1F87, SUI_COIN_A93F]
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn usdc_whole_coin() {
+        assert_eq!(
+            parse_tx(USDC_WHOLE_COIN, &[USDC_COIN_D3BA]),
+            Some(KnownTx::TransferTx {
+                recipient: hex!("6fb21feead027da4873295affd6c4f3618fe176fa2fbf3e7b5ef1d9463b31e21"),
+                coin_type: usdc_coin_type(),
+                total_amount: 100023,
+                gas_budget: 2501224,
+            })
+        );
+    }
+
+    #[test]
+    fn usdc_whole_coin_missing_object() {
+        assert_eq!(parse_tx(USDC_WHOLE_COIN, &[]), None);
+    }
+
+    #[test]
+    fn three_usdc_whole_coin() {
+        assert_eq!(
+            parse_tx(
+                THREE_USDC_WHOLE_COIN,
+                &[USDC_COIN_D3BA, USDC_COIN_8BA6, USDC_COIN_8EBF]
+            ),
+            Some(KnownTx::TransferTx {
+                recipient: hex!("6fb21feead027da4873295affd6c4f3618fe176fa2fbf3e7b5ef1d9463b31e21"),
+                coin_type: usdc_coin_type(),
+                total_amount: 345017,
+                gas_budget: 2527672,
+            })
+        );
+    }
+
+    #[test]
+    fn huge_usdc_two_coins() {
+        // The total amount overflows, the transaction is not recognized
+        assert_eq!(
+            parse_tx(HUGE_USDC_TWO_COINS, &[USDC_COIN_5555, USDC_COIN_6666]),
+            None
+        );
+    }
+
+    #[test]
+    fn usdc_split_coin() {
+        assert_eq!(
+            parse_tx(USDC_SPLIT_COIN, &[USDC_COIN_D3BA]),
+            Some(KnownTx::TransferTx {
+                recipient: hex!("6fb21feead027da4873295affd6c4f3618fe176fa2fbf3e7b5ef1d9463b31e21"),
+                coin_type: usdc_coin_type(),
+                total_amount: 80000,
+                gas_budget: 3823624,
+            })
+        );
+    }
+
+    #[test]
+    fn stake_gas_coin() {
+        let mut stakes = ArrayVec::new();
+        stakes.push((VALIDATOR, 101000000));
+        assert_eq!(
+            parse_tx(
+                STAKE_GAS_COIN,
+                &[SUI_COIN_1C12, SUI_COIN_1F87, SUI_COIN_A93F, SUI_COIN_EBFF]
+            ),
+            Some(KnownTx::StakeTx {
+                stakes,
+                total_amount: 101000000,
+                gas_budget: 123000,
+            })
+        );
+    }
+
+    #[test]
+    fn stake_split_coin() {
+        let mut stakes = ArrayVec::new();
+        stakes.push((VALIDATOR, 6000000));
+        assert_eq!(
+            parse_tx(
+                STAKE_SPLIT_COIN,
+                &[SUI_COIN_1C12, SUI_COIN_1F87, SUI_COIN_A93F, SUI_COIN_EBFF]
+            ),
+            Some(KnownTx::StakeTx {
+                stakes,
+                total_amount: 6000000,
+                gas_budget: 123000,
+            })
+        );
+    }
+
+    #[test]
+    fn stake_multiple_move_call() {
+        let mut stakes = ArrayVec::new();
+        stakes.push((VALIDATOR, 30000000));
+        stakes.push((VALIDATOR, 10000000));
+        assert_eq!(
+            parse_tx(
+                MULTIPLE_MOVE_CALL,
+                &[SUI_COIN_1C12, SUI_COIN_1F87, SUI_COIN_A93F, SUI_COIN_EBFF]
+            ),
+            Some(KnownTx::StakeTx {
+                stakes,
+                total_amount: 40000000,
+                gas_budget: 123000,
+            })
+        );
+    }
+
+    #[test]
+    fn stake_two_validators() {
+        let mut stakes = ArrayVec::new();
+        stakes.push((VALIDATOR, 30000000));
+        stakes.push((OTHER_VALIDATOR, 10000000));
+        assert_eq!(
+            parse_tx(
+                MULTIPLE_MOVE_CALL_TWO_VALIDATORS,
+                &[SUI_COIN_1C12, SUI_COIN_1F87, SUI_COIN_A93F, SUI_COIN_EBFF]
+            ),
+            Some(KnownTx::StakeTx {
+                stakes,
+                total_amount: 40000000,
+                gas_budget: 123000,
+            })
+        );
+    }
+
+    #[test]
+    fn stake_over_max_count() {
+        let stakes: ArrayVec<_, MAX_STAKE_COUNT> = (1..=MAX_STAKE_COUNT as u64)
+            .map(|i| (VALIDATOR, i * 1000000))
+            .collect();
+        assert_eq!(
+            parse_tx(STAKE_MAX_COUNT, &[SUI_COIN_1F87, SUI_COIN_EBFF]),
+            Some(KnownTx::StakeTx {
+                stakes,
+                total_amount: 10000000,
+                gas_budget: 123000,
+            })
+        );
+        // One more stake is rejected, rather than left out of the review
+        assert_eq!(
+            parse_tx(STAKE_OVER_MAX_COUNT, &[SUI_COIN_1F87, SUI_COIN_EBFF]),
+            None
+        );
+    }
+
+    #[test]
+    fn unstake_staked_coin() {
+        assert_eq!(
+            parse_tx(UNSTAKE_STAKED_COIN, &[SUI_COIN_4939, STAKED_SUI_D263]),
+            Some(KnownTx::UnstakeTx {
+                total_amount: 3000000000,
+                gas_budget: 10901576,
+            })
+        );
+    }
+
+    #[test]
+    fn restake_staked_coin() {
+        assert_eq!(
+            parse_tx(RESTAKE_STAKED_COIN, &[SUI_COIN_4939, STAKED_SUI_D263]),
+            Some(KnownTx::RestakeTx {
+                source_stake: hex!(
+                    "d263dbcc9f13e9302b8b629518d2a12c5be755aba0c978606cc291367059015d"
+                ),
+                recipient: VALIDATOR,
+                total_amount: 3000000000,
+                gas_budget: 10901576,
+            })
+        );
+    }
+
+    #[test]
+    fn intent_not_transaction_data() {
+        let mut tx = SUI_WHOLE_GAS_COIN;
+        // IntentScope::TransactionEffects
+        tx[1] = 1;
+        assert_eq!(parse_tx(tx, &[SUI_COIN_400D]), None);
+    }
+
+    #[test]
+    fn input_count_over_u16() {
+        // 70000 inputs
+        assert_eq!(parse_tx(hex!("0000000000f0a204"), &[]), None);
+    }
+
+    #[test]
+    fn input_count_not_minimal() {
+        // The input count of the transaction, with a redundant zero ULEB128 digit
+        let mut tx = SUI_WHOLE_GAS_COIN.to_vec();
+        assert!(tx[5] < 0x80);
+        tx[5] |= 0x80;
+        tx.insert(6, 0);
+        assert_eq!(parse_tx(tx, &[SUI_COIN_400D]), None);
+    }
+
+    #[test]
+    fn move_call_unknown_argument() {
+        // The GasCoin argument of request_add_stake, with an unknown Argument tag
+        let mut tx = STAKE_GAS_COIN.to_vec();
+        assert_eq!(tx[151], 0);
+        tx[151] = 7;
+        let mut bs = TestReadable::new(tx);
+        let parser = tx_parser(
+            MockObjectData(&[SUI_COIN_1C12, SUI_COIN_1F87, SUI_COIN_A93F, SUI_COIN_EBFF]),
+            None::<&MoveCallDescriptor>,
+        );
+        let e = match poll_once(TryResultFuture(parser.parse(&mut bs))) {
+            core::task::Poll::Ready(Err(e)) => e,
+            _ => panic!("not rejected"),
+        };
+        assert_eq!(e.code, SyscallError::NotSupported as u16);
+        if breadcrumbs::ENABLED {
+            assert_eq!(
+                alloc::format!("{}", e.path),
+                "TransactionData.V1.Kind.PTB.commands[0].MoveCall.args[1]"
+            );
+        }
+    }
+
+    fn swap_descriptor() -> MoveCallDescriptor {
+        let arg = |arg, label, format| ArgDescriptor {
+            arg,
+            label: ArrayString::from(label).unwrap(),
+            format,
+        };
+        MoveCallDescriptor {
+            package: hex!("dee9000000000000000000000000000000000000000000000000000000000000"),
+            module: ArrayString::from("pool").unwrap(),
+            function: ArrayString::from("swap").unwrap(),
+            label: ArrayString::from("swap coins").unwrap(),
+            args: [
+                arg(0, "Pool", ArgFormat::Object),
+                arg(1, "Amount in", ArgFormat::Amount { type_arg: 0 }),
+                arg(2, "Max slippage", ArgFormat::Percentage),
+            ]
+            .into_iter()
+            .collect(),
+        }
+    }
+
+    #[test]
+    fn described_move_call() {
+        let descriptor = swap_descriptor();
+        let call = DescribedMoveCall {
+            label: ArrayString::from("swap coins").unwrap(),
+            args: [
+                (
+                    ArrayString::from("Pool").unwrap(),
+                    DescribedValue::Object([0x7f; 32]),
+                ),
+                (
+                    ArrayString::from("Amount in").unwrap(),
+                    DescribedValue::Amount(2000000, usdc_coin_type()),
+                ),
+                (
+                    ArrayString::from("Max slippage").unwrap(),
+                    DescribedValue::Percentage(50),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        };
+        assert_eq!(
+            parse_described_tx(DEX_SWAP, &[], Some(&descriptor)),
+            Some(KnownTx::MoveCallTx {
+                call: Box::new(call),
+                gas_budget: 2000000,
+            })
+        );
+    }
+
+    #[test]
+    fn described_move_call_rejected() {
+        let descriptor = swap_descriptor();
+        // Without a descriptor, or with the descriptor of another function
+        assert_eq!(parse_tx(DEX_SWAP, &[]), None);
+        let mut other = descriptor.clone();
+        other.function = ArrayString::from("swap_exact").unwrap();
+        assert_eq!(parse_described_tx(DEX_SWAP, &[], Some(&other)), None);
+
+        // The amount is described with the type of an argument the call does not have
+        let mut other = descriptor.clone();
+        other.args[1].format = ArgFormat::Amount { type_arg: 2 };
+        assert_eq!(parse_described_tx(DEX_SWAP, &[], Some(&other)), None);
+
+        // The results are transferred to someone else than the sender
+        let mut tx = DEX_SWAP;
+        // The recipient input, then the sender
+        let (recipient, sender) = (71..103, 259..291);
+        assert_eq!(tx[recipient.clone()], tx[sender]);
+        tx[recipient].fill(0x11);
+        assert_eq!(parse_described_tx(tx, &[], Some(&descriptor)), None);
+    }
+
+    #[test]
+    fn described_move_call_undescribed_coin() {
+        let descriptor = swap_descriptor();
+        assert_eq!(
+            parse_described_tx(DEX_SWAP_WITH_COIN, &[SUI_COIN_1C12], Some(&descriptor)),
+            None
+        );
+
+        // The coin is shown once it is described
+        let mut described = descriptor.clone();
+        described.args.push(ArgDescriptor {
+            arg: 3,
+            label: ArrayString::from("Fee").unwrap(),
+            format: ArgFormat::Amount { type_arg: 1 },
+        });
+        let call = parse_described_tx(DEX_SWAP_WITH_COIN, &[SUI_COIN_1C12], Some(&described));
+        match call {
+            Some(KnownTx::MoveCallTx { call, .. }) => assert_eq!(
+                call.args[3],
+                (
+                    ArrayString::from("Fee").unwrap(),
+                    DescribedValue::Amount(10000000, SUI_COIN_TYPE)
+                )
+            ),
+            _ => panic!("not clear signed"),
+        }
+    }
+
+    #[test]
+    fn described_move_call_arg_count_mismatch() {
+        let descriptor = swap_descriptor();
+        // An argument is not described
+        let mut other = descriptor.clone();
+        other.args.pop();
+        assert_eq!(parse_described_tx(DEX_SWAP, &[], Some(&other)), None);
+
+        // An argument the call does not have is described
+        let mut other = descriptor.clone();
+        other.args.push(ArgDescriptor {
+            arg: 3,
+            label: ArrayString::from("Recipient").unwrap(),
+            format: ArgFormat::Address,
+        });
+        assert_eq!(parse_described_tx(DEX_SWAP, &[], Some(&other)), None);
+
+        // An argument is described twice, and another not at all
+        let mut other = descriptor.clone();
+        other.args[2] = ArgDescriptor {
+            arg: 1,
+            label: ArrayString::from("Amount").unwrap(),
+            format: ArgFormat::Percentage,
+        };
+        assert_eq!(parse_described_tx(DEX_SWAP, &[], Some(&other)), None);
+    }
 }
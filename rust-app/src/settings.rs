@@ -1,48 +1,232 @@
-use ledger_device_sdk::nvm::*;
-use ledger_device_sdk::NVMData;
+#[cfg(target_family = "bolos")]
+pub use storage::Settings;
 
-// This is necessary to store the object in NVM and not in RAM
 const SETTINGS_SIZE: usize = 10;
-#[link_section = ".nvm_data"]
-static mut SETTINGS: NVMData<AtomicStorage<[u8; SETTINGS_SIZE]>> =
-    NVMData::new(AtomicStorage::new(&[0u8; 10]));
 
+// The switches of the settings menu are toggled by NBGL directly in the NVM,
+// using their index in the array. So each switch is kept in its own byte.
 const BLINDSIGN_IX: usize = 0;
+const EXPERT_MODE_IX: usize = 1;
+const HIGH_VALUE_WARNING_IX: usize = 2;
+// Set with an APDU only, as a u32 LE
+const HIGH_VALUE_THRESHOLD_IX: usize = 3;
 
-#[derive(Clone, Copy)]
-pub struct Settings;
+// The last byte holds the version of the layout.
+// Version 0 is the layout of the app before versioning, where the NVM
+// was only written through the settings menu and set_blind_sign.
+const VERSION_IX: usize = SETTINGS_SIZE - 1;
+const SETTINGS_VERSION: u8 = 2;
 
-impl Default for Settings {
-    fn default() -> Self {
-        Settings
-    }
+// In whole coins, when the switch is on and no threshold was set with an APDU
+pub const DEFAULT_HIGH_VALUE_THRESHOLD: u32 = 1000;
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct SettingsData {
+    pub blind_sign: bool,
+    pub expert_mode: bool,
+    pub high_value_warning: bool,
+    // In whole coins, 0 if not set
+    pub high_value_threshold: u32,
 }
 
-impl Settings {
-    #[inline(never)]
-    pub fn get_mut(&mut self) -> &mut AtomicStorage<[u8; SETTINGS_SIZE]> {
-        let data = &raw mut SETTINGS;
-        unsafe { (*data).get_mut() }
+impl SettingsData {
+    pub fn decode(raw: &[u8; SETTINGS_SIZE]) -> Self {
+        SettingsData {
+            blind_sign: raw[BLINDSIGN_IX] == 1,
+            expert_mode: raw[EXPERT_MODE_IX] == 1,
+            high_value_warning: raw[HIGH_VALUE_WARNING_IX] == 1,
+            high_value_threshold: u32::from_le_bytes(
+                raw[HIGH_VALUE_THRESHOLD_IX..HIGH_VALUE_THRESHOLD_IX + 4]
+                    .try_into()
+                    .unwrap(),
+            ),
+        }
+    }
+
+    pub fn encode(&self) -> [u8; SETTINGS_SIZE] {
+        let mut raw = [0u8; SETTINGS_SIZE];
+        raw[BLINDSIGN_IX] = self.blind_sign as u8;
+        raw[EXPERT_MODE_IX] = self.expert_mode as u8;
+        raw[HIGH_VALUE_WARNING_IX] = self.high_value_warning as u8;
+        raw[HIGH_VALUE_THRESHOLD_IX..HIGH_VALUE_THRESHOLD_IX + 4]
+            .copy_from_slice(&self.high_value_threshold.to_le_bytes());
+        raw[VERSION_IX] = SETTINGS_VERSION;
+        raw
     }
 
-    #[inline(never)]
-    pub fn get_blind_sign(&self) -> bool {
-        let data = &raw const SETTINGS;
-        let settings = unsafe { (*data).get_ref() };
-        settings.get_ref()[BLINDSIGN_IX] == 1
+    // Returns the settings in the current layout, from the raw NVM content
+    // of any version
+    pub fn migrate(raw: &[u8; SETTINGS_SIZE]) -> Self {
+        match raw[VERSION_IX] {
+            // The switches were stored at the same indexes, and unused bytes were 0
+            0 | 1 | SETTINGS_VERSION => Self::decode(raw),
+            // Unknown layout, eg after a downgrade of the app
+            _ => Self::default(),
+        }
     }
+}
+
+#[cfg(target_family = "bolos")]
+mod storage {
+    use super::*;
+    use ledger_device_sdk::nvm::*;
+    use ledger_device_sdk::NVMData;
+
+    // This is necessary to store the object in NVM and not in RAM
+    #[link_section = ".nvm_data"]
+    static mut SETTINGS: NVMData<AtomicStorage<[u8; SETTINGS_SIZE]>> =
+        NVMData::new(AtomicStorage::new(&[0u8; 10]));
+
+    #[derive(Clone, Copy)]
+    pub struct Settings;
+
+    impl Default for Settings {
+        fn default() -> Self {
+            Settings
+        }
+    }
+
+    impl Settings {
+        #[inline(never)]
+        pub fn get_mut(&mut self) -> &mut AtomicStorage<[u8; SETTINGS_SIZE]> {
+            let data = &raw mut SETTINGS;
+            unsafe { (*data).get_mut() }
+        }
+
+        // Rewrites the NVM in the current layout, if it was written by an older version of the app
+        // This must be done before the settings menu is shown
+        #[inline(never)]
+        pub fn migrate(&mut self) {
+            let data = &raw mut SETTINGS;
+            let settings = unsafe { (*data).get_mut() };
+            let raw: [u8; SETTINGS_SIZE] = *settings.get_ref();
+            if raw[VERSION_IX] != SETTINGS_VERSION {
+                settings.update(&SettingsData::migrate(&raw).encode());
+            }
+        }
+
+        #[inline(never)]
+        pub fn get(&self) -> SettingsData {
+            let data = &raw const SETTINGS;
+            let settings = unsafe { (*data).get_ref() };
+            SettingsData::migrate(settings.get_ref())
+        }
+
+        // The inline(never) is important. Otherwise weird segmentation faults happen on speculos.
+        #[inline(never)]
+        pub fn set(&mut self, value: SettingsData) {
+            let data = &raw mut SETTINGS;
+            let settings = unsafe { (*data).get_mut() };
+            settings.update(&value.encode());
+        }
 
-    // The inline(never) is important. Otherwise weird segmentation faults happen on speculos.
-    #[inline(never)]
-    pub fn set_blind_sign(&mut self, enabled: bool) {
-        let data = &raw mut SETTINGS;
-        let settings = unsafe { (*data).get_mut() };
-        let mut switch_values: [u8; SETTINGS_SIZE] = *settings.get_ref();
-        if enabled {
-            switch_values[BLINDSIGN_IX] = 1;
-        } else {
-            switch_values[BLINDSIGN_IX] = 0;
+        pub fn get_blind_sign(&self) -> bool {
+            self.get().blind_sign
         }
-        settings.update(&switch_values);
+
+        pub fn set_blind_sign(&mut self, enabled: bool) {
+            let value = SettingsData {
+                blind_sign: enabled,
+                ..self.get()
+            };
+            self.set(value);
+        }
+
+        pub fn get_expert_mode(&self) -> bool {
+            self.get().expert_mode
+        }
+
+        pub fn set_expert_mode(&mut self, enabled: bool) {
+            let value = SettingsData {
+                expert_mode: enabled,
+                ..self.get()
+            };
+            self.set(value);
+        }
+
+        // The threshold of the transfers which are confirmed twice, in whole coins
+        pub fn get_high_value_threshold(&self) -> Option<u32> {
+            let settings = self.get();
+            match settings.high_value_threshold {
+                _ if !settings.high_value_warning => None,
+                0 => Some(DEFAULT_HIGH_VALUE_THRESHOLD),
+                threshold => Some(threshold),
+            }
+        }
+
+        // None turns the warning off
+        pub fn set_high_value_threshold(&mut self, threshold: Option<u32>) {
+            let value = SettingsData {
+                high_value_warning: threshold.is_some(),
+                high_value_threshold: threshold.unwrap_or(0),
+                ..self.get()
+            };
+            self.set(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[cfg(target_family = "bolos")]
+    #[allow(unused_imports)]
+    use ledger_device_sdk::testing::TestType;
+    #[cfg(target_family = "bolos")]
+    use testmacro::test_item as test;
+
+    #[test]
+    fn migrate_baseline() {
+        // Blind signing enabled with the settings menu, before versioning
+        let mut raw = [0u8; SETTINGS_SIZE];
+        raw[BLINDSIGN_IX] = 1;
+        assert_eq!(
+            SettingsData::migrate(&raw),
+            SettingsData {
+                blind_sign: true,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn migrate_version_1() {
+        // Expert mode, without the high value warning
+        let mut raw = [0u8; SETTINGS_SIZE];
+        raw[EXPERT_MODE_IX] = 1;
+        raw[VERSION_IX] = 1;
+        assert_eq!(
+            SettingsData::migrate(&raw),
+            SettingsData {
+                expert_mode: true,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn migrate_unknown_version() {
+        let mut raw = [1u8; SETTINGS_SIZE];
+        raw[VERSION_IX] = SETTINGS_VERSION + 1;
+        assert_eq!(SettingsData::migrate(&raw), SettingsData::default());
+    }
+
+    #[test]
+    fn encode_decode() {
+        let settings = SettingsData {
+            blind_sign: true,
+            expert_mode: false,
+            high_value_warning: true,
+            high_value_threshold: 0x12345678,
+        };
+        let raw = settings.encode();
+        assert_eq!(raw[VERSION_IX], SETTINGS_VERSION);
+        assert_eq!(
+            raw[HIGH_VALUE_THRESHOLD_IX..][..4],
+            [0x78, 0x56, 0x34, 0x12]
+        );
+        assert_eq!(SettingsData::decode(&raw), settings);
+        assert_eq!(SettingsData::migrate(&raw), settings);
     }
 }
@@ -18,6 +18,7 @@ use panic_handler::{set_swap_panic_handler, swap_panic_handler};
 use params::{CheckAddressParams, PrintableAmountParams, TxParams, MAX_SWAP_TICKER_LENGTH};
 
 use crate::app_main::app_main;
+use crate::utils::swap_checks::SWAP_FEE_POLICY;
 use crate::{ctx::RunCtx, parser::common::SUI_COIN_DECIMALS, utils::get_amount_in_decimals};
 use crate::{implementation::BIP32_PREFIX, interface::SuiPubKeyAddress};
 
@@ -94,9 +95,10 @@ pub fn get_printable_amount(params: &PrintableAmountParams) -> Result<ArrayStrin
     Ok(printable_amount)
 }
 
+// The received fee is the gas budget of the transaction, see SWAP_FEE_POLICY
 pub fn check_tx_params(expected: &TxParams, received: &TxParams) -> bool {
     expected.amount == received.amount
-        && expected.fee == received.fee
+        && SWAP_FEE_POLICY.check(expected.fee, received.fee)
         && expected.destination_address == received.destination_address
 }
 
@@ -57,7 +57,7 @@ impl TryFrom<&custom::CheckAddressParams> for CheckAddressParams {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CoinConfig {
     pub ticker: ArrayString<MAX_SWAP_TICKER_LENGTH>,
     pub decimals: u8,
@@ -126,6 +126,8 @@ pub struct TxParams {
     pub amount: u64,
     pub fee: u64,
     pub destination_address: SuiAddressRaw,
+    // The coin being swapped, None for SUI
+    pub coin_config: Option<CoinConfig>,
 }
 
 impl TryFrom<&custom::CreateTxParams> for TxParams {
@@ -146,10 +148,14 @@ impl TryFrom<&custom::CreateTxParams> for TxParams {
 
         let destination_address = address_from_hex_cstr(params.dest_address.as_ptr())?;
 
+        let coin_config =
+            CoinConfig::try_from_bytes(&params.coin_config[..params.coin_config_len])?;
+
         Ok(TxParams {
             amount,
             fee,
             destination_address,
+            coin_config,
         })
     }
 }
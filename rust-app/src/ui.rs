@@ -1,4 +1,6 @@
+#[cfg(target_family = "bolos")]
 use ledger_device_sdk::include_gif;
+#[cfg(target_family = "bolos")]
 use ledger_device_sdk::nbgl::*;
 
 #[cfg(any(target_os = "nanosplus", target_os = "nanox"))]
@@ -13,6 +15,10 @@ pub const APP_ICON: NbglGlyph = NbglGlyph::from_include(include_gif!("sui_48x48.
 #[cfg(any(target_os = "stax", target_os = "flex"))]
 pub const APP_ICON: NbglGlyph = NbglGlyph::from_include(include_gif!("sui_64x64.gif", NBGL));
 
+// Only the review model is built on the host, the rendering needs the SDK
+#[cfg(target_family = "bolos")]
 pub mod nbgl;
+#[cfg(target_family = "bolos")]
 pub use nbgl::*;
 pub mod common;
+pub mod review;
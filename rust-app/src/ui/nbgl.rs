@@ -1,21 +1,28 @@
-use crate::ctx::RunCtx;
 use crate::interface::*;
-use crate::parser::common::{CoinType, SUI_COIN_DECIMALS};
-use crate::ui::common::*;
-use crate::utils::*;
+use crate::ui::review::*;
 
 extern crate alloc;
 use alloc::format;
 use alloc::string::ToString;
+use alloc::vec::Vec;
 
-use crate::crypto_helpers::common::HexSlice;
 use crate::crypto_helpers::hasher::HexHash;
 use core::cell::RefCell;
-use either::*;
 use ledger_device_sdk::nbgl::*;
 
 use super::*;
 
+fn nbgl_fields(review: &ReviewModel) -> Vec<Field<'_>> {
+    review
+        .fields
+        .iter()
+        .map(|f| Field {
+            name: f.name.as_str(),
+            value: f.value.as_str(),
+        })
+        .collect()
+}
+
 #[derive(Copy, Clone)]
 pub struct UserInterface {
     pub main_menu: &'static RefCell<NbglHomeAndSettings>,
@@ -46,57 +53,50 @@ impl UserInterface {
         }
     }
 
-    pub fn confirm_sign_tx(
-        &self,
-        address: &SuiPubKeyAddress,
-        recipient: [u8; 32],
-        total_amount: u64,
-        coin_type: CoinType,
-        gas_budget: u64,
-        ctx: &RunCtx,
-    ) -> Option<()> {
-        self.do_refresh.replace(true);
-        let from = Field {
-            name: "From",
-            value: &format!("{address}"),
-        };
-        let to = Field {
-            name: "To",
-            value: &format!("0x{}", HexSlice(&recipient)),
-        };
-        let gas = Field {
-            name: "Max Gas",
-            value: {
-                let (quotient, remainder_str) =
-                    get_amount_in_decimals(gas_budget, SUI_COIN_DECIMALS);
-                &format!("SUI {}.{}", quotient, remainder_str.as_str())
-            },
-        };
-        let ((amt_str, amt_val), coin_fields) =
-            get_coin_and_amount_fields(total_amount, coin_type, ctx);
-        let amt = Field {
-            name: amt_str.as_str(),
-            value: amt_val.as_str(),
-        };
+    // A transfer with a high value warning is confirmed again after the review, by holding
+    pub fn confirm_tx_review(&self, review: &ReviewModel, warning: Option<&str>) -> Option<()> {
+        self.show_review(review, false, warning)
+    }
 
-        let do_review = |fields, ticker| {
-            let first_msg = &format!("Review transaction to transfer {ticker}");
-            let last_msg = &format!("Sign transaction to transfer {ticker}");
-            NbglReview::new()
-                .glyph(&APP_ICON)
-                .titles(first_msg, "", last_msg)
-                .show(fields)
-        };
-        let success = match coin_fields {
-            Left(ticker) => do_review(&[from, to, amt, gas], ticker.as_str()),
-            Right((coin_str, id_str)) => {
-                let coin = Field {
-                    name: coin_str.as_str(),
-                    value: id_str.as_str(),
-                };
-                do_review(&[from, to, coin, amt, gas], "coins")
+    pub fn confirm_blind_sign_tx(&self, hash: &HexHash<32>) -> Option<()> {
+        self.show_review(&blind_sign_review(&hash.0), true, None)
+    }
+
+    fn show_review(&self, review: &ReviewModel, blind: bool, warning: Option<&str>) -> Option<()> {
+        self.do_refresh.replace(true);
+        if let Some(warning) = warning {
+            let proceed = NbglChoice::new().show(
+                "High value transfer",
+                warning,
+                "Continue",
+                "Reject transaction",
+            );
+            if !proceed {
+                NbglReviewStatus::new()
+                    .status_type(StatusType::Transaction)
+                    .show(false);
+                return None;
             }
-        };
+        }
+        let fields = nbgl_fields(review);
+
+        let mut nbgl_review = NbglReview::new().glyph(&APP_ICON);
+        if blind {
+            nbgl_review = nbgl_review.blind();
+        }
+        let mut success = nbgl_review
+            .titles(&review.title, "", &review.final_message)
+            .show(&fields);
+        if success && warning.is_some() {
+            success = NbglGenericReview::new()
+                .add_content(NbglPageContent::InfoLongPress(InfoLongPress {
+                    text: "Confirm high value transfer",
+                    icon: Some(&APP_ICON),
+                    long_press_text: "Hold to confirm",
+                    tune_id: TuneIndex::TapCasual,
+                }))
+                .show("Reject transaction");
+        }
         NbglReviewStatus::new()
             .status_type(StatusType::Transaction)
             .show(success);
@@ -107,97 +107,36 @@ impl UserInterface {
         }
     }
 
-    pub fn confirm_stake_tx(
-        &self,
-        address: &SuiPubKeyAddress,
-        recipient: [u8; 32],
-        total_amount: u64,
-        gas_budget: u64,
-    ) -> Option<()> {
+    // None turns the high value warning off
+    pub fn confirm_high_value_threshold(&self, threshold: Option<u32>) -> Option<()> {
         self.do_refresh.replace(true);
-        let from = Field {
-            name: "From",
-            value: &format!("{address}"),
-        };
-        let to = Field {
-            name: "Validator",
-            value: if recipient == LEDGER_STAKE_ADDRESS {
-                "Ledger by P2P"
-            } else {
-                &format!("0x{}", HexSlice(&recipient))
-            },
-        };
-        let gas = Field {
-            name: "Max Gas",
-            value: {
-                let (quotient, remainder_str) =
-                    get_amount_in_decimals(gas_budget, SUI_COIN_DECIMALS);
-                &format!("SUI {}.{}", quotient, remainder_str.as_str())
-            },
-        };
-
-        let (quotient, remainder_str) = get_amount_in_decimals(total_amount, SUI_COIN_DECIMALS);
-        let amt = Field {
-            name: "Stake amount",
-            value: &format!("SUI {}.{}", quotient, remainder_str.as_str()),
-        };
-
-        let do_review = |fields| {
-            let first_msg = "Review transaction to stake SUI".to_string();
-            let last_msg = "Sign transaction to stake SUI".to_string();
-            NbglReview::new()
-                .glyph(&APP_ICON)
-                .titles(&first_msg, "", &last_msg)
-                .show(fields)
+        let message = match threshold {
+            Some(threshold) => {
+                format!(
+                    "Transfers above {threshold} whole units of any coin, e.g. {threshold} SUI, \
+                     will need an extra confirmation"
+                )
+            }
+            None => "Transfers will not need an extra confirmation".to_string(),
         };
-        let success = do_review(&[from, amt, to, gas]);
-        NbglReviewStatus::new()
-            .status_type(StatusType::Transaction)
-            .show(success);
-        if success {
+        let confirmed =
+            NbglChoice::new().show("Change high value threshold", &message, "Confirm", "Reject");
+        if confirmed {
             Some(())
         } else {
             None
         }
     }
 
-    pub fn confirm_unstake_tx(
-        &self,
-        address: &SuiPubKeyAddress,
-        total_amount: u64,
-        gas_budget: u64,
-    ) -> Option<()> {
+    pub fn confirm_allowlist_entry(&self, review: &ReviewModel) -> Option<()> {
         self.do_refresh.replace(true);
-        let from = Field {
-            name: "From",
-            value: &format!("{address}"),
-        };
-        let gas = Field {
-            name: "Max Gas",
-            value: {
-                let (quotient, remainder_str) =
-                    get_amount_in_decimals(gas_budget, SUI_COIN_DECIMALS);
-                &format!("SUI {}.{}", quotient, remainder_str.as_str())
-            },
-        };
-
-        let (quotient, remainder_str) = get_amount_in_decimals(total_amount, SUI_COIN_DECIMALS);
-        let amt = Field {
-            name: "Unstake amount",
-            value: &format!("SUI {}.{}", quotient, remainder_str.as_str()),
-        };
-
-        let do_review = |fields| {
-            let first_msg = "Review transaction to unstake SUI".to_string();
-            let last_msg = "Sign transaction to unstake SUI".to_string();
-            NbglReview::new()
-                .glyph(&APP_ICON)
-                .titles(&first_msg, "", &last_msg)
-                .show(fields)
-        };
-        let success = do_review(&[from, amt, gas]);
+        let fields = nbgl_fields(review);
+        let success = NbglReview::new()
+            .glyph(&APP_ICON)
+            .titles(&review.title, "", &review.final_message)
+            .show(&fields);
         NbglReviewStatus::new()
-            .status_type(StatusType::Transaction)
+            .status_type(StatusType::Operation)
             .show(success);
         if success {
             Some(())
@@ -206,22 +145,17 @@ impl UserInterface {
         }
     }
 
-    pub fn confirm_blind_sign_tx(&self, hash: &HexHash<32>) -> Option<()> {
+    pub fn confirm_allowlist_only(&self, allowlist_only: bool) -> Option<()> {
         self.do_refresh.replace(true);
-        let tx_fields = [Field {
-            name: "Transaction hash",
-            value: &format!("0x{hash}"),
-        }];
-
-        let success = NbglReview::new()
-            .glyph(&APP_ICON)
-            .blind()
-            .titles("Review transaction", "", "Sign transaction")
-            .show(&tx_fields);
-        NbglReviewStatus::new()
-            .status_type(StatusType::Transaction)
-            .show(success);
-        if success {
+        let message = if allowlist_only {
+            "Transfers to recipients not in the allowlist, dApp calls and blind signing \
+             will be refused"
+        } else {
+            "Transfers to any recipient will be allowed"
+        };
+        let confirmed =
+            NbglChoice::new().show("Change allowlist mode", message, "Confirm", "Reject");
+        if confirmed {
             Some(())
         } else {
             None
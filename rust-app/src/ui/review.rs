@@ -0,0 +1,847 @@
+// What the user is shown to review a transaction, independently of how it is rendered
+use crate::ctx::RunCtx;
+use crate::parser::common::{CoinType, SuiAddressRaw, SUI_COIN_DECIMALS};
+use crate::parser::move_call::{DescribedMoveCall, DescribedValue};
+use crate::parser::tx::{KnownTx, TxDetails};
+use crate::ui::common::*;
+use crate::utils::*;
+
+extern crate alloc;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use either::*;
+use ledger_parser_combinators::numbers::Decimal;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReviewField {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReviewModel {
+    pub title: String,
+    pub fields: Vec<ReviewField>,
+    pub final_message: String,
+}
+
+// The transaction details and the signing digest, shown in expert mode
+pub type ExpertDetails<'a> = (&'a TxDetails, &'a [u8; 32]);
+
+fn field(name: &str, value: String) -> ReviewField {
+    ReviewField {
+        name: name.to_string(),
+        value,
+    }
+}
+
+fn address_str(address: &SuiAddressRaw) -> String {
+    format!("0x{}", HexSlice(address))
+}
+
+fn sui_amount_str(amount: u64) -> String {
+    let (quotient, remainder_str) = get_amount_in_decimals(amount, SUI_COIN_DECIMALS);
+    format!("SUI {}.{}", quotient, remainder_str.as_str())
+}
+
+fn validator_str(validator: &SuiAddressRaw) -> String {
+    if *validator == LEDGER_STAKE_ADDRESS {
+        "Ledger by P2P".to_string()
+    } else {
+        address_str(validator)
+    }
+}
+
+fn gas_field(gas_budget: u64) -> ReviewField {
+    field("Max Gas", sui_amount_str(gas_budget))
+}
+
+fn review(action: &str, fields: Vec<ReviewField>, expert: Option<ExpertDetails>) -> ReviewModel {
+    let mut fields = fields;
+    if let Some(expert) = expert {
+        fields.extend(expert_fields(expert));
+    }
+    ReviewModel {
+        title: format!("Review transaction to {action}"),
+        fields,
+        final_message: format!("Sign transaction to {action}"),
+    }
+}
+
+pub fn known_tx_review(
+    sender: &SuiAddressRaw,
+    tx: &KnownTx,
+    expert: Option<ExpertDetails>,
+    ctx: &RunCtx,
+) -> ReviewModel {
+    match tx {
+        KnownTx::TransferTx {
+            recipient,
+            coin_type,
+            total_amount,
+            gas_budget,
+        } => transfer_review(
+            sender,
+            recipient,
+            *total_amount,
+            coin_type.clone(),
+            *gas_budget,
+            expert,
+            ctx,
+        ),
+        KnownTx::StakeTx {
+            stakes,
+            total_amount,
+            gas_budget,
+        } => stake_review(sender, stakes, *total_amount, *gas_budget, expert),
+        KnownTx::UnstakeTx {
+            total_amount,
+            gas_budget,
+        } => unstake_review(sender, *total_amount, *gas_budget, expert),
+        KnownTx::RestakeTx {
+            source_stake,
+            recipient,
+            total_amount,
+            gas_budget,
+        } => restake_review(
+            sender,
+            source_stake,
+            recipient,
+            *total_amount,
+            *gas_budget,
+            expert,
+        ),
+        KnownTx::CoinManagementTx {
+            coin_type,
+            input_coin_count,
+            output_amounts,
+            gas_budget,
+        } => coin_management_review(
+            sender,
+            coin_type.clone(),
+            *input_coin_count,
+            output_amounts,
+            *gas_budget,
+            expert,
+            ctx,
+        ),
+        KnownTx::MoveCallTx { call, gas_budget } => {
+            move_call_review(sender, call, *gas_budget, expert, ctx)
+        }
+    }
+}
+
+pub fn transfer_review(
+    sender: &SuiAddressRaw,
+    recipient: &SuiAddressRaw,
+    total_amount: u64,
+    coin_type: CoinType,
+    gas_budget: u64,
+    expert: Option<ExpertDetails>,
+    ctx: &RunCtx,
+) -> ReviewModel {
+    let ((amt_str, amt_val), coin_fields) =
+        get_coin_and_amount_fields(total_amount, coin_type, ctx);
+    let mut fields = vec![
+        field("From", address_str(sender)),
+        field("To", address_str(recipient)),
+    ];
+    let ticker = match coin_fields {
+        Left(ticker) => ticker.to_string(),
+        Right((coin_str, id_str)) => {
+            fields.push(field(coin_str.as_str(), id_str.to_string()));
+            "coins".to_string()
+        }
+    };
+    fields.push(field(amt_str.as_str(), amt_val.to_string()));
+    fields.push(gas_field(gas_budget));
+    review(&format!("transfer {ticker}"), fields, expert)
+}
+
+pub fn stake_review(
+    sender: &SuiAddressRaw,
+    stakes: &[(SuiAddressRaw, u64)],
+    total_amount: u64,
+    gas_budget: u64,
+    expert: Option<ExpertDetails>,
+) -> ReviewModel {
+    let mut fields = vec![field("From", address_str(sender))];
+    match stakes {
+        [(validator, _)] => {
+            fields.push(field("Stake amount", sui_amount_str(total_amount)));
+            fields.push(field("Validator", validator_str(validator)));
+        }
+        _ => {
+            // One page per validator, with the amount staked with it
+            for (i, (validator, amount)) in stakes.iter().enumerate() {
+                fields.push(field(
+                    &format!("Validator {}", i + 1),
+                    validator_str(validator),
+                ));
+                fields.push(field(&format!("Amount {}", i + 1), sui_amount_str(*amount)));
+            }
+            fields.push(field("Total stake", sui_amount_str(total_amount)));
+        }
+    }
+    fields.push(gas_field(gas_budget));
+    review("stake SUI", fields, expert)
+}
+
+pub fn unstake_review(
+    sender: &SuiAddressRaw,
+    total_amount: u64,
+    gas_budget: u64,
+    expert: Option<ExpertDetails>,
+) -> ReviewModel {
+    let fields = vec![
+        field("From", address_str(sender)),
+        field("Unstake amount", sui_amount_str(total_amount)),
+        gas_field(gas_budget),
+    ];
+    review("unstake SUI", fields, expert)
+}
+
+pub fn restake_review(
+    sender: &SuiAddressRaw,
+    source_stake: &SuiAddressRaw,
+    recipient: &SuiAddressRaw,
+    total_amount: u64,
+    gas_budget: u64,
+    expert: Option<ExpertDetails>,
+) -> ReviewModel {
+    // The withdrawn stake rewards are re-staked as well, but their amount
+    // is not known, so we only show the principal
+    let fields = vec![
+        field("From", address_str(sender)),
+        field("Source stake", address_str(source_stake)),
+        field("Principal (excl. rewards)", sui_amount_str(total_amount)),
+        field("Validator", validator_str(recipient)),
+        gas_field(gas_budget),
+    ];
+    review("restake SUI", fields, expert)
+}
+
+pub fn coin_management_review(
+    sender: &SuiAddressRaw,
+    coin_type: CoinType,
+    input_coin_count: usize,
+    output_amounts: &[u64],
+    gas_budget: u64,
+    expert: Option<ExpertDetails>,
+    ctx: &RunCtx,
+) -> ReviewModel {
+    let mut coin_field = None;
+    let outputs: Vec<ReviewField> = output_amounts
+        .iter()
+        .enumerate()
+        .map(|(i, amount)| {
+            let ((amt_str, amt_val), coin_fields) =
+                get_coin_and_amount_fields(*amount, coin_type.clone(), ctx);
+            if coin_field.is_none() {
+                coin_field = Some(coin_fields);
+            }
+            field(
+                &format!("{} {}", amt_str.as_str(), i + 1),
+                amt_val.to_string(),
+            )
+        })
+        .collect();
+
+    let mut fields = vec![field("From", address_str(sender))];
+    if let Some(Right((coin_str, id_str))) = coin_field {
+        fields.push(field(coin_str.as_str(), id_str.to_string()));
+    }
+    fields.push(field("Input coins", format!("{input_coin_count}")));
+    fields.extend(outputs);
+    fields.push(field("Outputs owner", "Sender (unchanged)".to_string()));
+    fields.push(gas_field(gas_budget));
+    review("merge/split coins", fields, expert)
+}
+
+// The labels come from the descriptor of the call, which is signed by Ledger. The label of the
+// call is a verb phrase, e.g. "swap coins", shown after "Review transaction to".
+pub fn move_call_review(
+    sender: &SuiAddressRaw,
+    call: &DescribedMoveCall,
+    gas_budget: u64,
+    expert: Option<ExpertDetails>,
+    ctx: &RunCtx,
+) -> ReviewModel {
+    let mut fields = vec![field("From", address_str(sender))];
+    for (label, value) in call.args.iter() {
+        match value {
+            DescribedValue::Amount(amount, coin_type) => {
+                let ((_, amt_val), coin_fields) =
+                    get_coin_and_amount_fields(*amount, coin_type.clone(), ctx);
+                fields.push(field(label, amt_val.to_string()));
+                // The raw amount of an unknown coin, followed by the coin type
+                if let Right((_, id_str)) = coin_fields {
+                    fields.push(field(&format!("{label} coin"), id_str.to_string()));
+                }
+            }
+            DescribedValue::Address(address) | DescribedValue::Object(address) => {
+                fields.push(field(label, address_str(address)))
+            }
+            DescribedValue::Percentage(basis_points) => {
+                fields.push(field(label, format!("{}%", Decimal::new(*basis_points, 2))))
+            }
+        }
+    }
+    fields.push(gas_field(gas_budget));
+    review(call.label.as_str(), fields, expert)
+}
+
+// The warning shown before the review of a transfer above the threshold, in whole coins. The
+// amounts of unknown coins cannot be compared with it.
+pub fn high_value_warning(tx: &KnownTx, threshold: u32, ctx: &RunCtx) -> Option<String> {
+    let KnownTx::TransferTx {
+        coin_type,
+        total_amount,
+        ..
+    } = tx
+    else {
+        return None;
+    };
+    let (ticker, decimals) = get_known_coin_ticker(coin_type, ctx)?;
+    let limit = 10u128
+        .checked_pow(decimals as u32)?
+        .checked_mul(threshold as u128)?;
+    if (*total_amount as u128) > limit {
+        Some(format!(
+            "The amount is above {} {}",
+            threshold,
+            ticker.as_str()
+        ))
+    } else {
+        None
+    }
+}
+
+// Shows the label of an allowlisted recipient, after its address
+pub fn add_recipient_label(review: &mut ReviewModel, label: &str) {
+    if let Some(i) = review.fields.iter().position(|f| f.name == "To") {
+        review
+            .fields
+            .insert(i + 1, field("Allowlisted as", label.to_string()));
+    }
+}
+
+// The review of an entry added to or removed from the recipient allowlist
+pub fn allowlist_entry_review(add: bool, address: &SuiAddressRaw, label: &str) -> ReviewModel {
+    let (title, final_message) = if add {
+        ("Add recipient to allowlist", "Add recipient")
+    } else {
+        ("Remove recipient from allowlist", "Remove recipient")
+    };
+    ReviewModel {
+        title: title.to_string(),
+        fields: vec![
+            field("Address", address_str(address)),
+            field("Label", label.to_string()),
+        ],
+        final_message: final_message.to_string(),
+    }
+}
+
+pub fn blind_sign_review(hash: &[u8; 32]) -> ReviewModel {
+    ReviewModel {
+        title: "Review transaction".to_string(),
+        fields: vec![field("Transaction hash", format!("0x{}", HexSlice(hash)))],
+        final_message: "Sign transaction".to_string(),
+    }
+}
+
+// The additional fields shown in expert mode
+fn expert_fields((details, hash): ExpertDetails) -> Vec<ReviewField> {
+    let mut fields = vec![
+        field("Gas price", format!("{} MIST", details.gas_price)),
+        field("Gas owner", address_str(&details.gas_owner)),
+        field(
+            "Expiration",
+            match details.expiration {
+                Some(epoch) => format!("Epoch {epoch}"),
+                None => "None".to_string(),
+            },
+        ),
+        field("Gas coins", format!("{}", details.gas_coin_count)),
+        field("Input objects", format!("{}", details.input_objects.count)),
+    ];
+    for (i, id) in details.input_objects.ids.iter().enumerate() {
+        fields.push(field(&format!("Input object {}", i + 1), address_str(id)));
+    }
+    fields.push(field("Signing digest", format!("0x{}", HexSlice(hash))));
+    fields
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parser::common::SUI_COIN_TYPE;
+    use crate::parser::tx::InputObjects;
+    use arrayvec::{ArrayString, ArrayVec};
+    use hex_literal::hex;
+
+    #[cfg(target_family = "bolos")]
+    #[allow(unused_imports)]
+    use ledger_device_sdk::testing::TestType;
+    #[cfg(target_family = "bolos")]
+    use testmacro::test_item as test;
+
+    const SENDER: SuiAddressRaw =
+        hex!("1d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be488");
+    const RECIPIENT: SuiAddressRaw =
+        hex!("6fb21feead027da4873295affd6c4f3618fe176fa2fbf3e7b5ef1d9463b31e21");
+    const VALIDATOR: SuiAddressRaw =
+        hex!("35f5f154f0117464e3379c45c7f3cb6b2d4efedf300acadff8a7e8e9e3a15109");
+    const STAKE: SuiAddressRaw =
+        hex!("d263dbcc9f13e9302b8b629518d2a12c5be755aba0c978606cc291367059015d");
+
+    fn fields(review: &ReviewModel) -> Vec<(&str, &str)> {
+        review
+            .fields
+            .iter()
+            .map(|f| (f.name.as_str(), f.value.as_str()))
+            .collect()
+    }
+
+    fn coin_type(id: [u8; 32], module: &str, name: &str) -> CoinType {
+        (
+            id,
+            ArrayVec::try_from(module.as_bytes()).unwrap(),
+            ArrayVec::try_from(name.as_bytes()).unwrap(),
+        )
+    }
+
+    #[test]
+    fn transfer_sui() {
+        let tx = KnownTx::TransferTx {
+            recipient: RECIPIENT,
+            coin_type: SUI_COIN_TYPE,
+            total_amount: 30000000,
+            gas_budget: 2997880,
+        };
+        let review = known_tx_review(&SENDER, &tx, None, &RunCtx::app());
+        assert_eq!(review.title, "Review transaction to transfer SUI");
+        assert_eq!(
+            fields(&review),
+            vec![
+                (
+                    "From",
+                    "0x1d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be488"
+                ),
+                (
+                    "To",
+                    "0x6fb21feead027da4873295affd6c4f3618fe176fa2fbf3e7b5ef1d9463b31e21"
+                ),
+                ("Amount", "SUI 0.03"),
+                ("Max Gas", "SUI 0.00299788"),
+            ]
+        );
+        assert_eq!(review.final_message, "Sign transaction to transfer SUI");
+    }
+
+    #[test]
+    fn transfer_known_token() {
+        let usdc = coin_type(
+            hex!("dba34672e30cb065b1f93e3ab55318768fd6fef66c15942c9f7cb846e2f900e7"),
+            "usdc",
+            "USDC",
+        );
+        let review = transfer_review(
+            &SENDER,
+            &RECIPIENT,
+            100023,
+            usdc,
+            2501224,
+            None,
+            &RunCtx::app(),
+        );
+        assert_eq!(review.title, "Review transaction to transfer USDC");
+        assert_eq!(
+            fields(&review)[2..],
+            [("Amount", "USDC 0.100023"), ("Max Gas", "SUI 0.002501224")]
+        );
+        assert_eq!(review.final_message, "Sign transaction to transfer USDC");
+    }
+
+    #[test]
+    fn transfer_token_from_ctx() {
+        let ctx = RunCtx::app();
+        let id = [0x12; 32];
+        ctx.set_token(
+            id,
+            ArrayString::from("abc").unwrap(),
+            ArrayString::from("ABC").unwrap(),
+            3,
+            ArrayString::from("ABC").unwrap(),
+        );
+        let review = transfer_review(
+            &SENDER,
+            &RECIPIENT,
+            100023,
+            coin_type(id, "abc", "ABC"),
+            2501224,
+            None,
+            &ctx,
+        );
+        assert_eq!(review.title, "Review transaction to transfer ABC");
+        assert_eq!(fields(&review)[2], ("Amount", "ABC 100.023"));
+    }
+
+    #[test]
+    fn transfer_unknown_token() {
+        let review = transfer_review(
+            &SENDER,
+            &RECIPIENT,
+            100023,
+            coin_type([0x12; 32], "abc", "ABC"),
+            2501224,
+            None,
+            &RunCtx::app(),
+        );
+        assert_eq!(review.title, "Review transaction to transfer coins");
+        assert_eq!(
+            fields(&review)[2..],
+            [
+                (
+                    "Coin",
+                    "1212121212121212121212121212121212121212121212121212121212121212::abc::ABC"
+                ),
+                ("Raw Amount", "100023"),
+                ("Max Gas", "SUI 0.002501224"),
+            ]
+        );
+        assert_eq!(review.final_message, "Sign transaction to transfer coins");
+    }
+
+    #[test]
+    fn stake_ledger_validator() {
+        let mut stakes = ArrayVec::new();
+        stakes.push((LEDGER_STAKE_ADDRESS, 1000000000));
+        let tx = KnownTx::StakeTx {
+            stakes,
+            total_amount: 1000000000,
+            gas_budget: 123000,
+        };
+        let review = known_tx_review(&SENDER, &tx, None, &RunCtx::app());
+        assert_eq!(review.title, "Review transaction to stake SUI");
+        assert_eq!(
+            fields(&review)[1..],
+            [
+                ("Stake amount", "SUI 1.0"),
+                ("Validator", "Ledger by P2P"),
+                ("Max Gas", "SUI 0.000123"),
+            ]
+        );
+        assert_eq!(review.final_message, "Sign transaction to stake SUI");
+    }
+
+    #[test]
+    fn stake_multiple_validators() {
+        let review = stake_review(
+            &SENDER,
+            &[(VALIDATOR, 30000000), (LEDGER_STAKE_ADDRESS, 10000000)],
+            40000000,
+            123000,
+            None,
+        );
+        assert_eq!(
+            fields(&review)[1..],
+            [
+                (
+                    "Validator 1",
+                    "0x35f5f154f0117464e3379c45c7f3cb6b2d4efedf300acadff8a7e8e9e3a15109"
+                ),
+                ("Amount 1", "SUI 0.03"),
+                ("Validator 2", "Ledger by P2P"),
+                ("Amount 2", "SUI 0.01"),
+                ("Total stake", "SUI 0.04"),
+                ("Max Gas", "SUI 0.000123"),
+            ]
+        );
+    }
+
+    #[test]
+    fn unstake() {
+        let tx = KnownTx::UnstakeTx {
+            total_amount: 3000000000,
+            gas_budget: 10901576,
+        };
+        let review = known_tx_review(&SENDER, &tx, None, &RunCtx::app());
+        assert_eq!(review.title, "Review transaction to unstake SUI");
+        assert_eq!(
+            fields(&review)[1..],
+            [
+                ("Unstake amount", "SUI 3.0"),
+                ("Max Gas", "SUI 0.010901576"),
+            ]
+        );
+        assert_eq!(review.final_message, "Sign transaction to unstake SUI");
+    }
+
+    #[test]
+    fn restake() {
+        let tx = KnownTx::RestakeTx {
+            source_stake: STAKE,
+            recipient: LEDGER_STAKE_ADDRESS,
+            total_amount: 3000000000,
+            gas_budget: 10901576,
+        };
+        let review = known_tx_review(&SENDER, &tx, None, &RunCtx::app());
+        assert_eq!(review.title, "Review transaction to restake SUI");
+        assert_eq!(
+            fields(&review)[1..],
+            [
+                (
+                    "Source stake",
+                    "0xd263dbcc9f13e9302b8b629518d2a12c5be755aba0c978606cc291367059015d"
+                ),
+                ("Principal (excl. rewards)", "SUI 3.0"),
+                ("Validator", "Ledger by P2P"),
+                ("Max Gas", "SUI 0.010901576"),
+            ]
+        );
+        assert_eq!(review.final_message, "Sign transaction to restake SUI");
+    }
+
+    #[test]
+    fn coin_management() {
+        let mut output_amounts = ArrayVec::new();
+        output_amounts.push(40000000);
+        output_amounts.push(5);
+        let tx = KnownTx::CoinManagementTx {
+            coin_type: SUI_COIN_TYPE,
+            input_coin_count: 2,
+            output_amounts,
+            gas_budget: 2019760,
+        };
+        let review = known_tx_review(&SENDER, &tx, None, &RunCtx::app());
+        assert_eq!(review.title, "Review transaction to merge/split coins");
+        assert_eq!(
+            fields(&review)[1..],
+            [
+                ("Input coins", "2"),
+                ("Amount 1", "SUI 0.04"),
+                ("Amount 2", "SUI 0.000000005"),
+                ("Outputs owner", "Sender (unchanged)"),
+                ("Max Gas", "SUI 0.00201976"),
+            ]
+        );
+        assert_eq!(
+            review.final_message,
+            "Sign transaction to merge/split coins"
+        );
+    }
+
+    #[test]
+    fn coin_management_unknown_token() {
+        let review = coin_management_review(
+            &SENDER,
+            coin_type([0x12; 32], "abc", "ABC"),
+            3,
+            &[100],
+            2019760,
+            None,
+            &RunCtx::app(),
+        );
+        assert_eq!(
+            fields(&review)[1..],
+            [
+                (
+                    "Coin",
+                    "1212121212121212121212121212121212121212121212121212121212121212::abc::ABC"
+                ),
+                ("Input coins", "3"),
+                ("Raw Amount 1", "100"),
+                ("Outputs owner", "Sender (unchanged)"),
+                ("Max Gas", "SUI 0.00201976"),
+            ]
+        );
+    }
+
+    #[test]
+    fn expert_fields_last() {
+        let mut input_objects = InputObjects::default();
+        input_objects.ids.push(STAKE);
+        input_objects.count = 2;
+        let details = TxDetails {
+            gas_price: 750,
+            gas_owner: SENDER,
+            expiration: Some(512),
+            gas_coin_count: 1,
+            input_objects,
+        };
+        let hash = [0xab; 32];
+        let review = unstake_review(&SENDER, 3000000000, 10901576, Some((&details, &hash)));
+        assert_eq!(
+            fields(&review)[3..],
+            [
+                ("Gas price", "750 MIST"),
+                (
+                    "Gas owner",
+                    "0x1d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be488"
+                ),
+                ("Expiration", "Epoch 512"),
+                ("Gas coins", "1"),
+                ("Input objects", "2"),
+                (
+                    "Input object 1",
+                    "0xd263dbcc9f13e9302b8b629518d2a12c5be755aba0c978606cc291367059015d"
+                ),
+                (
+                    "Signing digest",
+                    "0xabababababababababababababababababababababababababababababababab"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn blind_sign() {
+        let review = blind_sign_review(&[0xab; 32]);
+        assert_eq!(review.title, "Review transaction");
+        assert_eq!(
+            fields(&review),
+            vec![(
+                "Transaction hash",
+                "0xabababababababababababababababababababababababababababababababab"
+            )]
+        );
+        assert_eq!(review.final_message, "Sign transaction");
+    }
+
+    #[test]
+    fn described_move_call() {
+        let label = |s| ArrayString::from(s).unwrap();
+        let call = DescribedMoveCall {
+            label: label("swap coins"),
+            args: [
+                (label("Pool"), DescribedValue::Object(STAKE)),
+                (
+                    label("Amount in"),
+                    DescribedValue::Amount(2500000000, SUI_COIN_TYPE),
+                ),
+                (
+                    label("Min out"),
+                    DescribedValue::Amount(100023, coin_type([0x12; 32], "abc", "ABC")),
+                ),
+                (label("Max slippage"), DescribedValue::Percentage(5)),
+            ]
+            .into_iter()
+            .collect(),
+        };
+        let tx = KnownTx::MoveCallTx {
+            call: Box::new(call),
+            gas_budget: 2501224,
+        };
+        let review = known_tx_review(&SENDER, &tx, None, &RunCtx::app());
+        assert_eq!(review.title, "Review transaction to swap coins");
+        assert_eq!(
+            fields(&review)[1..],
+            [
+                (
+                    "Pool",
+                    "0xd263dbcc9f13e9302b8b629518d2a12c5be755aba0c978606cc291367059015d"
+                ),
+                ("Amount in", "SUI 2.5"),
+                ("Min out", "100023"),
+                (
+                    "Min out coin",
+                    "1212121212121212121212121212121212121212121212121212121212121212::abc::ABC"
+                ),
+                ("Max slippage", "0.05%"),
+                ("Max Gas", "SUI 0.002501224"),
+            ]
+        );
+        assert_eq!(review.final_message, "Sign transaction to swap coins");
+    }
+
+    #[test]
+    fn high_value_transfer() {
+        let transfer = |total_amount, coin_type| KnownTx::TransferTx {
+            recipient: RECIPIENT,
+            coin_type,
+            total_amount,
+            gas_budget: 2997880,
+        };
+        let ctx = RunCtx::app();
+        assert_eq!(
+            high_value_warning(&transfer(1000000000001, SUI_COIN_TYPE), 1000, &ctx).as_deref(),
+            Some("The amount is above 1000 SUI")
+        );
+        assert_eq!(
+            high_value_warning(&transfer(1000000000000, SUI_COIN_TYPE), 1000, &ctx),
+            None
+        );
+
+        // A token with 3 decimals, then the same token when it is not known
+        let abc = coin_type([0x12; 32], "abc", "ABC");
+        assert_eq!(
+            high_value_warning(&transfer(100023, abc.clone()), 100, &ctx),
+            None
+        );
+        ctx.set_token(
+            [0x12; 32],
+            ArrayString::from("abc").unwrap(),
+            ArrayString::from("ABC").unwrap(),
+            3,
+            ArrayString::from("ABC").unwrap(),
+        );
+        assert_eq!(
+            high_value_warning(&transfer(100023, abc), 100, &ctx).as_deref(),
+            Some("The amount is above 100 ABC")
+        );
+
+        // Only transfers are checked
+        let tx = KnownTx::UnstakeTx {
+            total_amount: 3000000000000,
+            gas_budget: 10901576,
+        };
+        assert_eq!(high_value_warning(&tx, 1000, &ctx), None);
+    }
+
+    #[test]
+    fn transfer_to_allowlisted_recipient() {
+        let mut review = transfer_review(
+            &SENDER,
+            &RECIPIENT,
+            30000000,
+            SUI_COIN_TYPE,
+            2997880,
+            None,
+            &RunCtx::app(),
+        );
+        add_recipient_label(&mut review, "Treasury");
+        assert_eq!(
+            fields(&review)[1..4],
+            [
+                (
+                    "To",
+                    "0x6fb21feead027da4873295affd6c4f3618fe176fa2fbf3e7b5ef1d9463b31e21"
+                ),
+                ("Allowlisted as", "Treasury"),
+                ("Amount", "SUI 0.03"),
+            ]
+        );
+    }
+
+    #[test]
+    fn allowlist_entry() {
+        let review = allowlist_entry_review(false, &RECIPIENT, "Treasury");
+        assert_eq!(review.title, "Remove recipient from allowlist");
+        assert_eq!(
+            fields(&review),
+            [
+                (
+                    "Address",
+                    "0x6fb21feead027da4873295affd6c4f3618fe176fa2fbf3e7b5ef1d9463b31e21"
+                ),
+                ("Label", "Treasury"),
+            ]
+        );
+        assert_eq!(review.final_message, "Remove recipient");
+    }
+}
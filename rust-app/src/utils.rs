@@ -1,3 +1,5 @@
+pub mod swap_checks;
+
 // A couple type ascription functions to help the compiler along.
 pub const fn mkfn<A, B, C>(q: fn(&A, &mut B) -> C) -> fn(&A, &mut B) -> C {
     q
@@ -52,7 +54,7 @@ pub fn get_amount_in_decimals(amount: u64, decimals: u8) -> (u64, ArrayString<12
 }
 
 extern crate alloc;
-use alloc::collections::BTreeMap;
+use alloc::collections::{BTreeMap, BTreeSet};
 use core::mem::size_of;
 
 /// Estimates the memory usage of a BTreeMap
@@ -71,3 +73,24 @@ pub fn estimate_btree_map_usage<K, V>(map: &BTreeMap<K, V>) -> usize {
 
     base_size + (entry_size * map.len())
 }
+
+/// Estimates the memory usage of a BTreeSet, in the same way as a BTreeMap
+pub fn estimate_btree_set_usage<K>(set: &BTreeSet<K>) -> usize {
+    let base_size = size_of::<BTreeSet<K>>();
+    let node_overhead = 16;
+
+    base_size + ((size_of::<K>() + node_overhead) * set.len())
+}
+
+pub struct HexSlice<'a>(pub &'a [u8]);
+
+// You can choose to implement multiple traits, like Lower and UpperHex
+impl core::fmt::Display for HexSlice<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for byte in self.0 {
+            // Decide if you want to pad the value or have spaces inbetween, etc.
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,128 @@
+use crate::parser::common::SUI_COIN_DECIMALS;
+
+// Checks that the coin of the transaction is the one in the swap coin config.
+// Both coins are given by their ticker and decimals. The coin of the transaction is
+// only known if it is SUI, the token of the dynamic descriptor, or a known coin.
+// Without a coin config, the swapped coin is SUI. Tickers are compared exactly, as
+// coins can have tickers differing only by their case.
+pub fn check_coin_type(expected: Option<(&str, u8)>, received: Option<(&str, u8)>) -> bool {
+    match (expected, received) {
+        (None, Some((ticker, decimals))) => ticker == "SUI" && decimals == SUI_COIN_DECIMALS,
+        (Some(expected), Some(received)) => expected == received,
+        (_, None) => false,
+    }
+}
+
+// How the gas budget of the transaction is compared to the fee quoted by the exchange.
+// The gas budget is estimated again when the transaction is built, after the quote,
+// so an exact match would make swaps fail randomly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeePolicy {
+    // The gas budget must be equal to the quoted fee
+    Exact,
+    // The gas budget must be at most the quoted fee
+    AtMost,
+    // The gas budget may exceed the quoted fee by this many basis points of the quoted fee
+    Tolerance { basis_points: u16 },
+}
+
+pub const SWAP_FEE_POLICY: FeePolicy = FeePolicy::AtMost;
+
+const BASIS_POINTS_PER_UNIT: u128 = 10_000;
+
+impl FeePolicy {
+    pub fn check(&self, quoted_fee: u64, gas_budget: u64) -> bool {
+        match self {
+            FeePolicy::Exact => gas_budget == quoted_fee,
+            FeePolicy::AtMost => gas_budget <= quoted_fee,
+            FeePolicy::Tolerance { basis_points } => {
+                // Computed on u128 so that it cannot overflow
+                let max_budget = quoted_fee as u128
+                    + quoted_fee as u128 * *basis_points as u128 / BASIS_POINTS_PER_UNIT;
+                gas_budget as u128 <= max_budget
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[cfg(target_family = "bolos")]
+    #[allow(unused_imports)]
+    use ledger_device_sdk::testing::TestType;
+    #[cfg(target_family = "bolos")]
+    use testmacro::test_item as test;
+
+    const USDC: Option<(&str, u8)> = Some(("USDC", 6));
+
+    #[test]
+    fn coin_type_sui() {
+        assert!(check_coin_type(None, Some(("SUI", SUI_COIN_DECIMALS))));
+        assert!(!check_coin_type(None, Some(("USDC", 6))));
+        assert!(!check_coin_type(USDC, Some(("SUI", SUI_COIN_DECIMALS))));
+    }
+
+    #[test]
+    fn coin_type_token() {
+        assert!(check_coin_type(USDC, Some(("USDC", 6))));
+    }
+
+    #[test]
+    fn coin_type_wrong_ticker() {
+        assert!(!check_coin_type(USDC, Some(("USDT", 6))));
+        assert!(!check_coin_type(USDC, Some(("usdc", 6))));
+        assert!(!check_coin_type(None, Some(("sui", SUI_COIN_DECIMALS))));
+    }
+
+    #[test]
+    fn coin_type_wrong_decimals() {
+        assert!(!check_coin_type(USDC, Some(("USDC", 8))));
+        assert!(!check_coin_type(None, Some(("SUI", 6))));
+    }
+
+    #[test]
+    fn coin_type_unknown_coin() {
+        assert!(!check_coin_type(None, None));
+        assert!(!check_coin_type(USDC, None));
+    }
+
+    #[test]
+    fn fee_policy_exact() {
+        assert!(FeePolicy::Exact.check(1000, 1000));
+        assert!(!FeePolicy::Exact.check(1000, 999));
+        assert!(!FeePolicy::Exact.check(1000, 1001));
+    }
+
+    #[test]
+    fn fee_policy_at_most() {
+        assert!(FeePolicy::AtMost.check(1000, 1000));
+        assert!(FeePolicy::AtMost.check(1000, 0));
+        assert!(!FeePolicy::AtMost.check(1000, 1001));
+        assert!(FeePolicy::AtMost.check(u64::MAX, u64::MAX));
+    }
+
+    #[test]
+    fn fee_policy_tolerance() {
+        // 5%
+        let policy = FeePolicy::Tolerance { basis_points: 500 };
+        assert!(policy.check(1000, 900));
+        assert!(policy.check(1000, 1050));
+        assert!(!policy.check(1000, 1051));
+        assert!(policy.check(u64::MAX, u64::MAX));
+        assert!(!policy.check(0, 1));
+    }
+
+    #[test]
+    fn fee_policy_zero_tolerance() {
+        let policy = FeePolicy::Tolerance { basis_points: 0 };
+        assert!(policy.check(1000, 1000));
+        assert!(!policy.check(1000, 1001));
+    }
+
+    #[test]
+    fn swap_fee_policy_accepts_lower_budget() {
+        assert!(SWAP_FEE_POLICY.check(2_000_000, 1_500_000));
+        assert!(!SWAP_FEE_POLICY.check(2_000_000, 2_000_001));
+    }
+}
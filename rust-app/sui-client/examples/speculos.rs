@@ -0,0 +1,28 @@
+// Gets the version and the address of the app running in speculos
+//
+//   cargo run --example speculos -- "m/44'/784'/0'/0'/0'"
+use sui_client::transport::SpeculosTransport;
+use sui_client::{parse_bip32_path, Error, SuiClient};
+
+fn main() -> Result<(), Error> {
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "m/44'/784'/0'/0'/0'".to_string());
+    let path = parse_bip32_path(&path)?;
+
+    let mut client = SuiClient::new(SpeculosTransport::connect_default()?);
+    let version = client.get_version()?;
+    println!(
+        "{} {}.{}.{}",
+        version.name, version.major, version.minor, version.patch
+    );
+
+    let key = client.get_pubkey(&path)?;
+    println!("public key: {}", hex(&key.public_key));
+    println!("address: 0x{}", hex(&key.address));
+    Ok(())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,239 @@
+// Host half of the block protocol of alamgu-async-block
+//
+// The parameters of a command are split into chunks, each stored in a block
+// made of the hash of the next block followed by the chunk. The command is
+// started with the hashes of the first block of each parameter, then the
+// device asks for the blocks it needs (GET_CHUNK), may store blocks on the
+// host (PUT_CHUNK), and sends its result in pieces (RESULT_ACCUMULATING),
+// up to the last one (RESULT_FINAL).
+use crate::transport::{Transport, SW_OK};
+use crate::Error;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+// Size of the chunk of a parameter in each block
+pub const BLOCK_CHUNK_SIZE: usize = 180;
+
+pub type BlockHash = [u8; 32];
+
+// Next hash of the last block of a parameter
+pub const LAST_BLOCK_HASH: BlockHash = [0; 32];
+
+// Commands sent by the host, the first byte of each APDU
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum HostToLedger {
+    Start = 0,
+    GetChunkResponseSuccess = 1,
+    GetChunkResponseFailure = 2,
+    PutChunkResponse = 3,
+    ResultAccumulatingResponse = 4,
+}
+
+// Commands sent by the device, the first byte of each response
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum LedgerToHost {
+    ResultAccumulating = 0,
+    ResultFinal = 1,
+    GetChunk = 2,
+    PutChunk = 3,
+}
+
+impl TryFrom<u8> for LedgerToHost {
+    type Error = Error;
+    fn try_from(v: u8) -> Result<Self, Error> {
+        match v {
+            0 => Ok(LedgerToHost::ResultAccumulating),
+            1 => Ok(LedgerToHost::ResultFinal),
+            2 => Ok(LedgerToHost::GetChunk),
+            3 => Ok(LedgerToHost::PutChunk),
+            _ => Err(Error::Protocol("unknown command from the device")),
+        }
+    }
+}
+
+// The blocks the device can ask for, by hash
+#[derive(Default, Clone, Debug)]
+pub struct BlockStore {
+    blocks: HashMap<BlockHash, Vec<u8>>,
+}
+
+impl BlockStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Stores a block, and returns its hash
+    pub fn insert(&mut self, block: Vec<u8>) -> BlockHash {
+        let hash: BlockHash = Sha256::digest(&block).into();
+        self.blocks.insert(hash, block);
+        hash
+    }
+
+    pub fn get(&self, hash: &BlockHash) -> Option<&[u8]> {
+        self.blocks.get(hash).map(Vec::as_slice)
+    }
+
+    // Splits a parameter into linked blocks, and returns the hash of the first one
+    // An empty parameter has no blocks, its hash is LAST_BLOCK_HASH.
+    pub fn add_parameter(&mut self, param: &[u8]) -> BlockHash {
+        let mut next = LAST_BLOCK_HASH;
+        for chunk in param.chunks(BLOCK_CHUNK_SIZE).rev() {
+            let mut block = Vec::with_capacity(32 + chunk.len());
+            block.extend_from_slice(&next);
+            block.extend_from_slice(chunk);
+            next = self.insert(block);
+        }
+        next
+    }
+}
+
+// Runs a command with the block protocol, and returns its result
+pub fn send_with_blocks<T: Transport>(
+    transport: &mut T,
+    cla: u8,
+    ins: u8,
+    p1: u8,
+    p2: u8,
+    params: &[&[u8]],
+) -> Result<Vec<u8>, Error> {
+    let mut store = BlockStore::new();
+    let mut start = vec![HostToLedger::Start as u8];
+    for param in params {
+        start.extend_from_slice(&store.add_parameter(param));
+    }
+    handle_blocks_protocol(transport, cla, ins, p1, p2, start, &mut store)
+}
+
+// Answers the requests of the device, from the first APDU of the command,
+// up to its final result
+pub fn handle_blocks_protocol<T: Transport>(
+    transport: &mut T,
+    cla: u8,
+    ins: u8,
+    p1: u8,
+    p2: u8,
+    initial_payload: Vec<u8>,
+    store: &mut BlockStore,
+) -> Result<Vec<u8>, Error> {
+    let mut payload = initial_payload;
+    let mut result = Vec::new();
+
+    loop {
+        let (rv, sw) = transport.exchange(cla, ins, p1, p2, &payload)?;
+        if sw != SW_OK {
+            return Err(Error::Status(sw));
+        }
+        let (&command, rv_payload) = rv
+            .split_first()
+            .ok_or(Error::Protocol("empty response from the device"))?;
+
+        payload = match LedgerToHost::try_from(command)? {
+            LedgerToHost::ResultAccumulating => {
+                result.extend_from_slice(rv_payload);
+                vec![HostToLedger::ResultAccumulatingResponse as u8]
+            }
+            LedgerToHost::ResultFinal => {
+                result.extend_from_slice(rv_payload);
+                return Ok(result);
+            }
+            LedgerToHost::GetChunk => {
+                let hash: &BlockHash = rv_payload
+                    .try_into()
+                    .map_err(|_| Error::Protocol("GET_CHUNK without a block hash"))?;
+                match store.get(hash) {
+                    Some(block) => {
+                        let mut p = vec![HostToLedger::GetChunkResponseSuccess as u8];
+                        p.extend_from_slice(block);
+                        p
+                    }
+                    None => vec![HostToLedger::GetChunkResponseFailure as u8],
+                }
+            }
+            LedgerToHost::PutChunk => {
+                store.insert(rv_payload.to_vec());
+                vec![HostToLedger::PutChunkResponse as u8]
+            }
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::VecDeque;
+
+    // Plays the device side of a command, checking each APDU against the expected one
+    struct ScriptedDevice(VecDeque<(Vec<u8>, Vec<u8>)>);
+
+    impl Transport for ScriptedDevice {
+        fn exchange(
+            &mut self,
+            _cla: u8,
+            _ins: u8,
+            _p1: u8,
+            _p2: u8,
+            data: &[u8],
+        ) -> Result<(Vec<u8>, u16), Error> {
+            let (expected, response) = self.0.pop_front().expect("unexpected APDU");
+            assert_eq!(data, &expected[..]);
+            Ok((response, SW_OK))
+        }
+    }
+
+    fn cat(parts: &[&[u8]]) -> Vec<u8> {
+        parts.concat()
+    }
+
+    #[test]
+    fn parameter_blocks_are_linked() {
+        let param: Vec<u8> = (0..=255u8).chain(0..=43u8).collect();
+        let mut store = BlockStore::new();
+        let first = store.add_parameter(&param);
+
+        let block0 = store.get(&first).unwrap().to_vec();
+        assert_eq!(&block0[32..], &param[..BLOCK_CHUNK_SIZE]);
+        let next: BlockHash = block0[..32].try_into().unwrap();
+        let block1 = store.get(&next).unwrap();
+        assert_eq!(&block1[..32], &LAST_BLOCK_HASH);
+        assert_eq!(&block1[32..], &param[BLOCK_CHUNK_SIZE..]);
+    }
+
+    #[test]
+    fn empty_parameter_has_no_blocks() {
+        let mut store = BlockStore::new();
+        assert_eq!(store.add_parameter(&[]), LAST_BLOCK_HASH);
+        assert_eq!(store.get(&LAST_BLOCK_HASH), None);
+    }
+
+    #[test]
+    fn answers_chunk_requests_and_accumulates_result() {
+        let mut store = BlockStore::new();
+        let hash = store.add_parameter(b"param");
+        let block = store.get(&hash).unwrap().to_vec();
+        let put = b"stored by the device".to_vec();
+        let put_hash: BlockHash = Sha256::digest(&put).into();
+
+        let mut device = ScriptedDevice(VecDeque::from([
+            (cat(&[&[0], &hash]), cat(&[&[2], &hash])),
+            (cat(&[&[1], &block]), cat(&[&[3], &put])),
+            (vec![3], cat(&[&[2], &put_hash])),
+            (cat(&[&[1], &put]), cat(&[&[2], &[7; 32]])),
+            (vec![2], cat(&[&[0], b"res"])),
+            (vec![4], cat(&[&[1], b"ult"])),
+        ]));
+        let result = send_with_blocks(&mut device, 0, 3, 0, 0, &[b"param"]).unwrap();
+        assert_eq!(result, b"result");
+        assert!(device.0.is_empty());
+    }
+
+    #[test]
+    fn unknown_command_is_an_error() {
+        let mut device = ScriptedDevice(VecDeque::from([(vec![0], vec![9])]));
+        assert!(matches!(
+            send_with_blocks(&mut device, 0, 0, 0, 0, &[]),
+            Err(Error::Protocol(_))
+        ));
+    }
+}
@@ -0,0 +1,339 @@
+// Host client of the Sui ledger app
+//
+// The commands of the app are sent with the block protocol of
+// alamgu-async-block (see block.rs), over any Transport, such as the APDU
+// socket of speculos.
+use std::fmt;
+
+pub mod block;
+pub mod transport;
+
+use block::send_with_blocks;
+use transport::{Transport, SW_OK};
+
+pub const CLA: u8 = 0x00;
+pub const P1: u8 = 0x00;
+pub const P2: u8 = 0x00;
+
+// Instructions of the app, see Ins in interface.rs
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Ins {
+    GetVersion = 0x00,
+    VerifyAddress = 0x01,
+    GetPubkey = 0x02,
+    Sign = 0x03,
+    ProvideTrustedDynamicDescriptor = 0x22,
+    ProvideMoveCallDescriptor = 0x23,
+    SetHighValueThreshold = 0x24,
+    AddAllowlistEntry = 0x25,
+    RemoveAllowlistEntry = 0x26,
+    SetAllowlistOnly = 0x27,
+    GetVersionStr = 0xfe,
+    Exit = 0xff,
+}
+
+// The PKI certificate used to check the signature of dynamic descriptors
+// is loaded with an APDU of the OS, outside of the block protocol
+pub const PKI_CLA: u8 = 0xb0;
+pub const PKI_INS_LOAD_CERTIFICATE: u8 = 0x06;
+pub const PKI_KEY_USAGE_COIN_META: u8 = 0x08;
+pub const PKI_KEY_USAGE_CALLDATA: u8 = 0x0b;
+
+// Most components of a BIP32 path accepted by the app
+pub const MAX_BIP32_PATH_LENGTH: usize = 10;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    // The device answered with this status word
+    Status(u16),
+    // The device did not follow the block protocol
+    Protocol(&'static str),
+    // The response of a command could not be decoded
+    BadResponse(&'static str),
+    // This data is longer than the protocol allows
+    TooLong(&'static str, usize),
+    BadPath(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "transport error: {}", e),
+            Error::Status(sw) => write!(f, "device returned status {:#06x}", sw),
+            Error::Protocol(msg) => write!(f, "block protocol error: {}", msg),
+            Error::BadResponse(msg) => write!(f, "bad response: {}", msg),
+            Error::TooLong(what, len) => write!(f, "{} too long: {} bytes", what, len),
+            Error::BadPath(path) => write!(f, "bad BIP32 path: {}", path),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+// Parses a path like m/44'/784'/0'/0'/0'
+pub fn parse_bip32_path(path: &str) -> Result<Vec<u32>, Error> {
+    let bad_path = || Error::BadPath(path.to_string());
+    let mut components = path.split('/');
+    if components.next() != Some("m") {
+        return Err(bad_path());
+    }
+    let path: Vec<u32> = components
+        .map(|c| {
+            let (index, hardened) = match c.strip_suffix('\'') {
+                Some(i) => (i, 0x8000_0000),
+                None => (c, 0),
+            };
+            let index: u32 = index.parse().map_err(|_| bad_path())?;
+            if index >= 0x8000_0000 {
+                return Err(bad_path());
+            }
+            Ok(index | hardened)
+        })
+        .collect::<Result<_, _>>()?;
+    if path.len() > MAX_BIP32_PATH_LENGTH {
+        return Err(bad_path());
+    }
+    Ok(path)
+}
+
+// Encodes a path as the app expects: component count (u8), then each component (u32 LE)
+pub fn pack_bip32_path(path: &[u32]) -> Vec<u8> {
+    let mut rv = Vec::with_capacity(1 + 4 * path.len());
+    rv.push(path.len() as u8);
+    for c in path {
+        rv.extend_from_slice(&c.to_le_bytes());
+    }
+    rv
+}
+
+// Encodes the transaction parameter of a signing request: length (u32 LE), then the transaction
+pub fn pack_transaction(tx: &[u8]) -> Vec<u8> {
+    let mut rv = Vec::with_capacity(4 + tx.len());
+    rv.extend_from_slice(&(tx.len() as u32).to_le_bytes());
+    rv.extend_from_slice(tx);
+    rv
+}
+
+// Encodes the object list of a signing request: count (u32 LE), then each
+// object prefixed by its length (u32 LE)
+pub fn pack_object_list(objects: &[&[u8]]) -> Vec<u8> {
+    let mut rv = Vec::new();
+    rv.extend_from_slice(&(objects.len() as u32).to_le_bytes());
+    for o in objects {
+        rv.extend_from_slice(&(o.len() as u32).to_le_bytes());
+        rv.extend_from_slice(o);
+    }
+    rv
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AppVersion {
+    pub major: u8,
+    pub minor: u8,
+    pub patch: u8,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PublicKey {
+    pub public_key: Vec<u8>,
+    pub address: Vec<u8>,
+}
+
+pub struct SuiClient<T> {
+    transport: T,
+}
+
+impl<T: Transport> SuiClient<T> {
+    pub fn new(transport: T) -> Self {
+        SuiClient { transport }
+    }
+
+    pub fn into_transport(self) -> T {
+        self.transport
+    }
+
+    // Runs a command of the app with the block protocol
+    pub fn send(&mut self, ins: Ins, params: &[&[u8]]) -> Result<Vec<u8>, Error> {
+        send_with_blocks(&mut self.transport, CLA, ins as u8, P1, P2, params)
+    }
+
+    pub fn get_version(&mut self) -> Result<AppVersion, Error> {
+        let rv = self.send(Ins::GetVersion, &[&[]])?;
+        match rv.as_slice() {
+            [major, minor, patch, name @ ..] => Ok(AppVersion {
+                major: *major,
+                minor: *minor,
+                patch: *patch,
+                name: String::from_utf8(name.to_vec())
+                    .map_err(|_| Error::BadResponse("app name is not UTF-8"))?,
+            }),
+            _ => Err(Error::BadResponse("version too short")),
+        }
+    }
+
+    // The public key and address for the path, without confirmation on the device
+    pub fn get_pubkey(&mut self, path: &[u32]) -> Result<PublicKey, Error> {
+        self.get_address(Ins::GetPubkey, path)
+    }
+
+    // The public key and address for the path, once the user has confirmed
+    // the address on the device
+    pub fn verify_address(&mut self, path: &[u32]) -> Result<PublicKey, Error> {
+        self.get_address(Ins::VerifyAddress, path)
+    }
+
+    fn get_address(&mut self, ins: Ins, path: &[u32]) -> Result<PublicKey, Error> {
+        let rv = self.send(ins, &[&pack_bip32_path(path)])?;
+        let (public_key, rest) = pop_size_prefixed(&rv)?;
+        let (address, _) = pop_size_prefixed(rest)?;
+        Ok(PublicKey {
+            public_key: public_key.to_vec(),
+            address: address.to_vec(),
+        })
+    }
+
+    // Signs a transaction with the key of the path, and returns the signature
+    // The objects are the coins used by the transaction, needed by the app to
+    // show the amounts of tokens other than SUI.
+    pub fn sign_tx(
+        &mut self,
+        path: &[u32],
+        tx: &[u8],
+        objects: &[&[u8]],
+    ) -> Result<Vec<u8>, Error> {
+        let tx_param = pack_transaction(tx);
+        let path_param = pack_bip32_path(path);
+        if objects.is_empty() {
+            self.send(Ins::Sign, &[&tx_param, &path_param])
+        } else {
+            let objects_param = pack_object_list(objects);
+            self.send(Ins::Sign, &[&tx_param, &path_param, &objects_param])
+        }
+    }
+
+    // Loads the PKI certificate checking the signature of dynamic descriptors
+    pub fn send_pki_certificate(&mut self, key_usage: u8, certificate: &[u8]) -> Result<(), Error> {
+        let (_, sw) = self.transport.exchange(
+            PKI_CLA,
+            PKI_INS_LOAD_CERTIFICATE,
+            key_usage,
+            0x00,
+            certificate,
+        )?;
+        if sw != SW_OK {
+            return Err(Error::Status(sw));
+        }
+        Ok(())
+    }
+
+    // Provides the signed TLV descriptor of a token, so that its ticker and
+    // decimals are shown in the reviews of the following transactions
+    pub fn provide_dynamic_descriptor(&mut self, descriptor: &[u8]) -> Result<(), Error> {
+        let length = u16::try_from(descriptor.len())
+            .map_err(|_| Error::TooLong("descriptor", descriptor.len()))?;
+        let mut param = Vec::with_capacity(2 + descriptor.len());
+        param.extend_from_slice(&length.to_le_bytes());
+        param.extend_from_slice(descriptor);
+        self.send(Ins::ProvideTrustedDynamicDescriptor, &[&param])?;
+        Ok(())
+    }
+
+    // Provides the signed TLV descriptor of a Move call, so that the next
+    // transaction to sign, if it makes this call, is reviewed with its labels
+    pub fn provide_move_call_descriptor(&mut self, descriptor: &[u8]) -> Result<(), Error> {
+        let length = u16::try_from(descriptor.len())
+            .map_err(|_| Error::TooLong("descriptor", descriptor.len()))?;
+        let mut param = Vec::with_capacity(2 + descriptor.len());
+        param.extend_from_slice(&length.to_le_bytes());
+        param.extend_from_slice(descriptor);
+        self.send(Ins::ProvideMoveCallDescriptor, &[&param])?;
+        Ok(())
+    }
+
+    // Sets the amount, in whole coins, above which a transfer needs an extra
+    // confirmation, or turns it off with None. The user confirms on the device.
+    pub fn set_high_value_threshold(&mut self, threshold: Option<u32>) -> Result<(), Error> {
+        let param = threshold.unwrap_or(0).to_le_bytes();
+        self.send(Ins::SetHighValueThreshold, &[&param])?;
+        Ok(())
+    }
+
+    // Adds a recipient to the allowlist of the device, or changes its label.
+    // The user confirms on the device.
+    pub fn add_allowlist_entry(&mut self, address: &[u8; 32], label: &str) -> Result<(), Error> {
+        let length = u8::try_from(label.len()).map_err(|_| Error::TooLong("label", label.len()))?;
+        let mut param = Vec::with_capacity(33 + label.len());
+        param.extend_from_slice(address);
+        param.push(length);
+        param.extend_from_slice(label.as_bytes());
+        self.send(Ins::AddAllowlistEntry, &[&param])?;
+        Ok(())
+    }
+
+    pub fn remove_allowlist_entry(&mut self, address: &[u8; 32]) -> Result<(), Error> {
+        self.send(Ins::RemoveAllowlistEntry, &[address])?;
+        Ok(())
+    }
+
+    // When on, transfers to recipients not in the allowlist are refused, as well as blind
+    // signing and described Move calls
+    pub fn set_allowlist_only(&mut self, allowlist_only: bool) -> Result<(), Error> {
+        self.send(Ins::SetAllowlistOnly, &[&[allowlist_only as u8]])?;
+        Ok(())
+    }
+}
+
+fn pop_size_prefixed(buf: &[u8]) -> Result<(&[u8], &[u8]), Error> {
+    let (&len, rest) = buf
+        .split_first()
+        .ok_or(Error::BadResponse("missing length"))?;
+    if rest.len() < len as usize {
+        return Err(Error::BadResponse("truncated field"));
+    }
+    Ok(rest.split_at(len as usize))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn bip32_path() {
+        let path = parse_bip32_path("m/44'/784'/0'/0'/1").unwrap();
+        assert_eq!(
+            path,
+            [0x8000_002c, 0x8000_0310, 0x8000_0000, 0x8000_0000, 1]
+        );
+        assert_eq!(
+            pack_bip32_path(&path[..2]),
+            [2, 0x2c, 0, 0, 0x80, 0x10, 0x03, 0, 0x80]
+        );
+        assert!(parse_bip32_path("44'/784'").is_err());
+        assert!(parse_bip32_path("m/44'//0").is_err());
+        assert!(parse_bip32_path("m/2147483648").is_err());
+    }
+
+    #[test]
+    fn object_list() {
+        assert_eq!(
+            pack_object_list(&[&[1, 2], &[3]]),
+            [2, 0, 0, 0, 2, 0, 0, 0, 1, 2, 1, 0, 0, 0, 3]
+        );
+    }
+}
@@ -0,0 +1,92 @@
+// Transports carrying the APDUs to the device
+use crate::Error;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+
+// Status word of a successful APDU
+pub const SW_OK: u16 = 0x9000;
+
+// Largest payload of a short APDU
+pub const MAX_APDU_DATA: usize = 255;
+
+// Sends one APDU, and returns the response data and status word
+pub trait Transport {
+    fn exchange(
+        &mut self,
+        cla: u8,
+        ins: u8,
+        p1: u8,
+        p2: u8,
+        data: &[u8],
+    ) -> Result<(Vec<u8>, u16), Error>;
+}
+
+impl<T: Transport + ?Sized> Transport for &mut T {
+    fn exchange(
+        &mut self,
+        cla: u8,
+        ins: u8,
+        p1: u8,
+        p2: u8,
+        data: &[u8],
+    ) -> Result<(Vec<u8>, u16), Error> {
+        (**self).exchange(cla, ins, p1, p2, data)
+    }
+}
+
+// Encodes a short APDU: header, data length and data
+pub fn encode_apdu(cla: u8, ins: u8, p1: u8, p2: u8, data: &[u8]) -> Result<Vec<u8>, Error> {
+    if data.len() > MAX_APDU_DATA {
+        return Err(Error::TooLong("APDU data", data.len()));
+    }
+    let mut apdu = Vec::with_capacity(5 + data.len());
+    apdu.extend_from_slice(&[cla, ins, p1, p2, data.len() as u8]);
+    apdu.extend_from_slice(data);
+    Ok(apdu)
+}
+
+// The APDU socket of speculos (--apdu-port, 9999 by default)
+// Each APDU is sent prefixed by its length (u32 BE), and each response is
+// its data length (u32 BE), the data and the status word.
+pub struct SpeculosTransport {
+    stream: TcpStream,
+}
+
+pub const SPECULOS_APDU_PORT: u16 = 9999;
+
+impl SpeculosTransport {
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> Result<Self, Error> {
+        let stream = TcpStream::connect(addr)?;
+        stream.set_nodelay(true)?;
+        Ok(SpeculosTransport { stream })
+    }
+
+    pub fn connect_default() -> Result<Self, Error> {
+        Self::connect(("127.0.0.1", SPECULOS_APDU_PORT))
+    }
+}
+
+impl Transport for SpeculosTransport {
+    fn exchange(
+        &mut self,
+        cla: u8,
+        ins: u8,
+        p1: u8,
+        p2: u8,
+        data: &[u8],
+    ) -> Result<(Vec<u8>, u16), Error> {
+        let apdu = encode_apdu(cla, ins, p1, p2, data)?;
+        let mut packet = Vec::with_capacity(4 + apdu.len());
+        packet.extend_from_slice(&(apdu.len() as u32).to_be_bytes());
+        packet.extend_from_slice(&apdu);
+        self.stream.write_all(&packet)?;
+
+        let mut length = [0; 4];
+        self.stream.read_exact(&mut length)?;
+        let mut response = vec![0; u32::from_be_bytes(length) as usize];
+        self.stream.read_exact(&mut response)?;
+        let mut sw = [0; 2];
+        self.stream.read_exact(&mut sw)?;
+        Ok((response, u16::from_be_bytes(sw)))
+    }
+}
@@ -32,8 +32,25 @@ class DynamicTokenTag(IntEnum):
     TUID = 0x07
     SIGNATURE = 0x08
 
+class MoveCallDescriptorTag(IntEnum):
+    STRUCTURE_TYPE = 0x01
+    VERSION = 0x02
+    PACKAGE_ADDRESS = 0x10
+    MODULE_NAME = 0x11
+    FUNCTION_NAME = 0x12
+    LABEL = 0x13
+    ARG = 0x14
+    SIGNATURE = 0x15
+
+class MoveCallArgFormat(IntEnum):
+    AMOUNT = 0
+    ADDRESS = 1
+    OBJECT = 2
+    PERCENTAGE = 3
+
 class CertificatePubKeyUsage(IntEnum):
     CERTIFICATE_PUBLIC_KEY_USAGE_COIN_META            = 0x08,
+    CERTIFICATE_PUBLIC_KEY_USAGE_CALLDATA             = 0x0b,
 
 MAX_APDU_LEN: int = 255
 
@@ -48,6 +65,11 @@ class InsType(IntEnum):
     GET_PUBLIC_KEY                      = 0x02
     SIGN_TX                             = 0x03
     PROVIDE_TRUSTED_DYNAMIC_DESCRIPTOR  = 0x22
+    PROVIDE_MOVE_CALL_DESCRIPTOR        = 0x23
+    SET_HIGH_VALUE_THRESHOLD            = 0x24
+    ADD_ALLOWLIST_ENTRY                 = 0x25
+    REMOVE_ALLOWLIST_ENTRY              = 0x26
+    SET_ALLOWLIST_ONLY                  = 0x27
 
 class Errors(IntEnum):
     SW_DENY                    = 0x6985
@@ -66,6 +88,7 @@ class Errors(IntEnum):
     SW_SIGNATURE_FAIL          = 0xB008
 
 DYNAMIC_TOKEN = 0x90
+MOVE_CALL_DESCRIPTOR = 0x91
 
 def split_message(message: bytes, max_size: int) -> List[bytes]:
     return [message[x:x + max_size] for x in range(0, len(message), max_size)]
@@ -157,7 +180,16 @@ class Client:
         p_len = (len(payload)).to_bytes(2, byteorder='little')
         payload = p_len + payload
 
-        # send PKI certificate
+        self.send_coin_meta_certificate()
+
+        self.send_fn(cla=CLA,
+                    ins=InsType.PROVIDE_TRUSTED_DYNAMIC_DESCRIPTOR,
+                    p1=P1,
+                    p2=P2,
+                    payload=payload)
+
+    # Loads the PKI certificate of the key signing the token dynamic descriptors
+    def send_coin_meta_certificate(self):
         # pylint: disable=line-too-long
         if self.backend.device.name == "nanosp":
             cert_apdu = "01010102010211040000000212010013020002140101160400000000200D44796E616D69635F546F6B656E3002000E310108320121332102E3C05B637A0626AB382004A9350BEB1DE47958A3B9FC1EFC6E16A72104C05FE73401013501031546304402207756CFA4C31D0732F45FE12AD824262C1359BD49A89EE847A5322D99023E1D2802204D6B36F57BE3DD3D0FF2BBD5ECC71FAAE5D52899742673200D9F8236A58913E3"  # noqa: E501
@@ -174,11 +206,66 @@ class Client:
         self._pki_client.send_certificate(CertificatePubKeyUsage.CERTIFICATE_PUBLIC_KEY_USAGE_COIN_META,
                                           bytes.fromhex(cert_apdu))
 
+    # Sends a Move call descriptor, signed with the given key, whose PKI certificate
+    # must have been loaded beforehand
+    # args are (index of the argument, format, index of the type argument of an amount, label)
+    def provide_move_call_descriptor(self,
+                                     key: Key,
+                                     package: str,
+                                     module: str,
+                                     function: str,
+                                     label: str,
+                                     args: List[Tuple[int, MoveCallArgFormat, int, str]]):
+        payload = format_tlv(MoveCallDescriptorTag.STRUCTURE_TYPE, MOVE_CALL_DESCRIPTOR)
+        payload += format_tlv(MoveCallDescriptorTag.VERSION, 1)
+        payload += format_tlv(MoveCallDescriptorTag.PACKAGE_ADDRESS, bytes.fromhex(package.removeprefix("0x")))
+        payload += format_tlv(MoveCallDescriptorTag.MODULE_NAME, module)
+        payload += format_tlv(MoveCallDescriptorTag.FUNCTION_NAME, function)
+        payload += format_tlv(MoveCallDescriptorTag.LABEL, label)
+        for (arg, arg_format, type_arg, arg_label) in args:
+            payload += format_tlv(MoveCallDescriptorTag.ARG, bytes([arg, arg_format, type_arg]) + arg_label.encode())
+        payload += format_tlv(MoveCallDescriptorTag.SIGNATURE, sign_data(key, payload))
+
+        p_len = (len(payload)).to_bytes(2, byteorder='little')
+        payload = p_len + payload
+
         self.send_fn(cla=CLA,
-                    ins=InsType.PROVIDE_TRUSTED_DYNAMIC_DESCRIPTOR,
+                    ins=InsType.PROVIDE_MOVE_CALL_DESCRIPTOR,
                     p1=P1,
                     p2=P2,
-                    payload=payload)
+                    payload=[payload])
+
+    # 0 turns the high value warning off
+    def set_high_value_threshold(self, threshold: int) -> bytes:
+        return self.send_fn(cla=CLA,
+                            ins=InsType.SET_HIGH_VALUE_THRESHOLD,
+                            p1=P1,
+                            p2=P2,
+                            payload=[threshold.to_bytes(4, byteorder='little')])
+
+    # Adds a recipient to the allowlist, or changes its label
+    def add_allowlist_entry(self, address: str, label: str) -> bytes:
+        label_bytes = label.encode()
+        payload = bytes.fromhex(address.removeprefix("0x")) + len(label_bytes).to_bytes(1, byteorder='little') + label_bytes
+        return self.send_fn(cla=CLA,
+                            ins=InsType.ADD_ALLOWLIST_ENTRY,
+                            p1=P1,
+                            p2=P2,
+                            payload=[payload])
+
+    def remove_allowlist_entry(self, address: str) -> bytes:
+        return self.send_fn(cla=CLA,
+                            ins=InsType.REMOVE_ALLOWLIST_ENTRY,
+                            p1=P1,
+                            p2=P2,
+                            payload=[bytes.fromhex(address.removeprefix("0x"))])
+
+    def set_allowlist_only(self, allowlist_only: bool) -> bytes:
+        return self.send_fn(cla=CLA,
+                            ins=InsType.SET_ALLOWLIST_ONLY,
+                            p1=P1,
+                            p2=P2,
+                            payload=[bytes([int(allowlist_only)])])
 
     def sign_tx(self, path: str, transaction: bytes, object_list: Optional[list[bytes]] = None) -> bytes:
         if object_list is None:
@@ -0,0 +1,137 @@
+# Covers the allowlist of recipients, and its allowlist only mode
+
+import pytest
+
+from application_client.client import Client
+from contextlib import contextmanager
+from ragger.error import ExceptionRAPDU
+from ragger.navigator import NavInsID
+from utils import check_signature_validity, run_apdu_and_nav_tasks_concurrently
+
+# The simple transfer of test_sign_tx_sui_transfer, and its recipient
+TRANSFER = bytes.fromhex('000000000002000840420f000000000000204f2370b2a4810ad6c8e1cfd92cc8c8818fef8f59e3a80cea17871f78d850ba4b0202000101000001010200000101006fb21feead027da4873295affd6c4f3618fe176fa2fbf3e7b5ef1d9463b31e210112a6d0c44edc630d2724b1f57fea4f93308b1d22164402c65778bd99379c4733070000000000000020f2fd3c87b227f1015182fe4348ed680d7ed32bcd3269704252c03e1d0b13d30d6fb21feead027da4873295affd6c4f3618fe176fa2fbf3e7b5ef1d9463b31e2101000000000000000c0400000000000000')
+RECIPIENT = "0x4f2370b2a4810ad6c8e1cfd92cc8c8818fef8f59e3a80cea17871f78d850ba4b"
+
+SW_RECIPIENT_NOT_ALLOWLISTED = 0x6E06
+
+# can sign a transfer to an allowlisted recipient, whose label is shown after its address
+def test_sign_tx_sui_transfer_allowlisted(backend, scenario_navigator, firmware, navigator):
+    client = Client(backend, use_block_protocol=True)
+    path = "m/44'/784'/0'"
+
+    _, public_key, _, _ = client.get_public_key(path=path)
+    assert len(public_key) == 32
+
+    def apdu_task():
+        return client.sign_tx(path=path, transaction=TRANSFER)
+
+    def nav_task():
+        if firmware.device.startswith("nano"):
+            navigator.navigate_and_compare(
+                instructions=[ NavInsID.RIGHT_CLICK # Transfer SUI
+                               , NavInsID.RIGHT_CLICK, NavInsID.RIGHT_CLICK # From ...
+                               , NavInsID.RIGHT_CLICK, NavInsID.RIGHT_CLICK # To ...
+                               , NavInsID.RIGHT_CLICK # Allowlisted as
+                               , NavInsID.RIGHT_CLICK # Amount
+                               , NavInsID.RIGHT_CLICK # Max Gas
+                               , NavInsID.BOTH_CLICK
+                              ]
+                , timeout=10
+                , test_case_name=scenario_navigator.test_name
+                , path=scenario_navigator.screenshot_path
+                , screen_change_before_first_instruction=True
+                , screen_change_after_last_instruction=False
+            )
+        else:
+            scenario_navigator.review_approve()
+
+    def check_result(result):
+        assert len(result) == 64
+        assert check_signature_validity(public_key, result, TRANSFER)
+
+    with allowlist_entry(client, firmware, navigator, scenario_navigator, RECIPIENT, "Friend"):
+        run_apdu_and_nav_tasks_concurrently(apdu_task, nav_task, check_result)
+
+# should refuse a transfer to a recipient not in the allowlist, in allowlist only mode
+def test_sign_tx_sui_transfer_not_allowlisted_refused(backend, scenario_navigator, firmware, navigator):
+    client = Client(backend, use_block_protocol=True)
+    path = "m/44'/784'/0'"
+
+    with allowlist_only(client, firmware, navigator):
+        # Refused before its review
+        with pytest.raises(ExceptionRAPDU) as e:
+            client.sign_tx(path=path, transaction=TRANSFER)
+        assert e.value.status == SW_RECIPIENT_NOT_ALLOWLISTED
+
+# Adds the recipient for the test, and removes it afterwards, comparing both confirmations
+@contextmanager
+def allowlist_entry(client, firmware, navigator, scenario_navigator, address, label):
+    confirm_allowlist_entry(lambda: client.add_allowlist_entry(address, label),
+                            firmware, navigator, scenario_navigator, "_add")
+    try:
+        yield
+    except:
+        raise
+    else:
+        confirm_allowlist_entry(lambda: client.remove_allowlist_entry(address),
+                                firmware, navigator, scenario_navigator, "_remove")
+
+def confirm_allowlist_entry(apdu_task, firmware, navigator, scenario_navigator, suffix):
+    def nav_task():
+        if firmware.device.startswith("nano"):
+            navigator.navigate_and_compare(
+                instructions=[ NavInsID.RIGHT_CLICK # Add or Remove recipient
+                               , NavInsID.RIGHT_CLICK, NavInsID.RIGHT_CLICK # Address ...
+                               , NavInsID.RIGHT_CLICK # Label
+                               , NavInsID.BOTH_CLICK
+                              ]
+                , timeout=10
+                , test_case_name=scenario_navigator.test_name + suffix
+                , path=scenario_navigator.screenshot_path
+                , screen_change_before_first_instruction=True
+                , screen_change_after_last_instruction=False
+            )
+        else:
+            scenario_navigator.review_approve(test_name=scenario_navigator.test_name + suffix)
+
+    def check_result(result):
+        assert len(result) == 0
+
+    run_apdu_and_nav_tasks_concurrently(apdu_task, nav_task, check_result)
+
+# Turns the allowlist only mode on for the test, and off afterwards
+@contextmanager
+def allowlist_only(client, firmware, navigator):
+    set_allowlist_only(client, firmware, navigator, True)
+    try:
+        yield
+    except:
+        raise
+    else:
+        set_allowlist_only(client, firmware, navigator, False)
+
+def set_allowlist_only(client, firmware, navigator, allowlist_only):
+    def apdu_task():
+        return client.set_allowlist_only(allowlist_only)
+
+    def nav_task():
+        # "Change allowlist mode" choice
+        if firmware.device.startswith("nano"):
+            navigator.navigate_until_text(
+                navigate_instruction=NavInsID.RIGHT_CLICK
+                , validation_instructions=[NavInsID.BOTH_CLICK]
+                , text="^Confirm"
+                , timeout=10
+                , screen_change_before_first_instruction=True
+                , screen_change_after_last_instruction=False
+            )
+        else:
+            navigator.navigate([NavInsID.USE_CASE_CHOICE_CONFIRM],
+                               timeout=10,
+                               screen_change_before_first_instruction=True,
+                               screen_change_after_last_instruction=False)
+
+    def check_result(result):
+        assert len(result) == 0
+
+    run_apdu_and_nav_tasks_concurrently(apdu_task, nav_task, check_result)
@@ -271,6 +271,43 @@ def test_sign_tx_blind_sign_big_transfer_tx(backend, scenario_navigator, firmwar
 
     with blind_sign_enabled(firmware, navigator):
         run_apdu_and_nav_tasks_concurrently(apdu_task, nav_task, check_result)
+
+# shows the gas data, input objects and digest of a transaction in expert mode
+def test_sign_tx_sui_transfer_expert(backend, scenario_navigator, firmware, navigator):
+    client = Client(backend, use_block_protocol=True)
+    path = "m/44'/784'/0'"
+
+    _, public_key, _, _ = client.get_public_key(path=path)
+    assert len(public_key) == 32
+
+    transaction = bytes.fromhex('000000000002000840420f000000000000204f2370b2a4810ad6c8e1cfd92cc8c8818fef8f59e3a80cea17871f78d850ba4b0202000101000001010200000101006fb21feead027da4873295affd6c4f3618fe176fa2fbf3e7b5ef1d9463b31e210112a6d0c44edc630d2724b1f57fea4f93308b1d22164402c65778bd99379c4733070000000000000020f2fd3c87b227f1015182fe4348ed680d7ed32bcd3269704252c03e1d0b13d30d6fb21feead027da4873295affd6c4f3618fe176fa2fbf3e7b5ef1d9463b31e2101000000000000000c0400000000000000')
+
+    def apdu_task():
+        return client.sign_tx(path=path, transaction=transaction)
+
+    def nav_task():
+        if firmware.device.startswith("nano"):
+            # The number of pages of the signing digest depends on the device
+            navigator.navigate_until_text_and_compare(
+                navigate_instruction=NavInsID.RIGHT_CLICK
+                , validation_instructions=[NavInsID.BOTH_CLICK]
+                , text="^Sign transaction"
+                , timeout=10
+                , path=scenario_navigator.screenshot_path
+                , test_case_name=scenario_navigator.test_name
+                , screen_change_before_first_instruction=True
+                , screen_change_after_last_instruction=False
+            )
+        else:
+            scenario_navigator.review_approve()
+
+    def check_result(result):
+        assert len(result) == 64
+        assert check_signature_validity(public_key, result, transaction)
+
+    with expert_mode_enabled(firmware, navigator):
+        run_apdu_and_nav_tasks_concurrently(apdu_task, nav_task, check_result)
+
 @contextmanager
 def blind_sign_enabled(firmware, navigator):
     toggle_blind_sign(firmware, navigator)
@@ -282,17 +319,44 @@ def blind_sign_enabled(firmware, navigator):
     else:
         toggle_blind_sign(firmware, navigator)
 
-def toggle_blind_sign(firmware, navigator):
+@contextmanager
+def expert_mode_enabled(firmware, navigator):
+    toggle_expert_mode(firmware, navigator)
+    try:
+        yield
+    except:
+        raise
+    else:
+        toggle_expert_mode(firmware, navigator)
+
+# Blind Signing, Expert mode and High value warning
+SETTINGS_SWITCH_COUNT = 3
+
+# Position of the first two switches of the settings page, on touch devices
+SETTINGS_SWITCH_POSITIONS = [(200, 113), (200, 350)]
+
+def toggle_setting(firmware, navigator, index):
     if firmware.device.startswith("nano"):
+        # Each switch is on its own page, followed by the "Back" page
         navigator.navigate(
-            instructions=[NavInsID.RIGHT_CLICK, NavInsID.BOTH_CLICK, NavInsID.BOTH_CLICK, NavInsID.RIGHT_CLICK, NavInsID.BOTH_CLICK, NavInsID.LEFT_CLICK]
+            instructions=[NavInsID.RIGHT_CLICK, NavInsID.BOTH_CLICK]
+                + [NavInsID.RIGHT_CLICK] * index
+                + [NavInsID.BOTH_CLICK]
+                + [NavInsID.RIGHT_CLICK] * (SETTINGS_SWITCH_COUNT - index)
+                + [NavInsID.BOTH_CLICK, NavInsID.LEFT_CLICK]
             , timeout=10
             , screen_change_before_first_instruction=False
         )
     else:
         navigator.navigate([NavInsID.USE_CASE_HOME_SETTINGS,
-                            NavIns(NavInsID.TOUCH, (200, 113)),
+                            NavIns(NavInsID.TOUCH, SETTINGS_SWITCH_POSITIONS[index]),
                             NavInsID.USE_CASE_SUB_SETTINGS_EXIT],
                             timeout=10,
                             screen_change_before_first_instruction=False,
                             screen_change_after_last_instruction=False)
+
+def toggle_blind_sign(firmware, navigator):
+    toggle_setting(firmware, navigator, 0)
+
+def toggle_expert_mode(firmware, navigator):
+    toggle_setting(firmware, navigator, 1)
@@ -0,0 +1,110 @@
+# Covers the high value warning of transfers
+
+import pytest
+
+from application_client.client import Client
+from contextlib import contextmanager
+from ragger.navigator import NavInsID
+from utils import check_signature_validity, run_apdu_and_nav_tasks_concurrently
+
+# can sign a transfer of 2 SUI above a threshold of 1 SUI, after its warning and an extra confirmation
+def test_sign_tx_sui_high_value_transfer(backend, scenario_navigator, firmware, navigator):
+    client = Client(backend, use_block_protocol=True)
+    path = "m/44'/784'/0'"
+
+    _, public_key, _, _ = client.get_public_key(path=path)
+    assert len(public_key) == 32
+
+    # The simple transfer of test_sign_tx_sui_transfer, of 2 SUI
+    transaction = bytes.fromhex('0000000000020008009435770000000000204f2370b2a4810ad6c8e1cfd92cc8c8818fef8f59e3a80cea17871f78d850ba4b0202000101000001010200000101006fb21feead027da4873295affd6c4f3618fe176fa2fbf3e7b5ef1d9463b31e210112a6d0c44edc630d2724b1f57fea4f93308b1d22164402c65778bd99379c4733070000000000000020f2fd3c87b227f1015182fe4348ed680d7ed32bcd3269704252c03e1d0b13d30d6fb21feead027da4873295affd6c4f3618fe176fa2fbf3e7b5ef1d9463b31e2101000000000000000c0400000000000000')
+
+    def apdu_task():
+        return client.sign_tx(path=path, transaction=transaction)
+
+    def nav_task():
+        test_name = scenario_navigator.test_name
+        screenshot_path = scenario_navigator.screenshot_path
+        if firmware.device.startswith("nano"):
+            # "High value transfer" warning, review, then "Confirm high value transfer"
+            for (text, suffix) in [("^Continue", "_warning"), ("^Sign transaction", "_review"), ("^Hold to confirm", "_confirm")]:
+                navigator.navigate_until_text_and_compare(
+                    navigate_instruction=NavInsID.RIGHT_CLICK
+                    , validation_instructions=[NavInsID.BOTH_CLICK]
+                    , text=text
+                    , timeout=10
+                    , path=screenshot_path
+                    , test_case_name=test_name + suffix
+                    , screen_change_before_first_instruction=True
+                    , screen_change_after_last_instruction=False
+                )
+        else:
+            navigator.navigate_and_compare(
+                instructions=[NavInsID.USE_CASE_CHOICE_CONFIRM]
+                , timeout=10
+                , path=screenshot_path
+                , test_case_name=test_name + "_warning"
+                , screen_change_before_first_instruction=True
+                , screen_change_after_last_instruction=False
+            )
+            navigator.navigate_until_text_and_compare(
+                navigate_instruction=NavInsID.SWIPE_CENTER_TO_LEFT
+                , validation_instructions=[NavInsID.USE_CASE_REVIEW_CONFIRM]
+                , text="^Hold to sign"
+                , timeout=10
+                , path=screenshot_path
+                , test_case_name=test_name + "_review"
+                , screen_change_before_first_instruction=True
+                , screen_change_after_last_instruction=False
+            )
+            navigator.navigate_and_compare(
+                instructions=[NavInsID.USE_CASE_REVIEW_CONFIRM, NavInsID.USE_CASE_STATUS_DISMISS]
+                , timeout=10
+                , path=screenshot_path
+                , test_case_name=test_name + "_confirm"
+                , screen_change_before_first_instruction=True
+                , screen_change_after_last_instruction=False
+            )
+
+    def check_result(result):
+        assert len(result) == 64
+        assert check_signature_validity(public_key, result, transaction)
+
+    with high_value_threshold(client, firmware, navigator, 1):
+        run_apdu_and_nav_tasks_concurrently(apdu_task, nav_task, check_result)
+
+# Sets the threshold for the test, and turns the warning off afterwards
+@contextmanager
+def high_value_threshold(client, firmware, navigator, threshold):
+    set_high_value_threshold(client, firmware, navigator, threshold)
+    try:
+        yield
+    except:
+        raise
+    else:
+        set_high_value_threshold(client, firmware, navigator, 0)
+
+def set_high_value_threshold(client, firmware, navigator, threshold):
+    def apdu_task():
+        return client.set_high_value_threshold(threshold)
+
+    def nav_task():
+        # "Change high value threshold" choice
+        if firmware.device.startswith("nano"):
+            navigator.navigate_until_text(
+                navigate_instruction=NavInsID.RIGHT_CLICK
+                , validation_instructions=[NavInsID.BOTH_CLICK]
+                , text="^Confirm"
+                , timeout=10
+                , screen_change_before_first_instruction=True
+                , screen_change_after_last_instruction=False
+            )
+        else:
+            navigator.navigate([NavInsID.USE_CASE_CHOICE_CONFIRM],
+                               timeout=10,
+                               screen_change_before_first_instruction=True,
+                               screen_change_after_last_instruction=False)
+
+    def check_result(result):
+        assert len(result) == 0
+
+    run_apdu_and_nav_tasks_concurrently(apdu_task, nav_task, check_result)
@@ -0,0 +1,64 @@
+# Covers Move call descriptors, which let a call of a dApp be clear signed
+
+import pytest
+
+from application_client.client import Client, MoveCallArgFormat
+from application_client.sui_keychain import Key
+from ragger.error import ExceptionRAPDU
+from ragger.navigator import NavInsID
+from utils import run_apdu_and_nav_tasks_concurrently
+
+# A swap on a pool of the package 0xdee9, as in the parser tests of rust-app/src/parser/tx.rs
+DEX_SWAP = bytes.fromhex('00000000000401017f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7fe80300000000000001000880841e00000000000008320000000000000000201d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be4880200dee900000000000000000000000000000000000000000000000000000000000004706f6f6c04737761700207dba34672e30cb065b1f93e3ab55318768fd6fef66c15942c9f7cb846e2f900e70475736463045553444300070000000000000000000000000000000000000000000000000000000000000002037375690353554900030100000101000102000102030000000003000001000103001d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be488011f876ff0144386dcf4e886c5de53b326c718cc1221e1ccea71ef8aa6231a40ead3764211000000002021c01bdc29b088d3b0151eab0176ff9501e04ab49e00124a5b6fae1a05f351ba1d3f2643305760226e518c9b5a96165383808dd977971f73dea971543b0be488e80300000000000080841e000000000000')
+
+DEX_SWAP_DESCRIPTOR = dict(
+    package="0xdee9000000000000000000000000000000000000000000000000000000000000",
+    module="pool",
+    function="swap",
+    label="swap coins",
+    args=[
+        (0, MoveCallArgFormat.OBJECT, 0, "Pool"),
+        (1, MoveCallArgFormat.AMOUNT, 0, "Amount in"),
+        (2, MoveCallArgFormat.PERCENTAGE, 0, "Max slippage"),
+    ],
+)
+
+# 0x7100 plus DescriptorError::WrongSignature
+SW_DESCRIPTOR_WRONG_SIGNATURE = 0x7109
+
+# should refuse a descriptor signed by a key whose certificate is not of usage CALLDATA,
+# so that the call is then rejected as unknown, if blind signing is not enabled
+def test_sign_described_move_call_wrong_certificate(backend, scenario_navigator, firmware, navigator):
+    client = Client(backend, use_block_protocol=True)
+    path = "m/44'/784'/0'"
+
+    # The certificate of the token dynamic descriptor key is of usage COIN_META
+    client.send_coin_meta_certificate()
+    with pytest.raises(ExceptionRAPDU) as e:
+        client.provide_move_call_descriptor(Key.DYNAMIC_TOKEN, **DEX_SWAP_DESCRIPTOR)
+    assert e.value.status == SW_DESCRIPTOR_WRONG_SIGNATURE
+
+    def apdu_task():
+        return client.sign_tx(path=path, transaction=DEX_SWAP)
+
+    def nav_task():
+        if firmware.device.startswith("nano"):
+            navigator.navigate_and_compare(
+                instructions=[NavInsID.RIGHT_CLICK, NavInsID.RIGHT_CLICK, NavInsID.RIGHT_CLICK, NavInsID.BOTH_CLICK]
+                , timeout=10
+                , test_case_name="test_sign_described_move_call_wrong_certificate"
+                , path=scenario_navigator.screenshot_path
+                , screen_change_before_first_instruction=True
+                , screen_change_after_last_instruction=False
+            )
+        else:
+            # Dismiss the "Enable Blind signing" screen
+            navigator.navigate([NavInsID.USE_CASE_CHOICE_REJECT],
+                            screen_change_before_first_instruction=False,
+                            screen_change_after_last_instruction=False)
+
+    def check_result(result):
+        pytest.fail('should not happen')
+
+    with pytest.raises(ExceptionRAPDU):
+        run_apdu_and_nav_tasks_concurrently(apdu_task, nav_task, check_result)
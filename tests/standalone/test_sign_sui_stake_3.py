@@ -466,3 +466,47 @@ def test_sign_unstake_staked_coin(backend, scenario_navigator, firmware, navigat
         assert check_signature_validity(public_key, result, transaction)
 
     run_apdu_and_nav_tasks_concurrently(apdu_task, nav_task, check_result)
+
+# Withdraws the stake of test_sign_unstake_staked_coin and stakes the principal again, with the
+# validator 0x35f5f154f0117464e3379c45c7f3cb6b2d4efedf300acadff8a7e8e9e3a15109
+def test_sign_restake_staked_coin(backend, scenario_navigator, firmware, navigator):
+    client = Client(backend, use_block_protocol=True)
+    path = "m/44'/784'/0'/0'/1'"
+
+    _, public_key, _, _ = client.get_public_key(path=path)
+    assert len(public_key) == 32
+
+    transaction = base64.b64decode('AAAAAAADAQEAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAABQEAAAAAAAAAAQEA0mPbzJ8T6TAri2KVGNKhLFvnVaugyXhgbMKRNnBZAV33Gw4AAAAAACDBfsqjWZzYr3r/y2WeSuQSi3FgoCPQgT6mxxHSO8kQ5QAgNfXxVPARdGTjN5xFx/PLay1O/t8wCsrf+Kfo6eOhUQkDAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAADCnN1aV9zeXN0ZW0gcmVxdWVzdF93aXRoZHJhd19zdGFrZV9ub25fZW50cnkAAgEAAAEBAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAgRjb2luDGZyb21fYmFsYW5jZQEHAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAIDc3VpA1NVSQABAgAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAADCnN1aV9zeXN0ZW0RcmVxdWVzdF9hZGRfc3Rha2UAAwEAAAIBAAECABJV0JMExcyCS570/ZGW9bjpYWe0+wJm9w3TaCmtUZ1dAUk5VMZDoqqWqj0BzxKCHm02Et5gUaB7hxaD6CpACNOsxOs5AAAAAAAgieAeH7+ma0cU0J3UAfhOPAaRL0L4TIocjjGqTL7c8tISVdCTBMXMgkue9P2RlvW46WFntPsCZvcN02gprVGdXegDAAAAAAAASFimAAAAAAAA')
+
+    object_list = [ base64.b64decode('AAEBxOs5AAAAAAAoSTlUxkOiqpaqPQHPEoIebTYS3mBRoHuHFoPoKkAI06w4de91AAAAAAASVdCTBMXMgkue9P2RlvW46WFntPsCZvcN02gprVGdXSCdZIW6l8e6/2RKKj/87f3lTuqOuLzaxGn2e737cI8V3WATDwAAAAAA')
+      , base64.b64decode('AAIA9xsOAAAAAABQ0mPbzJ8T6TAri2KVGNKhLFvnVaugyXhgbMKRNnBZAV0LvEBHHC4MlHdvB1gaCcmbDBkRKg+8at3mmywuqL6PTwkAAAAAAAAAAF7QsgAAAAAAElXQkwTFzIJLnvT9kZb1uOlhZ7T7Amb3DdNoKa1RnV0gyhECC0cu3eeOqn5ga2K/jYGQXZsSfeBpV/9v3ftQBQTgthMAAAAAAA==')
+       ]
+
+    def apdu_task():
+        return client.sign_tx(path=path, transaction=transaction, object_list=object_list)
+
+    def nav_task():
+        if firmware.device.startswith("nano"):
+            navigator.navigate_and_compare(
+                instructions=[ NavInsID.RIGHT_CLICK # Review transaction to restake SUI
+                               , NavInsID.RIGHT_CLICK, NavInsID.RIGHT_CLICK # From ...
+                               , NavInsID.RIGHT_CLICK, NavInsID.RIGHT_CLICK # Source stake ...
+                               , NavInsID.RIGHT_CLICK # Principal (excl. rewards)
+                               , NavInsID.RIGHT_CLICK, NavInsID.RIGHT_CLICK # Validator ...
+                               , NavInsID.RIGHT_CLICK # Max Gas
+                               , NavInsID.BOTH_CLICK
+                              ]
+                , timeout=10
+                , test_case_name=scenario_navigator.test_name
+                , path=scenario_navigator.screenshot_path
+                , screen_change_before_first_instruction=True
+                , screen_change_after_last_instruction=False
+            )
+        else:
+            scenario_navigator.review_approve()
+
+    def check_result(result):
+        assert len(result) == 64
+        assert check_signature_validity(public_key, result, transaction)
+
+    run_apdu_and_nav_tasks_concurrently(apdu_task, nav_task, check_result)